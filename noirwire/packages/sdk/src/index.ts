@@ -632,6 +632,8 @@ export class NoirWireClient {
       nullifier,
       oldRoot,
       newRoot,
+      relayer: 0n, // Self-submitted: no relayer fee
+      fee: 0n,
 
       // Private inputs
       owner: commitment.owner,
@@ -657,6 +659,8 @@ export class NoirWireClient {
       nullifier: bigintToBytes32(nullifier),
       oldRoot: bigintToBytes32(oldRoot),
       newRoot: bigintToBytes32(newRoot),
+      relayer: bigintToBytes32(0n),
+      fee: bigintToBytes32(0n),
     };
 
     // Execute withdraw transaction on Solana
@@ -55,6 +55,8 @@ export interface WithdrawWitness {
   nullifier: bigint;
   oldRoot: bigint;
   newRoot: bigint;
+  relayer: bigint; // 0 if self-submitted
+  fee: bigint; // Paid to relayer out of amount
 
   // Private inputs
   owner: bigint;
@@ -78,6 +80,8 @@ export interface TransferWitness {
   nullifier: bigint;
   oldRoot: bigint;
   newRoot: bigint;
+  newSenderCommitment: bigint; // 0 when the sender's balance is fully spent
+  receiverCommitment: bigint;
 
   // Sender private inputs
   senderOwner: bigint;
@@ -216,6 +220,8 @@ export class ProofGenerator {
       nullifier: witness.nullifier.toString(),
       old_root: witness.oldRoot.toString(),
       new_root: witness.newRoot.toString(),
+      relayer: witness.relayer.toString(),
+      fee: witness.fee.toString(),
       private_inputs: {
         owner: witness.owner.toString(),
         balance: witness.balance.toString(),
@@ -264,6 +270,8 @@ export class ProofGenerator {
       nullifier: witness.nullifier.toString(),
       old_root: witness.oldRoot.toString(),
       new_root: witness.newRoot.toString(),
+      new_sender_commitment: witness.newSenderCommitment.toString(),
+      receiver_commitment: witness.receiverCommitment.toString(),
       private_inputs: {
         sender_owner: witness.senderOwner.toString(),
         sender_amount: witness.senderAmount.toString(),
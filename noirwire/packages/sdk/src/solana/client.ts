@@ -63,6 +63,8 @@ export interface WithdrawProofData {
   nullifier: Uint8Array; // 32 bytes
   oldRoot: Uint8Array; // 32 bytes
   newRoot: Uint8Array; // 32 bytes
+  relayer: Uint8Array; // 32 bytes (relayer pubkey, zero if self-submitted)
+  fee: Uint8Array; // 32 bytes (relayer fee, paid out of amount)
 }
 
 /**
@@ -217,6 +219,7 @@ export class SolanaClient {
         verifierProgram: PROGRAM_IDS.ZK_VERIFIER,
         depositor,
         historicalRoots, // Optional but recommended
+        tokenMint: this.tokenMint,
         tokenProgram: TOKEN_PROGRAM_ID,
       })
       .instruction();
@@ -239,6 +242,12 @@ export class SolanaClient {
     // Get recipient's token account
     const recipientTokenAccount = await getAssociatedTokenAddress(this.tokenMint, recipient);
 
+    // Relayer's token account receives the fee (only needed when fee > 0)
+    const hasFee = proofData.fee.some((b) => b !== 0);
+    const relayerTokenAccount = hasFee
+      ? await getAssociatedTokenAddress(this.tokenMint, new PublicKey(proofData.relayer))
+      : null;
+
     // Get historical roots PDA (optional, but recommended for production)
     const historicalRoots = this.getHistoricalRootsAddress();
 
@@ -253,6 +262,8 @@ export class SolanaClient {
           nullifier: Array.from(proofData.nullifier),
           oldRoot: Array.from(proofData.oldRoot),
           newRoot: Array.from(proofData.newRoot),
+          relayer: Array.from(proofData.relayer),
+          fee: Array.from(proofData.fee),
         },
         recipient,
       )
@@ -260,12 +271,14 @@ export class SolanaClient {
         pool: poolAddress,
         poolVault,
         recipientTokenAccount,
+        relayerTokenAccount,
         nullifierEntry,
         verificationKey,
         verifierProgram: PROGRAM_IDS.ZK_VERIFIER,
         payer,
         poolAuthority,
         historicalRoots, // Optional but recommended
+        tokenMint: this.tokenMint,
         tokenProgram: TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
       })
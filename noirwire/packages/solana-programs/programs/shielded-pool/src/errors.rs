@@ -64,4 +64,106 @@ pub enum PoolError {
 
     #[msg("Invalid PER authority - must not be zero")]
     InvalidPerAuthority,
+
+    #[msg("Value is not a canonical BN254 field element")]
+    InvalidFieldElement,
+
+    #[msg("Nullifier batch is empty or exceeds the per-instruction limit")]
+    NullifierBatchTooLarge,
+
+    #[msg("All nullifiers of this settled batch have already been recorded")]
+    BatchFullyRecorded,
+
+    #[msg("Batch withdrawals exceed the per-settlement limit")]
+    TooManyBatchWithdrawals,
+
+    #[msg("Batch withdrawals don't match the proof's withdrawals hash")]
+    InvalidWithdrawalsHash,
+
+    #[msg("Relayer fee exceeds the withdrawal amount")]
+    FeeExceedsAmount,
+
+    #[msg("Relayer token account missing or doesn't match proof")]
+    InvalidRelayer,
+
+    #[msg("Accounts don't match the pool's token mode (native SOL vs SPL)")]
+    InvalidPoolMode,
+
+    #[msg("Native SOL pools must use the wrapped SOL mint")]
+    InvalidNativeMint,
+
+    #[msg("Mint has an extension that is unsafe for a shielded pool")]
+    UnsupportedMintExtension,
+
+    #[msg("Deposit amount above maximum threshold")]
+    DepositAboveMaximum,
+
+    #[msg("Deposit limits are below the mint's minimum or inverted")]
+    InvalidDepositLimits,
+
+    #[msg("Amount is not one of the pool's denominations")]
+    InvalidDenomination,
+
+    #[msg("Denominations must be increasing, within deposit limits and at most 8")]
+    InvalidDenominations,
+
+    #[msg("Denominations can only change before the first deposit")]
+    DenominationsLocked,
+
+    #[msg("Config value is out of bounds")]
+    InvalidConfigValue,
+
+    #[msg("Pool has a PoolConfig account that must be provided")]
+    PoolConfigRequired,
+
+    #[msg("No authority change is pending")]
+    NoPendingAuthority,
+
+    #[msg("Authority change delay has not elapsed yet")]
+    AuthorityDelayNotElapsed,
+
+    #[msg("Emergency claims are still timelocked")]
+    EmergencyTimelockActive,
+
+    #[msg("Emergency claim does not open a commitment in the emergency root")]
+    InvalidEmergencyClaim,
+
+    #[msg("Emergency claims were paid out; the pool cannot resume")]
+    EmergencyClaimsMade,
+
+    #[msg("Pool cannot be unpaused while in emergency mode")]
+    EmergencyModeActive,
+
+    #[msg("Admin signers must be 1-10 distinct keys with a reachable threshold")]
+    InvalidAdminSigners,
+
+    #[msg("Not enough admin multisig approvals")]
+    InsufficientApprovals,
+
+    #[msg("Guardians must be at most 4 distinct non-zero keys")]
+    InvalidGuardians,
+
+    #[msg("Previous verification key is still in its grace period")]
+    VkRotationInProgress,
+
+    #[msg("Verification key version must increase")]
+    InvalidVkVersion,
+
+    #[msg("The pool's circuit registry must be provided")]
+    CircuitRegistryRequired,
+
+    #[msg("Emergency claim commitment is too recent to reveal")]
+    EmergencyClaimRevealTooEarly,
+
+    #[msg("Batch nullifier count must be between 1 and 2^MAX_NULLIFIER_TREE_DEPTH")]
+    InvalidNullifierCount,
+
+    #[msg("Nullifiers cannot be cleaned up while the pool is in emergency mode")]
+    CleanupDuringEmergency,
+
+    #[msg("Emergency mode is already active")]
+    EmergencyModeAlreadyActive,
+
+    #[msg("Pool account is not a version-2 PoolState")]
+    UnsupportedPoolVersion,
 }
@@ -1,3 +1,4 @@
+use crate::state::PoolConfigUpdate;
 use anchor_lang::prelude::*;
 
 #[event]
@@ -15,6 +16,18 @@ pub struct WithdrawEvent {
     pub nullifier: [u8; 32],
     pub amount: u64,
     pub recipient: Pubkey,
+    pub relayer: Pubkey,
+    pub fee: u64,
+    pub new_root: [u8; 32],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TransferEvent {
+    pub pool: Pubkey,
+    pub nullifier: [u8; 32],
+    pub new_sender_commitment: [u8; 32],
+    pub receiver_commitment: [u8; 32],
     pub new_root: [u8; 32],
     pub timestamp: i64,
 }
@@ -22,10 +35,22 @@ pub struct WithdrawEvent {
 #[event]
 pub struct BatchSettlementEvent {
     pub pool: Pubkey,
+    pub batch_sequence: u64,
     pub old_root: [u8; 32],
     pub new_root: [u8; 32],
     pub nullifiers_root: [u8; 32],
     pub nullifier_count: u32,
+    pub withdrawal_count: u32,
+    pub withdrawn_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AdminMultisigUpdatedEvent {
+    pub pool: Pubkey,
+    pub admin_multisig: Pubkey,
+    pub signers: Vec<Pubkey>,
+    pub threshold: u8,
     pub timestamp: i64,
 }
 
@@ -33,6 +58,17 @@ pub struct BatchSettlementEvent {
 pub struct EmergencyPauseEvent {
     pub pool: Pubkey,
     pub paused: bool,
+    pub paused_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OutflowLimitExceededEvent {
+    pub pool: Pubkey,
+    pub attempted_amount: u64,
+    pub epoch_outflow: u64,
+    pub epoch_limit: u64,
+    pub epoch_start_slot: u64,
     pub timestamp: i64,
 }
 
@@ -41,13 +77,24 @@ pub struct EmergencyModeEvent {
     pub pool: Pubkey,
     pub emergency_mode: bool,
     pub authority: Pubkey,
+    pub emergency_root: [u8; 32],
+    pub claims_open_slot: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EmergencyClaimCommittedEvent {
+    pub pool: Pubkey,
+    pub claim_hash: [u8; 32],
+    pub reveal_slot: u64,
     pub timestamp: i64,
 }
 
 #[event]
-pub struct EmergencyWithdrawEvent {
+pub struct EmergencyClaimEvent {
     pub pool: Pubkey,
     pub recipient: Pubkey,
+    pub nullifier: [u8; 32],
     pub amount: u64,
     pub timestamp: i64,
 }
@@ -55,12 +102,23 @@ pub struct EmergencyWithdrawEvent {
 #[event]
 pub struct NullifierRecordedEvent {
     pub pool: Pubkey,
+    pub batch_sequence: u64,
     pub nullifier: [u8; 32],
     pub nullifiers_root: [u8; 32],
     pub slot: u64,
     pub timestamp: i64,
 }
 
+#[event]
+pub struct NullifiersBatchRecordedEvent {
+    pub pool: Pubkey,
+    pub batch_sequence: u64,
+    pub nullifiers_root: [u8; 32],
+    pub nullifiers: Vec<[u8; 32]>,
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
 #[event]
 pub struct NullifierCleanupEvent {
     pub pool: Pubkey,
@@ -70,3 +128,50 @@ pub struct NullifierCleanupEvent {
     pub rent_recovered: u64,
     pub timestamp: i64,
 }
+
+#[event]
+pub struct PoolConfigUpdatedEvent {
+    pub pool: Pubkey,
+    pub authority: Pubkey,
+    pub update: PoolConfigUpdate,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityProposedEvent {
+    pub pool: Pubkey,
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+    pub effective_slot: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityTransferredEvent {
+    pub pool: Pubkey,
+    pub previous_authority: Pubkey,
+    pub new_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PerAuthorityRotationEvent {
+    pub pool: Pubkey,
+    pub previous_per_authority: Pubkey,
+    pub new_per_authority: Pubkey,
+    /// Slot at which the rotation takes (or took) effect
+    pub effective_slot: u64,
+    /// False while the rotation is queued behind authority_delay_slots
+    pub applied: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VkStagedEvent {
+    pub pool: Pubkey,
+    pub circuit_id: [u8; 32],
+    pub vk_hash: [u8; 32],
+    pub version: u32,
+    pub activation_slot: u64,
+    pub timestamp: i64,
+}
@@ -0,0 +1,106 @@
+use crate::errors::PoolError;
+use crate::events::AdminMultisigUpdatedEvent;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+/// Create Admin Multisig Context
+///
+/// Creates the pool's M-of-N admin account. Creating it does not hand over
+/// control: the current authority then proposes the multisig as the new
+/// authority and the signers accept it (see authority.rs).
+#[derive(Accounts)]
+pub struct CreateAdminMultisig<'info> {
+    #[account(
+        seeds = [b"pool", pool.token_mint.as_ref()],
+        bump = pool.bump,
+        has_one = authority @ PoolError::Unauthorized
+    )]
+    pub pool: Account<'info, PoolState>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + AdminMultisig::INIT_SPACE,
+        seeds = [ADMIN_MULTISIG_SEED, pool.key().as_ref()],
+        bump
+    )]
+    pub admin_multisig: Account<'info, AdminMultisig>,
+
+    /// Pool admin (must match pool.authority)
+    /// CHECK: Matched by has_one, authorized by require_admin
+    pub authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_handler(
+    ctx: Context<CreateAdminMultisig>,
+    signers: Vec<Pubkey>,
+    threshold: u8,
+) -> Result<()> {
+    require_admin(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+    let pool = &ctx.accounts.pool;
+    let admin_multisig = &mut ctx.accounts.admin_multisig;
+    admin_multisig.version = ADMIN_MULTISIG_VERSION;
+    admin_multisig.pool = pool.key();
+    admin_multisig.bump = ctx.bumps.admin_multisig;
+    admin_multisig.set_signers(&signers, threshold)?;
+
+    emit!(AdminMultisigUpdatedEvent {
+        pool: pool.key(),
+        admin_multisig: admin_multisig.key(),
+        signers,
+        threshold,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!(
+        "Admin multisig created: {}-of-{}",
+        threshold,
+        admin_multisig.signer_count
+    );
+    Ok(())
+}
+
+/// Update Admin Multisig Context
+///
+/// Replaces the signer set and threshold. Approved by the multisig itself:
+/// `threshold` of the current signers must sign (as remaining accounts).
+#[derive(Accounts)]
+pub struct UpdateAdminMultisig<'info> {
+    #[account(
+        mut,
+        seeds = [ADMIN_MULTISIG_SEED, admin_multisig.pool.as_ref()],
+        bump = admin_multisig.bump
+    )]
+    pub admin_multisig: Account<'info, AdminMultisig>,
+}
+
+pub fn update_handler(
+    ctx: Context<UpdateAdminMultisig>,
+    signers: Vec<Pubkey>,
+    threshold: u8,
+) -> Result<()> {
+    let admin_multisig = &mut ctx.accounts.admin_multisig;
+    admin_multisig.require_approvals(ctx.remaining_accounts)?;
+    admin_multisig.set_signers(&signers, threshold)?;
+
+    emit!(AdminMultisigUpdatedEvent {
+        pool: admin_multisig.pool,
+        admin_multisig: admin_multisig.key(),
+        signers,
+        threshold,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!(
+        "Admin multisig updated: {}-of-{}",
+        threshold,
+        admin_multisig.signer_count
+    );
+    Ok(())
+}
@@ -0,0 +1,155 @@
+use crate::errors::PoolError;
+use crate::events::{AuthorityProposedEvent, AuthorityTransferredEvent, PerAuthorityRotationEvent};
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+/// Propose Authority Context
+///
+/// Step 1 of the two-step admin transfer. The current authority names a
+/// successor, who must accept after `authority_delay_slots` have passed.
+#[derive(Accounts)]
+pub struct ProposeAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.token_mint.as_ref()],
+        bump = pool.bump,
+        has_one = authority @ PoolError::Unauthorized
+    )]
+    pub pool: Account<'info, PoolState>,
+
+    /// Pool admin: a signer, or the pool's AdminMultisig with co-signers as
+    /// remaining accounts
+    /// CHECK: Matched against pool.authority, authorized by require_admin
+    pub authority: UncheckedAccount<'info>,
+}
+
+/// Propose a new pool authority
+///
+/// Proposing Pubkey::default() cancels a pending transfer.
+pub fn propose_authority_handler(
+    ctx: Context<ProposeAuthority>,
+    new_authority: Pubkey,
+) -> Result<()> {
+    require_admin(&ctx.accounts.authority, ctx.remaining_accounts)?;
+    let pool = &mut ctx.accounts.pool;
+    let current_slot = Clock::get()?.slot;
+
+    let effective_slot = pool.propose_authority(new_authority, current_slot)?;
+
+    emit!(AuthorityProposedEvent {
+        pool: pool.key(),
+        authority: pool.authority,
+        pending_authority: new_authority,
+        effective_slot,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!(
+        "Authority transfer proposed: {} (acceptable from slot {})",
+        new_authority,
+        effective_slot
+    );
+    Ok(())
+}
+
+/// Accept Authority Context
+///
+/// Step 2 of the two-step admin transfer. Signing with the pending key proves
+/// the successor controls it, so a typo can't lock the pool. An AdminMultisig
+/// successor accepts with `threshold` of its signers instead.
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.token_mint.as_ref()],
+        bump = pool.bump,
+        constraint = pool.pending_authority != Pubkey::default() @ PoolError::NoPendingAuthority,
+        constraint = pool.pending_authority == new_authority.key() @ PoolError::Unauthorized
+    )]
+    pub pool: Account<'info, PoolState>,
+
+    /// Proposed authority: a signer, or an AdminMultisig with co-signers as
+    /// remaining accounts
+    /// CHECK: Matched against pool.pending_authority, authorized by require_admin
+    pub new_authority: UncheckedAccount<'info>,
+}
+
+/// Accept a proposed pool authority
+pub fn accept_authority_handler(ctx: Context<AcceptAuthority>) -> Result<()> {
+    require_admin(&ctx.accounts.new_authority, ctx.remaining_accounts)?;
+    let pool = &mut ctx.accounts.pool;
+    let current_slot = Clock::get()?.slot;
+
+    let previous_authority = pool.authority;
+    pool.accept_authority(current_slot)?;
+
+    emit!(AuthorityTransferredEvent {
+        pool: pool.key(),
+        previous_authority,
+        new_authority: pool.authority,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!(
+        "Authority transferred: {} -> {}",
+        previous_authority,
+        pool.authority
+    );
+    Ok(())
+}
+
+/// Rotate PER Authority Context
+///
+/// Replaces the key allowed to call settle_batch, so a lost or compromised
+/// PER key no longer bricks batch settlement.
+#[derive(Accounts)]
+pub struct RotatePerAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.token_mint.as_ref()],
+        bump = pool.bump,
+        has_one = authority @ PoolError::Unauthorized
+    )]
+    pub pool: Account<'info, PoolState>,
+
+    /// Pool admin: a signer, or the pool's AdminMultisig with co-signers as
+    /// remaining accounts
+    /// CHECK: Matched against pool.authority, authorized by require_admin
+    pub authority: UncheckedAccount<'info>,
+}
+
+/// Rotate the PER authority
+///
+/// FLOW:
+/// - authority_delay_slots == 0: the rotation applies immediately
+/// - Otherwise the first call queues it; calling again with the same key once
+///   the delay has elapsed applies it
+/// - Queuing a different key replaces (and restarts) the pending rotation
+pub fn rotate_per_authority_handler(
+    ctx: Context<RotatePerAuthority>,
+    new_per_authority: Pubkey,
+) -> Result<()> {
+    require_admin(&ctx.accounts.authority, ctx.remaining_accounts)?;
+    let pool = &mut ctx.accounts.pool;
+    let current_slot = Clock::get()?.slot;
+
+    let previous_per_authority = pool.per_authority;
+    let (applied, effective_slot) = pool.rotate_per_authority(new_per_authority, current_slot)?;
+
+    emit!(PerAuthorityRotationEvent {
+        pool: pool.key(),
+        previous_per_authority,
+        new_per_authority,
+        effective_slot,
+        applied,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!(
+        "PER authority rotation to {}: {} (effective slot {})",
+        new_per_authority,
+        if applied { "applied" } else { "queued" },
+        effective_slot
+    );
+    Ok(())
+}
@@ -3,22 +3,17 @@ use crate::events::NullifierCleanupEvent;
 use crate::state::*;
 use anchor_lang::prelude::*;
 
-/// Minimum age before a nullifier can be cleaned up (in slots)
-///
-/// SECURITY (LOW-02): Nullifier cleanup strategy
-/// - ~2 hours at 0.4s per slot = 18000 slots
-/// - Must be significantly longer than MAX_ROOT_AGE_SLOTS (900)
-/// - Ensures nullifiers cannot be reused even if someone holds old proofs
-pub const MIN_NULLIFIER_AGE_FOR_CLEANUP: u64 = 18000;
-
 /// Cleanup Nullifier Context
 ///
 /// SECURITY (LOW-02): Nullifier cleanup strategy
 /// This instruction allows cleaning up old nullifier PDAs to recover rent.
 ///
 /// SAFETY GUARANTEES:
-/// - Only nullifiers older than MIN_NULLIFIER_AGE_FOR_CLEANUP can be cleaned
+/// - Only nullifiers older than the cleanup age can be cleaned
+///   (PoolConfig.min_nullifier_age_for_cleanup, default MIN_NULLIFIER_AGE_FOR_CLEANUP)
 /// - Rent is returned to the pool authority (not arbitrary accounts)
+/// - Nothing can be cleaned up in emergency mode: emergency claims open the
+///   frozen emergency root, which never expires, so their nullifiers must stay
 /// - Event is emitted for audit trail
 ///
 /// CONSIDERATIONS:
@@ -30,7 +25,8 @@ pub struct CleanupNullifier<'info> {
     /// Pool state
     #[account(
         seeds = [b"pool", pool.token_mint.as_ref()],
-        bump = pool.bump
+        bump = pool.bump,
+        constraint = !pool.emergency_mode @ PoolError::CleanupDuringEmergency
     )]
     pub pool: Account<'info, PoolState>,
 
@@ -51,6 +47,13 @@ pub struct CleanupNullifier<'info> {
     )]
     pub rent_recipient: AccountInfo<'info>,
 
+    /// Pool config PDA (cleanup age; mandatory once initialized)
+    #[account(
+        seeds = [POOL_CONFIG_SEED, pool.key().as_ref()],
+        bump = pool_config.bump,
+    )]
+    pub pool_config: Option<Account<'info, PoolConfig>>,
+
     /// Anyone can call cleanup, but rent goes to authority
     pub payer: Signer<'info>,
 
@@ -60,7 +63,7 @@ pub struct CleanupNullifier<'info> {
 /// Cleanup old nullifier PDA and recover rent
 ///
 /// SECURITY (LOW-02): Nullifier cleanup
-/// - Only cleans nullifiers older than MIN_NULLIFIER_AGE_FOR_CLEANUP
+/// - Only cleans nullifiers older than the pool's cleanup age
 /// - Works in conjunction with root expiration (HIGH-01) for safety
 pub fn handler(ctx: Context<CleanupNullifier>) -> Result<()> {
     let nullifier_entry = &ctx.accounts.nullifier_entry;
@@ -70,8 +73,12 @@ pub fn handler(ctx: Context<CleanupNullifier>) -> Result<()> {
     let nullifier_age = current_slot.saturating_sub(nullifier_entry.slot);
 
     // Verify nullifier is old enough to clean up
+    let min_age = PoolConfig::min_nullifier_age_for_cleanup(
+        &ctx.accounts.pool,
+        ctx.accounts.pool_config.as_deref(),
+    )?;
     require!(
-        nullifier_age >= MIN_NULLIFIER_AGE_FOR_CLEANUP,
+        nullifier_age >= min_age,
         PoolError::Unauthorized // Reuse error - nullifier too young
     );
 
@@ -3,11 +3,13 @@ use crate::events::DepositEvent;
 use crate::state::*;
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::keccak;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_lang::system_program;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
 use zk_verifier::cpi;
-use zk_verifier::cpi::accounts::VerifyProof;
+use zk_verifier::cpi::accounts::VerifyPrepared;
 use zk_verifier::program::ZkVerifier;
-use zk_verifier::state::VerificationKey;
+use zk_verifier::registry::{CircuitRegistry, CIRCUIT_REGISTRY_SEED};
+use zk_verifier::state::{PreparedVerificationKey, VerificationKey, PREPARED_VK_SEED};
 
 #[derive(Accounts)]
 #[instruction(amount: u64, proof_data: DepositProofData)]
@@ -21,29 +23,57 @@ pub struct Deposit<'info> {
     )]
     pub pool: Account<'info, PoolState>,
 
-    /// User's token account (source)
+    /// User's token account (source, SPL pools only)
     #[account(
         mut,
         constraint = user_token_account.mint == pool.token_mint @ PoolError::InvalidMint
     )]
-    pub user_token_account: Account<'info, TokenAccount>,
+    pub user_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
 
-    /// Pool's token vault (destination)
+    /// Pool's token vault (destination, SPL pools only)
     #[account(
         mut,
         seeds = [b"vault", pool.key().as_ref()],
         bump
     )]
-    pub pool_vault: Account<'info, TokenAccount>,
+    pub pool_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// SOL vault PDA (destination, native SOL pools only)
+    #[account(
+        mut,
+        seeds = [SOL_VAULT_SEED, pool.key().as_ref()],
+        bump
+    )]
+    pub sol_vault: Option<SystemAccount<'info>>,
 
     /// Verification key account (for ZK proof verification)
     /// SECURITY: Verified to be for this pool and deposit circuit
     #[account(
         constraint = verification_key.pool == pool.key() @ PoolError::InvalidVerificationKey,
-        constraint = verification_key.circuit_id == proof::circuit_ids::DEPOSIT @ PoolError::InvalidVerificationKey
+        constraint = circuit_registry.is_registered_as(
+            &verification_key.circuit_id,
+            proof::circuit_ids::names::DEPOSIT
+        ) @ PoolError::InvalidVerificationKey
     )]
     pub verification_key: Account<'info, VerificationKey>,
 
+    /// Verify-ready copy of verification_key, read zero-copy by verify_prepared
+    #[account(
+        seeds = [PREPARED_VK_SEED, verification_key.key().as_ref()],
+        seeds::program = zk_verifier::ID,
+        bump = prepared_vk.load()?.bump
+    )]
+    pub prepared_vk: AccountLoader<'info, PreparedVerificationKey>,
+
+    /// Pool's circuit registry (zk-verifier), resolves which circuit IDs are
+    /// deposit circuits
+    #[account(
+        seeds = [CIRCUIT_REGISTRY_SEED, pool.key().as_ref()],
+        seeds::program = zk_verifier::ID,
+        bump = circuit_registry.bump
+    )]
+    pub circuit_registry: Account<'info, CircuitRegistry>,
+
     /// ZK Verifier program (for CPI verification)
     pub verifier_program: Program<'info, ZkVerifier>,
 
@@ -62,27 +92,59 @@ pub struct Deposit<'info> {
     )]
     pub historical_roots: Option<Account<'info, HistoricalRoots>>,
 
-    /// SPL Token program
-    pub token_program: Program<'info, Token>,
+    /// Pool's token mint (needed by transfer_checked, SPL pools only)
+    #[account(address = pool.token_mint @ PoolError::InvalidMint)]
+    pub token_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// SPL Token or Token-2022 program
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
 }
 
-pub fn handler(ctx: Context<Deposit>, amount: u64, proof_data: DepositProofData) -> Result<()> {
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, Deposit<'info>>,
+    amount: u64,
+    proof_data: DepositProofData,
+) -> Result<()> {
     let pool = &mut ctx.accounts.pool;
     let current_slot = Clock::get()?.slot;
 
-    // 1. SECURITY (MEDIUM-04): Enforce minimum deposit to prevent spam
+    // 1. Token-2022 transfer-fee mints withhold part of `amount` in the vault,
+    // so only the post-fee amount is credited to the depositor's commitment
+    let expected_fee = if pool.native_sol {
+        0
+    } else {
+        let token_mint = ctx
+            .accounts
+            .token_mint
+            .as_ref()
+            .ok_or(PoolError::InvalidPoolMode)?;
+        transfer_fee(&token_mint.to_account_info(), amount)?
+    };
+    let credited_amount = amount
+        .checked_sub(expected_fee)
+        .ok_or(PoolError::Underflow)?;
+
+    // SECURITY (MEDIUM-04): Enforce minimum deposit to prevent spam
     // This protects against merkle tree bloating and compute exhaustion attacks
     require!(
-        amount >= MIN_DEPOSIT_SPL_UNITS,
+        credited_amount >= pool.min_deposit,
         PoolError::DepositBelowMinimum
     );
+    require!(
+        credited_amount <= pool.max_deposit,
+        PoolError::DepositAboveMaximum
+    );
+
+    // Fixed-denomination pools only accept listed amounts
+    let denomination_index = pool.check_denomination(credited_amount)?;
 
     // 2. Request compute budget for ZK verification (~600k CU)
     // This is done implicitly by the syscall, but we can log the estimate
     msg!("Verifying deposit proof (estimated 600k CU)");
 
-    // 3. Verify amount matches proof
-    let proof_amount = u64_to_field(amount);
+    // 3. Verify credited (post-fee) amount matches proof
+    let proof_amount = u64_to_field(credited_amount);
     require!(
         proof_data.deposit_amount == proof_amount,
         PoolError::InvalidProof
@@ -94,53 +156,94 @@ pub fn handler(ctx: Context<Deposit>, amount: u64, proof_data: DepositProofData)
         PoolError::InvalidMerkleRoot
     );
 
-    // 5. SECURITY (HIGH-02): Verify VK hash matches pool's expected VK
+    // 5. SECURITY (HIGH-02): Verify VK hash is one the pool accepts for this circuit
     // This prevents VK substitution attacks if admin key is compromised
     let vk_data = ctx.accounts.verification_key.try_to_vec()?;
     let vk_hash = keccak::hash(&vk_data);
-    require!(
-        pool.vk_hash == vk_hash.to_bytes(),
-        PoolError::VerificationKeyHashMismatch
-    );
+    pool.require_vk(
+        &ctx.accounts.verification_key.circuit_id,
+        &vk_hash.to_bytes(),
+        current_slot,
+    )?;
 
-    // 6. Verify ZK proof via CPI to zk-verifier program
+    // 6. Verify ZK proof via CPI to zk-verifier (verify_prepared on the key hashed above)
     let verify_cpi_ctx = CpiContext::new(
         ctx.accounts.verifier_program.to_account_info(),
-        VerifyProof {
-            verification_key: ctx.accounts.verification_key.to_account_info(),
+        VerifyPrepared {
+            prepared_vk: ctx.accounts.prepared_vk.to_account_info(),
         },
     );
 
     let public_inputs = proof_data.public_inputs();
-    cpi::verify(verify_cpi_ctx, proof_data.proof, public_inputs)?;
+    cpi::verify_prepared(verify_cpi_ctx, proof_data.proof, public_inputs)?;
 
     msg!("ZK proof verified successfully");
 
-    // 7. Transfer tokens from user to pool vault
+    // 7. Transfer tokens (or lamports, for native SOL pools) from user to pool vault
     // SECURITY (CRITICAL-06): Verify actual transfer amount matches declared amount
-    let vault_balance_before = ctx.accounts.pool_vault.amount;
-
-    let transfer_ctx = CpiContext::new(
-        ctx.accounts.token_program.to_account_info(),
-        Transfer {
-            from: ctx.accounts.user_token_account.to_account_info(),
-            to: ctx.accounts.pool_vault.to_account_info(),
-            authority: ctx.accounts.depositor.to_account_info(),
-        },
-    );
-    token::transfer(transfer_ctx, amount)?;
+    let (vault_balance_before, vault_balance_after) = if pool.native_sol {
+        let sol_vault = ctx
+            .accounts
+            .sol_vault
+            .as_ref()
+            .ok_or(PoolError::InvalidPoolMode)?;
+        let balance_before = sol_vault.lamports();
 
-    // Reload vault account to get updated balance
-    ctx.accounts.pool_vault.reload()?;
-    let vault_balance_after = ctx.accounts.pool_vault.amount;
+        let transfer_ctx = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.depositor.to_account_info(),
+                to: sol_vault.to_account_info(),
+            },
+        );
+        system_program::transfer(transfer_ctx, amount)?;
+
+        (balance_before, sol_vault.lamports())
+    } else {
+        let user_token_account = ctx
+            .accounts
+            .user_token_account
+            .as_ref()
+            .ok_or(PoolError::InvalidPoolMode)?;
+        let token_mint = ctx
+            .accounts
+            .token_mint
+            .as_ref()
+            .ok_or(PoolError::InvalidPoolMode)?;
+        let pool_vault = ctx
+            .accounts
+            .pool_vault
+            .as_mut()
+            .ok_or(PoolError::InvalidPoolMode)?;
+        let balance_before = pool_vault.amount;
+
+        let transfer_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: user_token_account.to_account_info(),
+                mint: token_mint.to_account_info(),
+                to: pool_vault.to_account_info(),
+                authority: ctx.accounts.depositor.to_account_info(),
+            },
+        );
+        token_interface::transfer_checked(
+            transfer_ctx.with_remaining_accounts(ctx.remaining_accounts.to_vec()),
+            amount,
+            token_mint.decimals,
+        )?;
+
+        // Reload vault account to get updated balance
+        pool_vault.reload()?;
+        (balance_before, pool_vault.amount)
+    };
 
-    // Verify actual transferred amount matches requested amount
+    // Verify the vault received exactly the credited (post-fee) amount
     let actual_transferred = vault_balance_after
         .checked_sub(vault_balance_before)
         .ok_or(PoolError::Underflow)?;
 
     require!(
-        actual_transferred == amount,
+        actual_transferred == credited_amount,
         PoolError::InvalidTransferAmount
     );
 
@@ -158,6 +261,11 @@ pub fn handler(ctx: Context<Deposit>, amount: u64, proof_data: DepositProofData)
         .total_deposits
         .checked_add(1)
         .ok_or(PoolError::Overflow)?;
+    if let Some(index) = denomination_index {
+        pool.denomination_deposits[index] = pool.denomination_deposits[index]
+            .checked_add(1)
+            .ok_or(PoolError::Overflow)?;
+    }
 
     // 9. SECURITY (CRITICAL-02): Also push to HistoricalRoots PDA if available
     // This provides the extended 900-slot (~6 min) spending window
@@ -176,14 +284,14 @@ pub fn handler(ctx: Context<Deposit>, amount: u64, proof_data: DepositProofData)
     emit!(DepositEvent {
         pool: pool.key(),
         commitment: proof_data.new_commitment,
-        amount,
+        amount: credited_amount,
         new_root,
         timestamp: Clock::get()?.unix_timestamp,
     });
 
     msg!(
-        "Deposit successful: {} tokens, new root: {:?}",
-        amount,
+        "Deposit successful: {} tokens credited, new root: {:?}",
+        credited_amount,
         new_root
     );
     Ok(())
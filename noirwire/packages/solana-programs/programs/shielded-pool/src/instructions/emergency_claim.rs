@@ -0,0 +1,386 @@
+use crate::errors::PoolError;
+use crate::events::{EmergencyClaimCommittedEvent, EmergencyClaimEvent};
+use crate::instructions::record_nullifier::compute_merkle_root_with_indices;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+/// Commit Emergency Claim Context
+///
+/// Step 1 of an emergency claim: records `claim_hash` (see
+/// `EmergencyClaimData::claim_hash`) without revealing the opening. Anyone
+/// may pay for it; the hash alone binds the recipient.
+#[derive(Accounts)]
+#[instruction(claim_hash: [u8; 32])]
+pub struct CommitEmergencyClaim<'info> {
+    /// Pool state - must be in emergency mode
+    #[account(
+        seeds = [b"pool", pool.token_mint.as_ref()],
+        bump = pool.bump,
+        constraint = pool.emergency_mode @ PoolError::EmergencyModeNotActive
+    )]
+    pub pool: Account<'info, PoolState>,
+
+    /// Commitment PDA (closed to the recipient when revealed)
+    #[account(
+        init,
+        payer = payer,
+        space = EmergencyClaimCommitment::SIZE,
+        seeds = [EMERGENCY_CLAIM_COMMITMENT_SEED, pool.key().as_ref(), &claim_hash],
+        bump
+    )]
+    pub claim_commitment: Account<'info, EmergencyClaimCommitment>,
+
+    /// Payer for the commitment account
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Record an emergency claim commitment
+pub fn commit_handler(ctx: Context<CommitEmergencyClaim>, claim_hash: [u8; 32]) -> Result<()> {
+    let current_slot = Clock::get()?.slot;
+
+    let claim_commitment = &mut ctx.accounts.claim_commitment;
+    claim_commitment.claim_hash = claim_hash;
+    claim_commitment.slot = current_slot;
+    claim_commitment.bump = ctx.bumps.claim_commitment;
+
+    let reveal_slot = current_slot.saturating_add(EMERGENCY_CLAIM_REVEAL_DELAY_SLOTS);
+    emit!(EmergencyClaimCommittedEvent {
+        pool: ctx.accounts.pool.key(),
+        claim_hash,
+        reveal_slot,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!(
+        "Emergency claim committed (revealable from slot {})",
+        reveal_slot
+    );
+    Ok(())
+}
+
+/// Emergency Claim Context
+///
+/// SECURITY (LOW-01): Emergency withdrawal mechanism
+/// Lets users exit without ZK proofs (e.g., circuits broken) and without
+/// trusting the admin: each claim opens a commitment against the root frozen
+/// by `set_emergency_mode(true)` and consumes its nullifier.
+///
+/// REQUIREMENTS:
+/// - Pool must be paused and in emergency_mode
+/// - EMERGENCY_CLAIM_DELAY_SLOTS must have passed since emergency mode was enabled
+/// - The opening must hash to a leaf of `emergency_root`
+/// - The opening and recipient must match a commitment made at least
+///   EMERGENCY_CLAIM_REVEAL_DELAY_SLOTS earlier (see state/emergency_claim.rs)
+///
+/// DOUBLE-SPEND: The nullifier is computed on-chain exactly like the withdraw
+/// circuit's, so the same PDA blocks claiming twice and claiming a balance that
+/// was already spent on L1. Balances spent in the PER are no longer leaves of
+/// the settled root.
+///
+/// FRONT-RUNNING: The opening is public once the claim is broadcast and no
+/// proof binds the recipient, so the recipient is bound by commit-reveal
+/// instead: a copied opening can only be revealed to the recipient committed
+/// to before the opening was public.
+#[derive(Accounts)]
+#[instruction(nullifier: [u8; 32])]
+pub struct EmergencyClaim<'info> {
+    /// Pool state - must be in emergency mode
+    #[account(
+        mut,
+        seeds = [b"pool", pool.token_mint.as_ref()],
+        bump = pool.bump,
+        constraint = pool.paused @ PoolError::PoolPaused,
+        constraint = pool.emergency_mode @ PoolError::EmergencyModeNotActive
+    )]
+    pub pool: Account<'info, PoolState>,
+
+    /// Nullifier PDA for the claimed balance
+    /// SECURITY: init constraint prevents double-spend by rejecting duplicate nullifiers
+    #[account(
+        init,
+        payer = payer,
+        space = NullifierEntry::SIZE,
+        seeds = [b"nullifier", pool.key().as_ref(), &nullifier],
+        bump
+    )]
+    pub nullifier_entry: Account<'info, NullifierEntry>,
+
+    /// Commitment to this claim and recipient (see commit_emergency_claim)
+    /// Checked against the revealed opening in the handler, closed on success
+    #[account(
+        mut,
+        close = recipient,
+        seeds = [
+            EMERGENCY_CLAIM_COMMITMENT_SEED,
+            pool.key().as_ref(),
+            &claim_commitment.claim_hash
+        ],
+        bump = claim_commitment.bump
+    )]
+    pub claim_commitment: Account<'info, EmergencyClaimCommitment>,
+
+    /// Pool's token vault (SPL pools only)
+    #[account(
+        mut,
+        seeds = [b"vault", pool.key().as_ref()],
+        bump
+    )]
+    pub pool_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Recipient's token account (SPL pools only)
+    #[account(
+        mut,
+        constraint = recipient_token_account.mint == pool.token_mint @ PoolError::InvalidMint,
+        constraint = recipient_token_account.owner == recipient.key() @ PoolError::InvalidRecipient
+    )]
+    pub recipient_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// SOL vault PDA (source, native SOL pools only)
+    #[account(
+        mut,
+        seeds = [SOL_VAULT_SEED, pool.key().as_ref()],
+        bump
+    )]
+    pub sol_vault: Option<SystemAccount<'info>>,
+
+    /// Pool authority PDA (for signing vault transfers)
+    /// CHECK: PDA verified by seeds
+    #[account(
+        seeds = [b"authority", pool.key().as_ref()],
+        bump
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    /// Recipient of the funds (receives lamports directly in native SOL pools)
+    /// SECURITY: Bound by the claim commitment, not by the signer
+    #[account(mut)]
+    pub recipient: SystemAccount<'info>,
+
+    /// Payer for nullifier account creation
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Pool's token mint (needed by transfer_checked, SPL pools only)
+    #[account(address = pool.token_mint @ PoolError::InvalidMint)]
+    pub token_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// SPL Token or Token-2022 program
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Emergency claim handler
+///
+/// COMPUTE: TREE_DEPTH + 2 Poseidon2 hashes; request a raised compute budget.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, EmergencyClaim<'info>>,
+    nullifier: [u8; 32],
+    claim: EmergencyClaimData,
+    salt: [u8; 32],
+) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    let current_slot = Clock::get()?.slot;
+
+    // 1. Timelock: claims only open a fixed delay after emergency mode was enabled
+    require!(
+        pool.allows_emergency_claim(current_slot),
+        PoolError::EmergencyTimelockActive
+    );
+
+    // 2. SECURITY: Pay only the recipient committed to with this opening,
+    // and only once the commitment predates anything a front-runner could make
+    let claim_commitment = &ctx.accounts.claim_commitment;
+    require!(
+        claim.claim_hash(&ctx.accounts.recipient.key(), &salt) == claim_commitment.claim_hash,
+        PoolError::InvalidEmergencyClaim
+    );
+    require!(
+        claim_commitment.is_revealable(current_slot),
+        PoolError::EmergencyClaimRevealTooEarly
+    );
+
+    // 3. Verify the opening is a leaf of the frozen root
+    require!(
+        claim.merkle_proof.len() == TREE_DEPTH && claim.path_indices.len() == TREE_DEPTH,
+        PoolError::InvalidEmergencyClaim
+    );
+    let commitment = claim.commitment()?;
+    let computed_root =
+        compute_merkle_root_with_indices(&commitment, &claim.merkle_proof, &claim.path_indices)?;
+    require!(
+        computed_root == pool.emergency_root,
+        PoolError::InvalidEmergencyClaim
+    );
+
+    // 4. Bind the nullifier PDA to the opened commitment
+    require!(
+        claim.nullifier(&commitment)? == nullifier,
+        PoolError::InvalidEmergencyClaim
+    );
+
+    // 5. Verify pool has sufficient balance
+    let amount = claim.amount;
+    require!(amount > 0, PoolError::InvalidEmergencyClaim);
+    require!(
+        pool.total_shielded >= amount,
+        PoolError::InsufficientPoolBalance
+    );
+
+    // 6. Pay out the full balance
+    let pool_key = pool.key();
+    if pool.native_sol {
+        // Native SOL: pay lamports straight from the SOL vault PDA
+        let sol_vault = ctx
+            .accounts
+            .sol_vault
+            .as_ref()
+            .ok_or(PoolError::InvalidPoolMode)?;
+        let sol_vault_bump = ctx.bumps.sol_vault.ok_or(PoolError::InvalidPoolMode)?;
+        transfer_from_sol_vault(
+            sol_vault,
+            &ctx.accounts.recipient.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            &pool_key,
+            sol_vault_bump,
+            amount,
+        )?;
+    } else {
+        let recipient_token_account = ctx
+            .accounts
+            .recipient_token_account
+            .as_ref()
+            .ok_or(PoolError::InvalidPoolMode)?;
+        let pool_vault = ctx
+            .accounts
+            .pool_vault
+            .as_ref()
+            .ok_or(PoolError::InvalidPoolMode)?;
+        let token_mint = ctx
+            .accounts
+            .token_mint
+            .as_ref()
+            .ok_or(PoolError::InvalidPoolMode)?;
+        require!(
+            pool_vault.amount >= amount,
+            PoolError::InsufficientVaultBalance
+        );
+
+        let authority_seeds = &[b"authority", pool_key.as_ref(), &[ctx.bumps.pool_authority]];
+        let signer_seeds = &[&authority_seeds[..]];
+
+        let transfer_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: pool_vault.to_account_info(),
+                mint: token_mint.to_account_info(),
+                to: recipient_token_account.to_account_info(),
+                authority: ctx.accounts.pool_authority.to_account_info(),
+            },
+            signer_seeds,
+        );
+        token_interface::transfer_checked(
+            transfer_ctx.with_remaining_accounts(ctx.remaining_accounts.to_vec()),
+            amount,
+            token_mint.decimals,
+        )?;
+    }
+
+    // 7. Record nullifier (account creation proves uniqueness)
+    let nullifier_entry = &mut ctx.accounts.nullifier_entry;
+    nullifier_entry.nullifier = nullifier;
+    nullifier_entry.slot = current_slot;
+    nullifier_entry.bump = ctx.bumps.nullifier_entry;
+
+    // 8. Update pool accounting (the frozen root is left untouched)
+    pool.total_shielded = pool
+        .total_shielded
+        .checked_sub(amount)
+        .ok_or(PoolError::Underflow)?;
+    pool.total_withdrawals = pool
+        .total_withdrawals
+        .checked_add(1)
+        .ok_or(PoolError::Overflow)?;
+    pool.total_nullifiers = pool
+        .total_nullifiers
+        .checked_add(1)
+        .ok_or(PoolError::Overflow)?;
+    pool.emergency_claims = pool
+        .emergency_claims
+        .checked_add(1)
+        .ok_or(PoolError::Overflow)?;
+
+    emit!(EmergencyClaimEvent {
+        pool: pool_key,
+        recipient: ctx.accounts.recipient.key(),
+        nullifier,
+        amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!(
+        "EMERGENCY CLAIM: {} tokens to {}",
+        amount,
+        ctx.accounts.recipient.key()
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_claim() -> EmergencyClaimData {
+        EmergencyClaimData {
+            owner: u64_to_field(12345),
+            amount: 100_000_000,
+            vault_id: [0u8; 32],
+            blinding: u64_to_field(99999),
+            nullifier_secret: [0u8; 32],
+            nonce: [0u8; 32],
+            merkle_proof: Vec::new(),
+            path_indices: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_commitment_matches_noir() {
+        // test_commitment_matches_sdk: Poseidon2::hash([1, 12345, 100000000, 0, 99999], 5)
+        let claim = test_claim();
+
+        assert_eq!(
+            claim.commitment().unwrap(),
+            [
+                0x02, 0x90, 0x72, 0x09, 0x7b, 0xde, 0xf4, 0x07, 0xdf, 0x7f, 0x0e, 0x4e, 0x28, 0x3c,
+                0x0b, 0xca, 0x58, 0xb7, 0xf4, 0x86, 0x70, 0x48, 0xab, 0x5d, 0x99, 0x98, 0xd6, 0xc5,
+                0x9b, 0x72, 0x0f, 0x86,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_claim_hash_binds_recipient() {
+        let claim = test_claim();
+        let recipient = Pubkey::new_unique();
+        let salt = [7u8; 32];
+        let claim_hash = claim.claim_hash(&recipient, &salt);
+
+        assert_ne!(claim.claim_hash(&Pubkey::new_unique(), &salt), claim_hash);
+        assert_ne!(claim.claim_hash(&recipient, &[8u8; 32]), claim_hash);
+        let mut other = test_claim();
+        other.nullifier_secret = [1u8; 32];
+        assert_ne!(other.claim_hash(&recipient, &salt), claim_hash);
+
+        let commitment = EmergencyClaimCommitment {
+            claim_hash,
+            slot: 1_000,
+            bump: 255,
+        };
+        assert!(!commitment.is_revealable(1_000 + EMERGENCY_CLAIM_REVEAL_DELAY_SLOTS - 1));
+        assert!(commitment.is_revealable(1_000 + EMERGENCY_CLAIM_REVEAL_DELAY_SLOTS));
+    }
+}
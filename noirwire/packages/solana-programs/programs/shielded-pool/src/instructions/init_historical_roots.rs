@@ -1,5 +1,5 @@
 use crate::errors::PoolError;
-use crate::state::{HistoricalRoots, PoolState, HISTORICAL_ROOTS_SEED};
+use crate::state::{require_admin, HistoricalRoots, PoolState, HISTORICAL_ROOTS_SEED};
 use anchor_lang::prelude::*;
 
 /// Initialize the Historical Roots PDA for production-scale merkle root storage
@@ -12,6 +12,7 @@ use anchor_lang::prelude::*;
 /// - Only the pool authority can call this
 /// - Account size is ~40KB (uses borsh serialization for Vec support)
 pub fn handler(ctx: Context<InitializeHistoricalRoots>) -> Result<()> {
+    require_admin(&ctx.accounts.authority, ctx.remaining_accounts)?;
     let pool = &ctx.accounts.pool;
     let historical_roots = &mut ctx.accounts.historical_roots;
 
@@ -39,16 +40,20 @@ pub struct InitializeHistoricalRoots<'info> {
     /// The historical roots PDA to create (uses borsh for vector support)
     #[account(
         init,
-        payer = authority,
+        payer = payer,
         space = HistoricalRoots::MAX_SPACE,
         seeds = [HISTORICAL_ROOTS_SEED, pool.key().as_ref()],
         bump
     )]
     pub historical_roots: Account<'info, HistoricalRoots>,
 
-    /// Pool authority (must match pool.authority)
+    /// Pool admin: a signer, or the pool's AdminMultisig with co-signers as
+    /// remaining accounts
+    /// CHECK: Matched against pool.authority, authorized by require_admin
+    pub authority: UncheckedAccount<'info>,
+
     #[account(mut)]
-    pub authority: Signer<'info>,
+    pub payer: Signer<'info>,
 
     pub system_program: Program<'info, System>,
 }
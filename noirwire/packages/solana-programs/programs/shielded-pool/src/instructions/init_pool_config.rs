@@ -0,0 +1,61 @@
+use crate::errors::PoolError;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+/// Initialize the PoolConfig PDA with the compile-time defaults
+///
+/// After this call the pool reads its root-age window and nullifier cleanup
+/// age from the PoolConfig account, and `update_pool_config` can tune them.
+///
+/// REQUIREMENTS:
+/// - Pool must be initialized first
+/// - Only the pool authority can call this
+pub fn handler(ctx: Context<InitializePoolConfig>) -> Result<()> {
+    require_admin(&ctx.accounts.authority, ctx.remaining_accounts)?;
+    let pool = &mut ctx.accounts.pool;
+    let pool_config = &mut ctx.accounts.pool_config;
+
+    pool_config.init(pool.key(), ctx.bumps.pool_config);
+    pool.pool_config_initialized = true;
+
+    msg!("Pool config PDA initialized for pool: {:?}", pool.key());
+    msg!(
+        "Root age window: {} slots, nullifier cleanup age: {} slots",
+        pool_config.max_root_age_slots,
+        pool_config.min_nullifier_age_for_cleanup
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializePoolConfig<'info> {
+    /// The shielded pool this config belongs to
+    #[account(
+        mut,
+        seeds = [b"pool", pool.token_mint.as_ref()],
+        bump = pool.bump,
+        has_one = authority @ PoolError::Unauthorized
+    )]
+    pub pool: Account<'info, PoolState>,
+
+    /// The pool config PDA to create
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + PoolConfig::INIT_SPACE,
+        seeds = [POOL_CONFIG_SEED, pool.key().as_ref()],
+        bump
+    )]
+    pub pool_config: Account<'info, PoolConfig>,
+
+    /// Pool admin: a signer, or the pool's AdminMultisig with co-signers as
+    /// remaining accounts
+    /// CHECK: Matched against pool.authority, authorized by require_admin
+    pub authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
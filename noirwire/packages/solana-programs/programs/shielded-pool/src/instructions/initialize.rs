@@ -1,7 +1,9 @@
 use crate::errors::PoolError;
 use crate::state::*;
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Mint, Token, TokenAccount};
+use anchor_lang::system_program;
+use anchor_spl::token::spl_token;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 #[derive(Accounts)]
 pub struct Initialize<'info> {
@@ -14,8 +16,10 @@ pub struct Initialize<'info> {
     )]
     pub pool: Account<'info, PoolState>,
 
-    /// Token mint for this pool
-    pub token_mint: Account<'info, Mint>,
+    /// Token mint for this pool (SPL Token or Token-2022)
+    /// SECURITY: Extensions are validated in the handler (see mint_extensions.rs)
+    #[account(mint::token_program = token_program)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
 
     /// Pool's token vault
     #[account(
@@ -23,10 +27,11 @@ pub struct Initialize<'info> {
         payer = authority,
         token::mint = token_mint,
         token::authority = pool_authority,
+        token::token_program = token_program,
         seeds = [b"vault", pool.key().as_ref()],
         bump
     )]
-    pub pool_vault: Account<'info, TokenAccount>,
+    pub pool_vault: InterfaceAccount<'info, TokenAccount>,
 
     /// Pool authority PDA (for signing vault transfers)
     /// CHECK: PDA verified by seeds
@@ -36,11 +41,19 @@ pub struct Initialize<'info> {
     )]
     pub pool_authority: AccountInfo<'info>,
 
+    /// SOL vault PDA (required for native SOL pools, funded with its rent reserve)
+    #[account(
+        mut,
+        seeds = [SOL_VAULT_SEED, pool.key().as_ref()],
+        bump
+    )]
+    pub sol_vault: Option<SystemAccount<'info>>,
+
     /// Pool admin (payer)
     #[account(mut)]
     pub authority: Signer<'info>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
@@ -48,8 +61,10 @@ pub struct Initialize<'info> {
 pub fn handler(
     ctx: Context<Initialize>,
     token_mint: Pubkey,
-    vk_hash: [u8; 32],
     per_authority: Pubkey,
+    native_sol: bool,
+    min_deposit: u64,
+    max_deposit: u64,
 ) -> Result<()> {
     let pool = &mut ctx.accounts.pool;
     let current_slot = Clock::get()?.slot;
@@ -61,14 +76,60 @@ pub fn handler(
         PoolError::InvalidPerAuthority
     );
 
+    // Reject Token-2022 mints whose extensions break custody or privacy
+    validate_mint_extensions(&ctx.accounts.token_mint.to_account_info())?;
+
+    // Native SOL mode: shielded lamports live in the SOL vault PDA
+    if native_sol {
+        require!(
+            token_mint == spl_token::native_mint::ID,
+            PoolError::InvalidNativeMint
+        );
+        let sol_vault = ctx
+            .accounts
+            .sol_vault
+            .as_ref()
+            .ok_or(PoolError::InvalidPoolMode)?;
+
+        // Fund the rent-exempt reserve so payouts can never close the vault
+        let reserve = Rent::get()?.minimum_balance(0);
+        let top_up = reserve.saturating_sub(sol_vault.lamports());
+        if top_up > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.authority.to_account_info(),
+                        to: sol_vault.to_account_info(),
+                    },
+                ),
+                top_up,
+            )?;
+        }
+    }
+
     // SECURITY (LOW-03): Set version for future migration support
     pool.version = POOL_STATE_VERSION;
 
     pool.authority = ctx.accounts.authority.key();
     pool.per_authority = per_authority; // CRITICAL-05: PER authority for batch settlement
+    pool.pending_authority = Pubkey::default();
+    pool.pending_authority_slot = 0;
+    pool.pending_per_authority = Pubkey::default();
+    pool.pending_per_authority_slot = 0;
+    pool.authority_delay_slots = 0; // Changes apply without delay until configured
+    pool.pending_authority_delay_slots = 0;
+    pool.pending_authority_delay_slot = 0;
     pool.token_mint = token_mint;
     pool.token_vault = ctx.accounts.pool_vault.key();
-    pool.vk_hash = vk_hash;
+    pool.legacy_vk_hash = [0u8; 32]; // Superseded by circuit_vks
+    pool.native_sol = native_sol;
+    pool.token_decimals = ctx.accounts.token_mint.decimals;
+    pool.set_deposit_limits(min_deposit, max_deposit)?;
+    pool.denominations = [0u64; MAX_DENOMINATIONS]; // Arbitrary amounts until set
+    pool.denomination_count = 0;
+    pool.denomination_deposits = [0u64; MAX_DENOMINATIONS];
+    pool.circuit_vks = [CircuitVk::default(); MAX_POOL_CIRCUITS]; // Staged per circuit via update_pool_config
     pool.commitment_root = [0u8; 32]; // Empty tree root
     pool.commitment_root_slot = current_slot; // SECURITY (HIGH-01): Track root slot
     pool.historical_roots = [[0u8; 32]; HISTORICAL_ROOTS_SIZE]; // Production: 900 slots = 6 min window
@@ -76,18 +137,31 @@ pub fn handler(
     pool.roots_index = 0;
     pool.total_shielded = 0;
     pool.paused = false;
+    pool.outflow_limit_bps = 0; // Circuit breaker disabled until configured
+    pool.outflow_epoch_slots = 0;
+    pool.outflow_epoch_start_slot = 0;
+    pool.outflow_epoch_base = 0;
+    pool.outflow_epoch_amount = 0;
+    pool.guardians = [Pubkey::default(); MAX_GUARDIANS];
+    pool.guardian_count = 0;
     pool.emergency_mode = false; // SECURITY (LOW-01): Emergency mode starts disabled
+    pool.emergency_root = [0u8; 32];
+    pool.emergency_activated_slot = 0;
+    pool.emergency_claims = 0;
     pool.total_deposits = 0;
     pool.total_withdrawals = 0;
     pool.total_nullifiers = 0;
     pool.last_nullifiers_root = [0u8; 32];
+    pool.batch_sequence = 0;
+    pool.pool_config_initialized = false; // Compile-time defaults until init_pool_config
     pool.bump = ctx.bumps.pool;
     pool._reserved = Vec::new();
 
     msg!("Pool initialized for mint: {}", token_mint);
     msg!("Pool version: {}", POOL_STATE_VERSION);
+    msg!("Native SOL mode: {}", native_sol);
+    msg!("Deposit limits: {}..={}", min_deposit, max_deposit);
     msg!("PER authority: {}", per_authority);
-    msg!("Verification key hash: {:?}", vk_hash);
     msg!("Initial slot: {}", current_slot);
 
     Ok(())
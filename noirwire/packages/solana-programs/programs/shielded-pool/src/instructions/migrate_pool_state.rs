@@ -0,0 +1,102 @@
+use crate::errors::PoolError;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_lang::Discriminator;
+use anchor_spl::token_interface::Mint;
+
+/// Migrate a version-2 pool to the current PoolState layout
+///
+/// Version-2 fields keep their offsets; every later field is appended after
+/// `bump`. The account grows to the current size, the appended bytes read as
+/// zero, and `PoolState::migrate_from_v2` sets the remaining defaults.
+///
+/// REQUIREMENTS:
+/// - Pool account must be a version-2 PoolState of this program
+/// - Only the pool authority can call this (a signer: version-2 pools predate
+///   the admin multisig)
+pub fn handler(ctx: Context<MigratePoolState>, min_deposit: u64, max_deposit: u64) -> Result<()> {
+    let pool_info = ctx.accounts.pool.to_account_info();
+
+    // 1. Only version-2 PoolState accounts (they no longer deserialize as is)
+    {
+        let data = pool_info.try_borrow_data()?;
+        require!(
+            data.len() > 8 && data[..8] == *PoolState::DISCRIMINATOR,
+            ErrorCode::AccountDiscriminatorMismatch
+        );
+        require!(
+            data[8] == LEGACY_POOL_STATE_VERSION,
+            PoolError::UnsupportedPoolVersion
+        );
+    }
+
+    // 2. Grow to the current layout, topping up rent from the payer
+    let new_len = 8 + PoolState::INIT_SPACE;
+    let top_up = Rent::get()?
+        .minimum_balance(new_len)
+        .saturating_sub(pool_info.lamports());
+    if top_up > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: pool_info.clone(),
+                },
+            ),
+            top_up,
+        )?;
+    }
+    pool_info.resize(new_len)?;
+
+    let mut pool = PoolState::try_deserialize(&mut &pool_info.try_borrow_data()?[..])?;
+
+    // 3. SECURITY: Same PDA, mint and admin checks the typed pool constraints do
+    let expected_pool = Pubkey::create_program_address(
+        &[b"pool", pool.token_mint.as_ref(), &[pool.bump]],
+        &crate::ID,
+    )
+    .map_err(|_| ErrorCode::ConstraintSeeds)?;
+    require_keys_eq!(expected_pool, pool_info.key(), ErrorCode::ConstraintSeeds);
+    require_keys_eq!(
+        pool.token_mint,
+        ctx.accounts.token_mint.key(),
+        PoolError::InvalidMint
+    );
+    require_keys_eq!(
+        pool.authority,
+        ctx.accounts.authority.key(),
+        PoolError::Unauthorized
+    );
+
+    // 4. Defaults for the appended fields
+    pool.migrate_from_v2(ctx.accounts.token_mint.decimals, min_deposit, max_deposit)?;
+    pool.try_serialize(&mut &mut pool_info.try_borrow_mut_data()?[..])?;
+
+    msg!("Pool migrated to version {}", POOL_STATE_VERSION);
+    msg!("Deposit limits: {}..={}", min_deposit, max_deposit);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigratePoolState<'info> {
+    /// Version-2 pool to migrate
+    /// CHECK: Discriminator, version, PDA and authority are validated in the
+    /// handler; the old layout cannot be loaded as Account<PoolState>
+    #[account(mut, owner = crate::ID)]
+    pub pool: UncheckedAccount<'info>,
+
+    /// The pool's mint (its decimals bound the deposit limits)
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    /// Pool admin
+    pub authority: Signer<'info>,
+
+    /// Pays the rent for the larger account
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -1,25 +1,39 @@
 #![allow(ambiguous_glob_reexports)]
 
+pub mod admin_multisig;
+pub mod authority;
 pub mod cleanup_nullifier;
 pub mod deposit;
-pub mod emergency_withdraw;
+pub mod emergency_claim;
 pub mod init_historical_roots;
+pub mod init_pool_config;
 pub mod initialize;
+pub mod migrate_pool_state;
 pub mod record_nullifier;
+pub mod record_nullifiers_batch;
 pub mod set_paused;
 pub mod settle_batch;
+pub mod transfer;
+pub mod update_pool_config;
 pub mod withdraw;
 
 // Re-export everything from each instruction module
 // This is required for Anchor's #[program] macro to work correctly
 // Note: The handler functions have the same name, but the lib.rs calls them
 // qualified as instructions::module::handler() to avoid ambiguity
+pub use admin_multisig::*;
+pub use authority::*;
 pub use cleanup_nullifier::*;
 pub use deposit::*;
-pub use emergency_withdraw::*;
+pub use emergency_claim::*;
 pub use init_historical_roots::*;
+pub use init_pool_config::*;
 pub use initialize::*;
+pub use migrate_pool_state::*;
 pub use record_nullifier::*;
+pub use record_nullifiers_batch::*;
 pub use set_paused::*;
 pub use settle_batch::*;
+pub use transfer::*;
+pub use update_pool_config::*;
 pub use withdraw::*;
@@ -1,5 +1,6 @@
 use crate::errors::PoolError;
 use crate::events::NullifierRecordedEvent;
+use crate::poseidon2;
 use crate::state::*;
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::keccak;
@@ -14,6 +15,14 @@ pub struct RecordNullifier<'info> {
     )]
     pub pool: Account<'info, PoolState>,
 
+    /// Settled batch the nullifier belongs to (any batch still pending)
+    #[account(
+        mut,
+        seeds = [SETTLED_BATCH_SEED, pool.key().as_ref(), &settled_batch.sequence.to_le_bytes()],
+        bump = settled_batch.bump,
+    )]
+    pub settled_batch: Account<'info, SettledBatch>,
+
     /// Nullifier PDA (created to prove uniqueness)
     /// SECURITY: init constraint prevents double-spend by rejecting duplicate nullifiers
     #[account(
@@ -39,21 +48,26 @@ pub fn handler(
     path_indices: Vec<u8>, // CRITICAL-02 FIX: Use path indices for merkle ordering
 ) -> Result<()> {
     let pool = &ctx.accounts.pool;
+    let settled_batch = &mut ctx.accounts.settled_batch;
 
-    // 1. Verify nullifiers_root matches last batch settlement
+    // 1. Verify nullifiers_root matches the settled batch and it is still pending
     require!(
-        pool.last_nullifiers_root == nullifiers_root,
+        settled_batch.nullifiers_root == nullifiers_root,
         PoolError::InvalidNullifierProof
     );
+    require!(settled_batch.is_pending(), PoolError::BatchFullyRecorded);
 
-    // 2. Verify merkle proof and path indices lengths match
+    // 2. Count the leaf against the batch: the proof must be exactly the batch
+    // tree depth and open a position below nullifier_count (never more than
+    // nullifier_count in total). An invalid proof below reverts the count.
     require!(
         merkle_proof.len() == path_indices.len(),
         PoolError::InvalidNullifierProof
     );
+    settled_batch.record(merkle_proof.len(), &[leaf_index(&path_indices)?])?;
 
     // 3. Verify nullifier is in nullifiers_root using merkle proof
-    let computed_root = compute_merkle_root_with_indices(&nullifier, &merkle_proof, &path_indices);
+    let computed_root = compute_merkle_root_with_indices(&nullifier, &merkle_proof, &path_indices)?;
     require!(
         computed_root == nullifiers_root,
         PoolError::InvalidNullifierProof
@@ -69,6 +83,7 @@ pub fn handler(
 
     emit!(NullifierRecordedEvent {
         pool: pool.key(),
+        batch_sequence: settled_batch.sequence,
         nullifier,
         nullifiers_root,
         slot: Clock::get()?.slot,
@@ -84,14 +99,26 @@ pub fn handler(
     Ok(())
 }
 
+/// Position of a leaf from its path indices (bit i set = right child at level i)
+fn leaf_index(path_indices: &[u8]) -> Result<u32> {
+    path_indices
+        .iter()
+        .enumerate()
+        .try_fold(0u32, |index, (level, bit)| match bit {
+            0 => Ok(index),
+            1 => Ok(index | (1 << level)),
+            _ => err!(PoolError::InvalidNullifierProof),
+        })
+}
+
 /// Compute merkle root from leaf and proof path using path indices
-/// Uses Keccak256 for hashing (same as Noir circuit)
+/// Uses Poseidon2 for hashing (same as Noir circuit `primitives::merkle::hash_pair`)
 /// CRITICAL-02 FIX: Uses path indices (0/1 bits) for left/right ordering
-fn compute_merkle_root_with_indices(
+pub(crate) fn compute_merkle_root_with_indices(
     leaf: &[u8; 32],
     proof: &[[u8; 32]],
     path_indices: &[u8],
-) -> [u8; 32] {
+) -> Result<[u8; 32]> {
     let mut current = *leaf;
 
     for (i, sibling) in proof.iter().enumerate() {
@@ -105,14 +132,14 @@ fn compute_merkle_root_with_indices(
         // Matches Noir circuit: is_right = 0 means current is left, is_right = 1 means current is right
         current = if is_right {
             // Current is on the right, sibling is on the left
-            keccak::hash(&[&sibling[..], &current[..]].concat()).to_bytes()
+            poseidon2::hash_pair(sibling, &current)?
         } else {
             // Current is on the left, sibling is on the right
-            keccak::hash(&[&current[..], &sibling[..]].concat()).to_bytes()
+            poseidon2::hash_pair(&current, sibling)?
         };
     }
 
-    current
+    Ok(current)
 }
 
 /// Legacy merkle root computation (deprecated)
@@ -146,7 +173,7 @@ mod tests {
         let proof = vec![sibling1, sibling2];
         let path_indices = vec![0, 0]; // Both siblings on right
 
-        let root = compute_merkle_root_with_indices(&leaf, &proof, &path_indices);
+        let root = compute_merkle_root_with_indices(&leaf, &proof, &path_indices).unwrap();
 
         // Verify root is deterministic
         assert_eq!(root.len(), 32);
@@ -162,13 +189,43 @@ mod tests {
 
         // Test with is_right = 0 (left, sibling on right)
         let path_indices_left = vec![0, 0];
-        let root_left = compute_merkle_root_with_indices(&leaf, &proof, &path_indices_left);
+        let root_left =
+            compute_merkle_root_with_indices(&leaf, &proof, &path_indices_left).unwrap();
 
         // Test with is_right = 1 (right, sibling on left)
         let path_indices_right = vec![1, 1];
-        let root_right = compute_merkle_root_with_indices(&leaf, &proof, &path_indices_right);
+        let root_right =
+            compute_merkle_root_with_indices(&leaf, &proof, &path_indices_right).unwrap();
 
         // Roots should be different for different orderings
         assert_ne!(root_left, root_right);
     }
+
+    #[test]
+    fn test_leaf_index_from_path_indices() {
+        assert_eq!(leaf_index(&[]).unwrap(), 0);
+        assert_eq!(leaf_index(&[1, 0, 1]).unwrap(), 5);
+        assert_eq!(leaf_index(&[0, 1, 1, 0, 1, 1]).unwrap(), 54);
+        assert!(leaf_index(&[0, 2]).is_err());
+    }
+
+    #[test]
+    fn test_compute_merkle_root_matches_noir_hash_pair() {
+        // Poseidon2::hash([1, 2], 2) from test_poseidon2_compat.nr
+        let mut leaf = [0u8; 32];
+        leaf[31] = 1;
+        let mut sibling = [0u8; 32];
+        sibling[31] = 2;
+
+        let root = compute_merkle_root_with_indices(&leaf, &[sibling], &[0]).unwrap();
+
+        assert_eq!(
+            root,
+            [
+                0x03, 0x86, 0x82, 0xaa, 0x1c, 0xb5, 0xae, 0x4e, 0x0a, 0x3f, 0x13, 0xda, 0x43, 0x2a,
+                0x95, 0xc7, 0x7c, 0x5c, 0x11, 0x1f, 0x6f, 0x03, 0x0f, 0xaf, 0x9c, 0xad, 0x64, 0x1c,
+                0xe1, 0xed, 0x73, 0x83,
+            ]
+        );
+    }
 }
@@ -0,0 +1,389 @@
+use crate::errors::PoolError;
+use crate::events::NullifiersBatchRecordedEvent;
+use crate::poseidon2;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Allocate, Assign, CreateAccount};
+
+/// Maximum nullifiers recorded per instruction
+///
+/// Bounded by compute (one Poseidon2 hash per multi-proof node plus one
+/// account creation per nullifier) and by transaction size (each nullifier
+/// needs its PDA passed in `remaining_accounts`). The compute bound is
+/// checked by `record_nullifiers_batch_fits_compute_budget` in
+/// tests/compute_units.rs, which records a full batch at
+/// MAX_NULLIFIER_TREE_DEPTH with leaves spread out to need the most hashes.
+pub const MAX_NULLIFIERS_PER_RECORD_BATCH: usize = 8;
+
+/// Maximum depth of a settled batch's nullifier tree
+/// 2^6 = 64 leaves covers the largest batch circuit (batch_64)
+pub const MAX_NULLIFIER_TREE_DEPTH: u8 = 6;
+
+/// Record Nullifiers Batch Context
+///
+/// Records several nullifiers from one settled batch in a single instruction,
+/// shrinking the window in which settled nullifiers are not yet marked as spent.
+///
+/// REMAINING ACCOUNTS:
+/// - One writable nullifier PDA per nullifier, in the same order as `nullifiers`
+///   (seeds: ["nullifier", pool, nullifier])
+#[derive(Accounts)]
+pub struct RecordNullifiersBatch<'info> {
+    /// Pool state
+    #[account(
+        seeds = [b"pool", pool.token_mint.as_ref()],
+        bump = pool.bump,
+    )]
+    pub pool: Account<'info, PoolState>,
+
+    /// Settled batch the nullifiers belong to (any batch still pending)
+    #[account(
+        mut,
+        seeds = [SETTLED_BATCH_SEED, pool.key().as_ref(), &settled_batch.sequence.to_le_bytes()],
+        bump = settled_batch.bump,
+    )]
+    pub settled_batch: Account<'info, SettledBatch>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Record many nullifiers against a pending settled batch
+///
+/// SECURITY (CRITICAL-04): Every nullifier is proven to be a leaf of
+/// nullifiers_root with a single multi-proof, and each nullifier PDA is
+/// created exactly once (existing PDAs are rejected as double-spends).
+///
+/// # Arguments
+/// * `nullifiers` - Leaves to record, ordered by strictly increasing leaf index
+/// * `leaf_indices` - Position of each nullifier in the batch nullifier tree
+/// * `proof` - Multi-proof sibling nodes, bottom-up and left-to-right
+/// * `depth` - Depth of the batch nullifier tree (must equal `SettledBatch.depth`)
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, RecordNullifiersBatch<'info>>,
+    nullifiers_root: [u8; 32],
+    nullifiers: Vec<[u8; 32]>,
+    leaf_indices: Vec<u32>,
+    proof: Vec<[u8; 32]>,
+    depth: u8,
+) -> Result<()> {
+    let pool = &ctx.accounts.pool;
+
+    // 1. Verify nullifiers_root matches the settled batch
+    require!(
+        ctx.accounts.settled_batch.nullifiers_root == nullifiers_root,
+        PoolError::InvalidNullifierProof
+    );
+
+    // 2. Bound the batch by compute and validate argument shapes
+    require!(
+        !nullifiers.is_empty() && nullifiers.len() <= MAX_NULLIFIERS_PER_RECORD_BATCH,
+        PoolError::NullifierBatchTooLarge
+    );
+    require!(
+        leaf_indices.len() == nullifiers.len() && ctx.remaining_accounts.len() == nullifiers.len(),
+        PoolError::InvalidNullifierProof
+    );
+
+    // 3. Count the leaves against the batch: proven at the batch tree depth,
+    // below nullifier_count, and never more than nullifier_count in total.
+    // Also bounds `depth` before the multi-proof walks it.
+    // An invalid proof below reverts the count.
+    ctx.accounts
+        .settled_batch
+        .record(depth as usize, &leaf_indices)?;
+
+    // 4. Verify all nullifiers are in nullifiers_root using one multi-proof
+    let computed_root = compute_multiproof_root(&nullifiers, &leaf_indices, &proof, depth)?;
+    require!(
+        computed_root == nullifiers_root,
+        PoolError::InvalidNullifierProof
+    );
+
+    msg!(
+        "Nullifier multi-proof verified: {} leaves, root={:?}",
+        nullifiers.len(),
+        computed_root
+    );
+
+    // 5. Create one nullifier PDA per nullifier (existing PDA = already spent)
+    let pool_key = pool.key();
+    let slot = Clock::get()?.slot;
+    for (nullifier, entry_info) in nullifiers.iter().zip(ctx.remaining_accounts.iter()) {
+        create_nullifier_entry(
+            &pool_key,
+            nullifier,
+            entry_info,
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            ctx.program_id,
+            slot,
+        )?;
+    }
+
+    emit!(NullifiersBatchRecordedEvent {
+        pool: pool_key,
+        batch_sequence: ctx.accounts.settled_batch.sequence,
+        nullifiers_root,
+        nullifiers: nullifiers.clone(),
+        slot,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Recorded {} nullifiers at slot {}", nullifiers.len(), slot);
+
+    Ok(())
+}
+
+/// Create and initialize a NullifierEntry PDA by hand
+///
+/// Equivalent to Anchor's `init` constraint for accounts passed through
+/// `remaining_accounts`, including the pre-funded PDA case (someone sending
+/// lamports to the address must not block recording).
+fn create_nullifier_entry<'info>(
+    pool: &Pubkey,
+    nullifier: &[u8; 32],
+    entry_info: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program_info: &AccountInfo<'info>,
+    program_id: &Pubkey,
+    slot: u64,
+) -> Result<()> {
+    let (expected_key, bump) =
+        Pubkey::find_program_address(&NullifierEntry::seeds(pool, nullifier), program_id);
+    require_keys_eq!(
+        entry_info.key(),
+        expected_key,
+        PoolError::InvalidNullifierProof
+    );
+
+    // SECURITY: An initialized PDA means this nullifier was already spent
+    require!(
+        entry_info.owner == &system_program::ID && entry_info.data_is_empty(),
+        PoolError::NullifierAlreadyUsed
+    );
+
+    let bump_seed = [bump];
+    let signer_seeds: &[&[u8]] = &[b"nullifier", pool.as_ref(), nullifier, &bump_seed];
+    let rent = Rent::get()?.minimum_balance(NullifierEntry::SIZE);
+    let current_lamports = entry_info.lamports();
+
+    if current_lamports == 0 {
+        system_program::create_account(
+            CpiContext::new_with_signer(
+                system_program_info.clone(),
+                CreateAccount {
+                    from: payer.clone(),
+                    to: entry_info.clone(),
+                },
+                &[signer_seeds],
+            ),
+            rent,
+            NullifierEntry::SIZE as u64,
+            program_id,
+        )?;
+    } else {
+        let top_up = rent.saturating_sub(current_lamports);
+        if top_up > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    system_program_info.clone(),
+                    system_program::Transfer {
+                        from: payer.clone(),
+                        to: entry_info.clone(),
+                    },
+                ),
+                top_up,
+            )?;
+        }
+        system_program::allocate(
+            CpiContext::new_with_signer(
+                system_program_info.clone(),
+                Allocate {
+                    account_to_allocate: entry_info.clone(),
+                },
+                &[signer_seeds],
+            ),
+            NullifierEntry::SIZE as u64,
+        )?;
+        system_program::assign(
+            CpiContext::new_with_signer(
+                system_program_info.clone(),
+                Assign {
+                    account_to_assign: entry_info.clone(),
+                },
+                &[signer_seeds],
+            ),
+            program_id,
+        )?;
+    }
+
+    let entry = NullifierEntry {
+        nullifier: *nullifier,
+        slot,
+        bump,
+    };
+    let mut data = entry_info.try_borrow_mut_data()?;
+    entry.try_serialize(&mut &mut data[..])?;
+
+    Ok(())
+}
+
+/// Compute the merkle root from several leaves and a shared multi-proof
+///
+/// Processes the tree level by level. At each level, a node whose sibling is
+/// also known (another proven leaf or an already computed parent) is paired
+/// with it directly; otherwise the next node is taken from `proof`.
+/// The proof must be consumed exactly.
+///
+/// Uses Poseidon2 for hashing (same as Noir circuit `primitives::merkle::hash_pair`)
+pub(crate) fn compute_multiproof_root(
+    leaves: &[[u8; 32]],
+    leaf_indices: &[u32],
+    proof: &[[u8; 32]],
+    depth: u8,
+) -> Result<[u8; 32]> {
+    require!(
+        !leaves.is_empty() && leaves.len() == leaf_indices.len(),
+        PoolError::InvalidNullifierProof
+    );
+
+    // Leaf indices must be strictly increasing and inside the tree
+    let leaf_count = 1u64 << depth;
+    for (i, index) in leaf_indices.iter().enumerate() {
+        require!(
+            (*index as u64) < leaf_count,
+            PoolError::InvalidNullifierProof
+        );
+        if i > 0 {
+            require!(
+                leaf_indices[i - 1] < *index,
+                PoolError::InvalidNullifierProof
+            );
+        }
+    }
+
+    let mut layer: Vec<(u32, [u8; 32])> = leaf_indices
+        .iter()
+        .copied()
+        .zip(leaves.iter().copied())
+        .collect();
+    let mut proof_nodes = proof.iter();
+
+    for _ in 0..depth {
+        let mut next_layer = Vec::with_capacity(layer.len());
+        let mut i = 0;
+
+        while i < layer.len() {
+            let (index, node) = layer[i];
+
+            let parent = if index % 2 == 0 {
+                // Node is a left child: sibling is the next known node or comes from the proof
+                match layer.get(i + 1) {
+                    Some((sibling_index, sibling)) if *sibling_index == index + 1 => {
+                        i += 1;
+                        poseidon2::hash_pair(&node, sibling)?
+                    }
+                    _ => {
+                        let sibling = proof_nodes.next().ok_or(PoolError::InvalidNullifierProof)?;
+                        poseidon2::hash_pair(&node, sibling)?
+                    }
+                }
+            } else {
+                // Node is a right child whose left sibling was not proven: take it from the proof
+                let sibling = proof_nodes.next().ok_or(PoolError::InvalidNullifierProof)?;
+                poseidon2::hash_pair(sibling, &node)?
+            };
+
+            next_layer.push((index / 2, parent));
+            i += 1;
+        }
+
+        layer = next_layer;
+    }
+
+    require!(
+        proof_nodes.next().is_none() && layer.len() == 1,
+        PoolError::InvalidNullifierProof
+    );
+
+    Ok(layer[0].1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Circuit-side tree, whose tests assert the root of `tree()` below
+    const NOIR_MERKLE: &str =
+        include_str!("../../../../../noir-circuits/circuits/src/primitives/merkle.nr");
+
+    fn leaf(value: u8) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        bytes[31] = value;
+        bytes
+    }
+
+    /// Depth-2 tree over leaves [1, 2, 3, 4]
+    fn tree() -> ([[u8; 32]; 4], [u8; 32], [u8; 32], [u8; 32]) {
+        let leaves = [leaf(1), leaf(2), leaf(3), leaf(4)];
+        let left = poseidon2::hash_pair(&leaves[0], &leaves[1]).unwrap();
+        let right = poseidon2::hash_pair(&leaves[2], &leaves[3]).unwrap();
+        let root = poseidon2::hash_pair(&left, &right).unwrap();
+        (leaves, left, right, root)
+    }
+
+    #[test]
+    fn test_multiproof_all_leaves_needs_no_proof() {
+        let (leaves, _, _, root) = tree();
+        let computed = compute_multiproof_root(&leaves, &[0, 1, 2, 3], &[], 2).unwrap();
+        assert_eq!(computed, root);
+    }
+
+    #[test]
+    fn test_multiproof_sibling_pair() {
+        let (leaves, _, right, root) = tree();
+        let computed = compute_multiproof_root(&leaves[0..2], &[0, 1], &[right], 2).unwrap();
+        assert_eq!(computed, root);
+    }
+
+    #[test]
+    fn test_multiproof_non_sibling_leaves() {
+        let (leaves, _, _, root) = tree();
+        // Leaves 1 and 2 need both outer leaves, bottom-up and left-to-right
+        let computed =
+            compute_multiproof_root(&leaves[1..3], &[1, 2], &[leaves[0], leaves[3]], 2).unwrap();
+        assert_eq!(computed, root);
+    }
+
+    #[test]
+    fn test_multiproof_single_leaf_matches_single_proof() {
+        let (leaves, _, right, root) = tree();
+        let computed =
+            compute_multiproof_root(&leaves[1..2], &[1], &[leaves[0], right], 2).unwrap();
+        assert_eq!(computed, root);
+    }
+
+    #[test]
+    fn test_multiproof_rejects_unsorted_indices() {
+        let (leaves, _, right, _) = tree();
+        let swapped = [leaves[1], leaves[0]];
+        assert!(compute_multiproof_root(&swapped, &[1, 0], &[right], 2).is_err());
+    }
+
+    #[test]
+    fn test_multiproof_rejects_unused_proof_nodes() {
+        let (leaves, _, _, _) = tree();
+        assert!(compute_multiproof_root(&leaves, &[0, 1, 2, 3], &[leaf(9)], 2).is_err());
+    }
+
+    #[test]
+    fn test_tree_root_matches_noir() {
+        // The batch circuit commits to nullifiers_root with compute_tree_root
+        let (_, _, _, root) = tree();
+        let hex: String = root.iter().map(|b| format!("{:02x}", b)).collect();
+        assert!(NOIR_MERKLE.contains(&format!("== 0x{}", hex)));
+    }
+}
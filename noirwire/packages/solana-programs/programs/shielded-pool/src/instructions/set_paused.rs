@@ -3,26 +3,53 @@ use crate::events::{EmergencyModeEvent, EmergencyPauseEvent};
 use crate::state::*;
 use anchor_lang::prelude::*;
 
+/// Pause Context
+///
+/// The pool admin can pause and unpause. Guardians (see PoolState.guardians)
+/// can only pause, so monitors can hold a hot key that halts the pool on an
+/// anomaly without being able to resume it or move funds.
 #[derive(Accounts)]
 pub struct SetPaused<'info> {
     #[account(
         mut,
         seeds = [b"pool", pool.token_mint.as_ref()],
-        bump = pool.bump,
-        has_one = authority @ PoolError::Unauthorized
+        bump = pool.bump
     )]
     pub pool: Account<'info, PoolState>,
 
-    pub authority: Signer<'info>,
+    /// Pool admin (a signer, or the pool's AdminMultisig with co-signers as
+    /// remaining accounts), or a signing guardian
+    /// CHECK: Authorized in the handler against pool.authority / pool.guardians
+    pub authority: UncheckedAccount<'info>,
 }
 
 pub fn handler(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+    let authority = &ctx.accounts.authority;
     let pool = &mut ctx.accounts.pool;
+
+    if authority.key() == pool.authority {
+        require_admin(authority, ctx.remaining_accounts)?;
+    } else {
+        // SECURITY: Guardians can only pause
+        require!(
+            authority.is_signer && pool.is_guardian(&authority.key()),
+            PoolError::Unauthorized
+        );
+        require!(paused, PoolError::Unauthorized);
+    }
+
+    // Emergency mode must be disabled before the pool can resume
+    require!(
+        paused || !pool.emergency_mode,
+        PoolError::EmergencyModeActive
+    );
+
     pool.paused = paused;
 
     emit!(EmergencyPauseEvent {
         pool: pool.key(),
         paused,
+        paused_by: authority.key(),
         timestamp: Clock::get()?.unix_timestamp,
     });
 
@@ -33,7 +60,8 @@ pub fn handler(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
 /// Emergency Mode Context
 ///
 /// SECURITY (LOW-01): Emergency withdrawal mechanism
-/// When enabled, allows users to recover funds without ZK proofs
+/// When enabled, users can recover funds by opening their commitment against
+/// the frozen root, without the admin or a ZK proof.
 /// This is a last-resort mechanism for catastrophic failure scenarios
 #[derive(Accounts)]
 pub struct SetEmergencyMode<'info> {
@@ -45,36 +73,64 @@ pub struct SetEmergencyMode<'info> {
     )]
     pub pool: Account<'info, PoolState>,
 
-    pub authority: Signer<'info>,
+    /// Pool admin: a signer, or the pool's AdminMultisig with co-signers as
+    /// remaining accounts
+    /// CHECK: Matched against pool.authority, authorized by require_admin
+    pub authority: UncheckedAccount<'info>,
 }
 
 /// Enable or disable emergency mode
 ///
 /// SECURITY (LOW-01): Emergency withdrawal mechanism
-/// - When enabled, `emergency_withdraw` instruction becomes available
+/// - Enabling freezes the current commitment root; `emergency_claim` opens
+///   EMERGENCY_CLAIM_DELAY_SLOTS later
 /// - Should only be enabled if ZK circuits are broken or compromised
-/// - Enables users to recover funds without proofs
 /// - Pool must also be paused to prevent normal operations
+/// - Cannot be enabled while already enabled, so the timelock and frozen
+///   root only reset through an explicit disable
+/// - Cannot be disabled once any claim was paid out (the tree no longer
+///   reflects the vault)
 pub fn set_emergency_mode_handler(
     ctx: Context<SetEmergencyMode>,
     emergency_mode: bool,
 ) -> Result<()> {
+    require_admin(&ctx.accounts.authority, ctx.remaining_accounts)?;
     let pool = &mut ctx.accounts.pool;
+    let current_slot = Clock::get()?.slot;
 
-    // Emergency mode can only be enabled when pool is paused
     if emergency_mode {
+        // Emergency mode can only be enabled when pool is paused
         require!(pool.paused, PoolError::PoolPaused);
+
+        // SECURITY: Enabling again would restart the timelock users are
+        // waiting out; the admin must disable first (only possible before
+        // any claim)
+        require!(!pool.emergency_mode, PoolError::EmergencyModeAlreadyActive);
+
+        pool.emergency_root = pool.commitment_root;
+        pool.emergency_activated_slot = current_slot;
+    } else {
+        require!(pool.emergency_claims == 0, PoolError::EmergencyClaimsMade);
     }
 
     pool.emergency_mode = emergency_mode;
 
+    let claims_open_slot = pool
+        .emergency_activated_slot
+        .saturating_add(EMERGENCY_CLAIM_DELAY_SLOTS);
     emit!(EmergencyModeEvent {
         pool: pool.key(),
         emergency_mode,
         authority: ctx.accounts.authority.key(),
+        emergency_root: pool.emergency_root,
+        claims_open_slot,
         timestamp: Clock::get()?.unix_timestamp,
     });
 
-    msg!("Pool emergency mode: {}", emergency_mode);
+    msg!(
+        "Pool emergency mode: {} (claims open at slot {})",
+        emergency_mode,
+        claims_open_slot
+    );
     Ok(())
 }
@@ -1,12 +1,22 @@
 use crate::errors::PoolError;
-use crate::events::BatchSettlementEvent;
+use crate::events::{BatchSettlementEvent, OutflowLimitExceededEvent};
 use crate::state::*;
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
 use zk_verifier::cpi;
-use zk_verifier::cpi::accounts::VerifyProof;
+use zk_verifier::cpi::accounts::VerifyPrepared;
 use zk_verifier::program::ZkVerifier;
-use zk_verifier::state::VerificationKey;
+use zk_verifier::registry::{CircuitRegistry, CIRCUIT_REGISTRY_SEED};
+use zk_verifier::state::{PreparedVerificationKey, VerificationKey, PREPARED_VK_SEED};
 
+/// Batch Settlement Context
+///
+/// REMAINING ACCOUNTS:
+/// - One writable recipient account per entry in `withdrawals`, in the same order:
+///   - SPL pools: token account owned by `BatchWithdrawal.recipient`
+///   - Native SOL pools: the `BatchWithdrawal.recipient` wallet itself
+/// - Then any Token-2022 transfer-hook extra accounts, forwarded to every payout
 #[derive(Accounts)]
 #[instruction(proof_data: BatchSettlementProofData)]
 pub struct SettleBatch<'info> {
@@ -24,15 +34,48 @@ pub struct SettleBatch<'info> {
     /// SECURITY: Verified to be for this pool and batch settlement circuit
     #[account(
         constraint = verification_key.pool == pool.key() @ PoolError::InvalidVerificationKey,
-        constraint = verification_key.circuit_id == proof::circuit_ids::BATCH_SETTLEMENT @ PoolError::InvalidVerificationKey
+        constraint = circuit_registry.is_registered_as(
+            &verification_key.circuit_id,
+            proof::circuit_ids::names::BATCH_SETTLEMENT
+        ) @ PoolError::InvalidVerificationKey
     )]
     pub verification_key: Account<'info, VerificationKey>,
 
+    /// Verify-ready copy of verification_key, read zero-copy by verify_prepared
+    #[account(
+        seeds = [PREPARED_VK_SEED, verification_key.key().as_ref()],
+        seeds::program = zk_verifier::ID,
+        bump = prepared_vk.load()?.bump
+    )]
+    pub prepared_vk: AccountLoader<'info, PreparedVerificationKey>,
+
+    /// Pool's circuit registry (zk-verifier), resolves which circuit IDs are
+    /// batch settlement circuits
+    #[account(
+        seeds = [CIRCUIT_REGISTRY_SEED, pool.key().as_ref()],
+        seeds::program = zk_verifier::ID,
+        bump = circuit_registry.bump
+    )]
+    pub circuit_registry: Account<'info, CircuitRegistry>,
+
     /// ZK Verifier program (for CPI verification)
     pub verifier_program: Program<'info, ZkVerifier>,
 
+    /// Settled batch record (one PDA per batch sequence number)
+    /// Lets record_nullifier accept nullifiers from any batch still pending
+    #[account(
+        init,
+        payer = per_authority,
+        space = 8 + SettledBatch::INIT_SPACE,
+        seeds = [SETTLED_BATCH_SEED, pool.key().as_ref(), &pool.batch_sequence.to_le_bytes()],
+        bump
+    )]
+    pub settled_batch: Account<'info, SettledBatch>,
+
     /// PER authority (MagicBlock delegation)
     /// SECURITY: Only this authorized PER can call settle_batch (CRITICAL-05)
+    /// Pays rent for the SettledBatch PDA
+    #[account(mut)]
     pub per_authority: Signer<'info>,
 
     /// Historical roots PDA for extended spending window (optional for production)
@@ -46,10 +89,44 @@ pub struct SettleBatch<'info> {
     )]
     pub historical_roots: Option<Account<'info, HistoricalRoots>>,
 
+    /// Pool's token vault (source of batch withdrawal payouts, SPL pools only)
+    #[account(
+        mut,
+        seeds = [b"vault", pool.key().as_ref()],
+        bump
+    )]
+    pub pool_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Pool authority PDA (for signing vault transfers)
+    /// CHECK: PDA verified by seeds
+    #[account(
+        seeds = [b"authority", pool.key().as_ref()],
+        bump
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    /// SOL vault PDA (source of payouts for native SOL pools)
+    #[account(
+        mut,
+        seeds = [SOL_VAULT_SEED, pool.key().as_ref()],
+        bump
+    )]
+    pub sol_vault: Option<SystemAccount<'info>>,
+
+    /// Pool's token mint (needed by transfer_checked, SPL pools only)
+    #[account(address = pool.token_mint @ PoolError::InvalidMint)]
+    pub token_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// SPL Token or Token-2022 program
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler(ctx: Context<SettleBatch>, proof_data: BatchSettlementProofData) -> Result<()> {
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SettleBatch<'info>>,
+    proof_data: BatchSettlementProofData,
+    withdrawals: Vec<BatchWithdrawal>,
+) -> Result<()> {
     let pool = &mut ctx.accounts.pool;
     let current_slot = Clock::get()?.slot;
 
@@ -57,12 +134,17 @@ pub fn handler(ctx: Context<SettleBatch>, proof_data: BatchSettlementProofData)
     let new_root = proof_data.new_root;
     let nullifiers_root = proof_data.nullifiers_root;
     let nullifier_count = field_to_u32(&proof_data.nullifier_count)?;
+    let nullifier_depth = nullifier_tree_depth(nullifier_count)?;
 
     // SECURITY (CRITICAL-03): Verify batch ZK proof
     // The batch circuit proves:
     // - All nullifiers are valid (double-spend prevention)
     // - State transition from old_root to new_root is correct
-    // - nullifiers_root is the merkle root of all batch nullifiers
+    // - nullifiers_root is the merkle root of all batch nullifiers, and
+    //   nullifier_count is the batch size
+    // - withdrawals_hash commits to the public withdrawals paid out below, each
+    //   of which spends in full a balance nullified in this batch
+    //   (noir-circuits batch_N packages, public inputs in this order)
 
     // 1. Verify old_root in proof matches current pool root
     require!(
@@ -70,35 +152,194 @@ pub fn handler(ctx: Context<SettleBatch>, proof_data: BatchSettlementProofData)
         PoolError::InvalidMerkleRoot
     );
 
+    // 2. Bind the payout list to the proof's withdrawals_hash public input
+    require!(
+        withdrawals.len() <= MAX_BATCH_WITHDRAWALS,
+        PoolError::TooManyBatchWithdrawals
+    );
+    require!(
+        ctx.remaining_accounts.len() >= withdrawals.len(),
+        PoolError::InvalidRecipient
+    );
+    let (recipient_accounts, hook_accounts) = ctx.remaining_accounts.split_at(withdrawals.len());
+    require!(
+        compute_withdrawals_hash(&withdrawals)? == proof_data.withdrawals_hash,
+        PoolError::InvalidWithdrawalsHash
+    );
+
+    // 3. SECURITY (HIGH-02): Verify VK hash is one the pool accepts for this circuit
+    let vk_data = ctx.accounts.verification_key.try_to_vec()?;
+    let vk_hash = keccak::hash(&vk_data);
+    pool.require_vk(
+        &ctx.accounts.verification_key.circuit_id,
+        &vk_hash.to_bytes(),
+        current_slot,
+    )?;
+
     msg!("Verifying batch settlement proof (estimated 600k CU)");
 
-    // 2. Verify batch ZK proof via CPI to zk-verifier program
+    // 4. Verify batch ZK proof via CPI to zk-verifier (verify_prepared on the key hashed above)
     let verify_cpi_ctx = CpiContext::new(
         ctx.accounts.verifier_program.to_account_info(),
-        VerifyProof {
-            verification_key: ctx.accounts.verification_key.to_account_info(),
+        VerifyPrepared {
+            prepared_vk: ctx.accounts.prepared_vk.to_account_info(),
         },
     );
 
     let public_inputs = proof_data.public_inputs();
-    cpi::verify(verify_cpi_ctx, proof_data.proof, public_inputs)?;
+    cpi::verify_prepared(verify_cpi_ctx, proof_data.proof, public_inputs)?;
 
     msg!("Batch ZK proof verified successfully");
 
-    // 3. Store nullifiers_root for verification by record_nullifier
+    // 5. Circuit breaker: pause instead of settling past the outflow limit
+    let batch_outflow = withdrawals
+        .iter()
+        .try_fold(0u64, |total, w| total.checked_add(w.amount))
+        .ok_or(PoolError::Overflow)?;
+    if !pool.record_outflow(batch_outflow, current_slot) {
+        pool.paused = true;
+        emit!(OutflowLimitExceededEvent {
+            pool: pool.key(),
+            attempted_amount: batch_outflow,
+            epoch_outflow: pool.outflow_epoch_amount,
+            epoch_limit: pool.outflow_epoch_limit(),
+            epoch_start_slot: pool.outflow_epoch_start_slot,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        msg!("Outflow limit exceeded: pool paused, batch not settled");
+
+        // Release the SettledBatch PDA so the batch can be resubmitted once resumed
+        return ctx
+            .accounts
+            .settled_batch
+            .close(ctx.accounts.per_authority.to_account_info());
+    }
+
+    // 6. Store nullifiers_root for verification by record_nullifier
     // Individual nullifier PDAs are created by the indexer/PER in separate txs
+    // The SettledBatch PDA keeps this batch recordable after later settlements
+    let batch_sequence = pool.batch_sequence;
+    let settled_batch = &mut ctx.accounts.settled_batch;
+    settled_batch.pool = pool.key();
+    settled_batch.sequence = batch_sequence;
+    settled_batch.nullifiers_root = nullifiers_root;
+    settled_batch.nullifier_count = nullifier_count;
+    settled_batch.depth = nullifier_depth;
+    settled_batch.recorded_count = 0;
+    settled_batch.settled_slot = current_slot;
+    settled_batch.bump = ctx.bumps.settled_batch;
+
+    pool.batch_sequence = batch_sequence.checked_add(1).ok_or(PoolError::Overflow)?;
     pool.last_nullifiers_root = nullifiers_root;
     pool.total_nullifiers = pool
         .total_nullifiers
         .checked_add(nullifier_count as u64)
         .ok_or(PoolError::Overflow)?;
 
-    // 4. Update pool state with new root (only after proof verification)
+    // 7. Pay out the batch's public withdrawals from the vault
+    // SECURITY (CRITICAL-07): Same balance checks as withdraw::handler, per payout
+    let pool_key = pool.key();
+    let authority_seeds = &[b"authority", pool_key.as_ref(), &[ctx.bumps.pool_authority]];
+    let signer_seeds = &[&authority_seeds[..]];
+
+    let mut withdrawn_amount: u64 = 0;
+    for (withdrawal, recipient_info) in withdrawals.iter().zip(recipient_accounts.iter()) {
+        pool.check_denomination(withdrawal.amount)?;
+        require!(
+            pool.total_shielded >= withdrawal.amount,
+            PoolError::InsufficientPoolBalance
+        );
+
+        if pool.native_sol {
+            // Native SOL: pay lamports straight to the recipient wallet
+            let sol_vault = ctx
+                .accounts
+                .sol_vault
+                .as_ref()
+                .ok_or(PoolError::InvalidPoolMode)?;
+            let sol_vault_bump = ctx.bumps.sol_vault.ok_or(PoolError::InvalidPoolMode)?;
+            require!(
+                recipient_info.key() == withdrawal.recipient,
+                PoolError::InvalidRecipient
+            );
+
+            transfer_from_sol_vault(
+                sol_vault,
+                recipient_info,
+                &ctx.accounts.system_program.to_account_info(),
+                &pool_key,
+                sol_vault_bump,
+                withdrawal.amount,
+            )?;
+        } else {
+            let recipient_token_account =
+                InterfaceAccount::<TokenAccount>::try_from(recipient_info)?;
+            require!(
+                recipient_token_account.mint == pool.token_mint,
+                PoolError::InvalidMint
+            );
+            require!(
+                recipient_token_account.owner == withdrawal.recipient,
+                PoolError::InvalidRecipient
+            );
+            let token_mint = ctx
+                .accounts
+                .token_mint
+                .as_ref()
+                .ok_or(PoolError::InvalidPoolMode)?;
+            let pool_vault = ctx
+                .accounts
+                .pool_vault
+                .as_mut()
+                .ok_or(PoolError::InvalidPoolMode)?;
+            require!(
+                pool_vault.amount >= withdrawal.amount,
+                PoolError::InsufficientVaultBalance
+            );
+
+            let transfer_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: pool_vault.to_account_info(),
+                    mint: token_mint.to_account_info(),
+                    to: recipient_info.clone(),
+                    authority: ctx.accounts.pool_authority.to_account_info(),
+                },
+                signer_seeds,
+            );
+            token_interface::transfer_checked(
+                transfer_ctx.with_remaining_accounts(hook_accounts.to_vec()),
+                withdrawal.amount,
+                token_mint.decimals,
+            )?;
+            pool_vault.reload()?;
+        }
+
+        pool.total_shielded = pool
+            .total_shielded
+            .checked_sub(withdrawal.amount)
+            .ok_or(PoolError::Underflow)?;
+        pool.total_withdrawals = pool
+            .total_withdrawals
+            .checked_add(1)
+            .ok_or(PoolError::Overflow)?;
+        withdrawn_amount = withdrawn_amount
+            .checked_add(withdrawal.amount)
+            .ok_or(PoolError::Overflow)?;
+
+        msg!(
+            "Batch withdrawal: {} tokens to {}",
+            withdrawal.amount,
+            withdrawal.recipient
+        );
+    }
+
+    // 8. Update pool state with new root (only after proof verification)
     // SECURITY (HIGH-01): Pass current slot for root expiration tracking
     let old_root = pool.commitment_root;
     pool.update_root(new_root, current_slot);
 
-    // 5. SECURITY (CRITICAL-02): Also push to HistoricalRoots PDA if available
+    // 9. SECURITY (CRITICAL-02): Also push to HistoricalRoots PDA if available
     // This provides the extended 900-slot (~6 min) spending window
     if let Some(ref mut historical_roots) = ctx.accounts.historical_roots {
         // Verify the historical roots account belongs to this pool
@@ -111,13 +352,16 @@ pub fn handler(ctx: Context<SettleBatch>, proof_data: BatchSettlementProofData)
         msg!("Root pushed to extended historical buffer (900-slot capacity)");
     }
 
-    // 6. Emit event with nullifiers_root (indexer will process individual nullifiers)
+    // 10. Emit event with nullifiers_root (indexer will process individual nullifiers)
     emit!(BatchSettlementEvent {
         pool: pool.key(),
+        batch_sequence,
         old_root,
         new_root,
         nullifiers_root,
         nullifier_count,
+        withdrawal_count: withdrawals.len() as u32,
+        withdrawn_amount,
         timestamp: Clock::get()?.unix_timestamp,
     });
 
@@ -0,0 +1,190 @@
+use crate::errors::PoolError;
+use crate::events::TransferEvent;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak;
+use zk_verifier::cpi;
+use zk_verifier::cpi::accounts::VerifyPrepared;
+use zk_verifier::program::ZkVerifier;
+use zk_verifier::registry::{CircuitRegistry, CIRCUIT_REGISTRY_SEED};
+use zk_verifier::state::{PreparedVerificationKey, VerificationKey, PREPARED_VK_SEED};
+
+/// Private Transfer Context
+///
+/// Settles a single private-to-private payment directly on L1.
+/// Normally transfers happen inside the PER and reach L1 through `settle_batch`;
+/// this instruction lets users keep transacting when the rollup is unavailable.
+///
+/// No tokens move: the sender's balance is nullified and the remainder and
+/// receiver commitments are inserted into the commitment tree.
+#[derive(Accounts)]
+#[instruction(proof_data: TransferProofData)]
+pub struct Transfer<'info> {
+    /// Pool state
+    #[account(
+        mut,
+        seeds = [b"pool", pool.token_mint.as_ref()],
+        bump = pool.bump,
+        constraint = !pool.paused @ PoolError::PoolPaused
+    )]
+    pub pool: Account<'info, PoolState>,
+
+    /// Nullifier PDA for the sender's spent balance
+    /// SECURITY: init constraint prevents double-spend by rejecting duplicate nullifiers
+    #[account(
+        init,
+        payer = payer,
+        space = NullifierEntry::SIZE,
+        seeds = [b"nullifier", pool.key().as_ref(), &proof_data.nullifier],
+        bump
+    )]
+    pub nullifier_entry: Account<'info, NullifierEntry>,
+
+    /// Verification key account (for ZK proof verification)
+    /// SECURITY: Verified to be for this pool and transfer circuit
+    #[account(
+        constraint = verification_key.pool == pool.key() @ PoolError::InvalidVerificationKey,
+        constraint = circuit_registry.is_registered_as(
+            &verification_key.circuit_id,
+            proof::circuit_ids::names::TRANSFER
+        ) @ PoolError::InvalidVerificationKey
+    )]
+    pub verification_key: Account<'info, VerificationKey>,
+
+    /// Verify-ready copy of verification_key, read zero-copy by verify_prepared
+    #[account(
+        seeds = [PREPARED_VK_SEED, verification_key.key().as_ref()],
+        seeds::program = zk_verifier::ID,
+        bump = prepared_vk.load()?.bump
+    )]
+    pub prepared_vk: AccountLoader<'info, PreparedVerificationKey>,
+
+    /// Pool's circuit registry (zk-verifier), resolves which circuit IDs are
+    /// transfer circuits
+    #[account(
+        seeds = [CIRCUIT_REGISTRY_SEED, pool.key().as_ref()],
+        seeds::program = zk_verifier::ID,
+        bump = circuit_registry.bump
+    )]
+    pub circuit_registry: Account<'info, CircuitRegistry>,
+
+    /// ZK Verifier program (for CPI verification)
+    pub verifier_program: Program<'info, ZkVerifier>,
+
+    /// Payer for nullifier account creation
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Historical roots PDA for extended spending window (optional for production)
+    /// SECURITY (CRITICAL-02): Provides 900-slot (~6 min) spending window
+    /// If provided, root validation also checks this extended buffer and the
+    /// replaced root is pushed here
+    #[account(
+        mut,
+        seeds = [HISTORICAL_ROOTS_SEED, pool.key().as_ref()],
+        bump,
+    )]
+    pub historical_roots: Option<Account<'info, HistoricalRoots>>,
+
+    /// Pool config PDA (root-age window; mandatory once initialized)
+    #[account(
+        seeds = [POOL_CONFIG_SEED, pool.key().as_ref()],
+        bump = pool_config.bump,
+    )]
+    pub pool_config: Option<Account<'info, PoolConfig>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<Transfer>, proof_data: TransferProofData) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    let nullifier = proof_data.nullifier;
+    let current_slot = Clock::get()?.slot;
+
+    // 1. SECURITY (CRITICAL-02 + HIGH-01): Validate old_root with expiration enforcement
+    let max_root_age_slots =
+        PoolConfig::max_root_age_slots(pool, ctx.accounts.pool_config.as_deref())?;
+    let root_valid_in_pool =
+        pool.is_valid_root_within(&proof_data.old_root, current_slot, max_root_age_slots);
+
+    let root_valid_in_extended = if let Some(ref historical_roots) = ctx.accounts.historical_roots {
+        // Verify the historical roots account belongs to this pool
+        require!(
+            historical_roots.pool == pool.key(),
+            PoolError::InvalidVerificationKey
+        );
+        historical_roots.contains_within(&proof_data.old_root, current_slot, max_root_age_slots)
+    } else {
+        false
+    };
+
+    require!(
+        root_valid_in_pool || root_valid_in_extended,
+        PoolError::MerkleRootExpired
+    );
+
+    msg!(
+        "Root validated: in_pool={}, in_extended={}, current_slot={}",
+        root_valid_in_pool,
+        root_valid_in_extended,
+        current_slot
+    );
+
+    // 2. SECURITY (HIGH-02): Verify VK hash is one the pool accepts for this circuit
+    let vk_data = ctx.accounts.verification_key.try_to_vec()?;
+    let vk_hash = keccak::hash(&vk_data);
+    pool.require_vk(
+        &ctx.accounts.verification_key.circuit_id,
+        &vk_hash.to_bytes(),
+        current_slot,
+    )?;
+
+    // 3. Verify ZK proof via CPI to zk-verifier (verify_prepared on the key hashed above)
+    msg!("Verifying transfer proof (estimated 600k CU)");
+
+    let verify_cpi_ctx = CpiContext::new(
+        ctx.accounts.verifier_program.to_account_info(),
+        VerifyPrepared {
+            prepared_vk: ctx.accounts.prepared_vk.to_account_info(),
+        },
+    );
+
+    let public_inputs = proof_data.public_inputs();
+    cpi::verify_prepared(verify_cpi_ctx, proof_data.proof, public_inputs)?;
+
+    msg!("ZK proof verified successfully");
+
+    // 4. Record sender's nullifier (account creation proves uniqueness)
+    let nullifier_entry = &mut ctx.accounts.nullifier_entry;
+    nullifier_entry.nullifier = nullifier;
+    nullifier_entry.slot = current_slot;
+    nullifier_entry.bump = ctx.bumps.nullifier_entry;
+
+    // 5. Update pool state with new merkle root from proof
+    let new_root = proof_data.new_root;
+    let old_root = pool.commitment_root;
+    pool.update_root(new_root, current_slot);
+    pool.total_nullifiers = pool
+        .total_nullifiers
+        .checked_add(1)
+        .ok_or(PoolError::Overflow)?;
+
+    // 6. SECURITY (CRITICAL-02): Also push to HistoricalRoots PDA if available
+    if let Some(ref mut historical_roots) = ctx.accounts.historical_roots {
+        historical_roots.push(old_root, current_slot);
+        msg!("Root pushed to extended historical buffer (900-slot capacity)");
+    }
+
+    // 7. Emit event with the new commitments (indexer inserts them into its tree)
+    emit!(TransferEvent {
+        pool: pool.key(),
+        nullifier,
+        new_sender_commitment: proof_data.new_sender_commitment,
+        receiver_commitment: proof_data.receiver_commitment,
+        new_root,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Private transfer settled, new root: {:?}", new_root);
+    Ok(())
+}
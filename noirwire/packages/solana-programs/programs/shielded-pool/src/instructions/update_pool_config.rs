@@ -0,0 +1,143 @@
+use crate::errors::PoolError;
+use crate::events::{PoolConfigUpdatedEvent, VkStagedEvent};
+use crate::state::*;
+use anchor_lang::prelude::*;
+use zk_verifier::registry::{CircuitRegistry, CIRCUIT_REGISTRY_SEED};
+
+/// Pool Configuration Update Context
+///
+/// Authority-gated changes to tunable pool parameters. Each call applies a
+/// single `PoolConfigUpdate` and emits one PoolConfigUpdatedEvent.
+///
+/// Root-age and cleanup-age updates live in the PoolConfig PDA and require
+/// it (see `init_pool_config`); the other updates change PoolState. Staging
+/// a verification key requires the pool's CircuitRegistry.
+/// Authority and PER authority changes go through the delayed flows in
+/// authority.rs instead.
+#[derive(Accounts)]
+pub struct UpdatePoolConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.token_mint.as_ref()],
+        bump = pool.bump,
+        has_one = authority @ PoolError::Unauthorized
+    )]
+    pub pool: Account<'info, PoolState>,
+
+    /// Pool config PDA (required for root-age and cleanup-age updates)
+    #[account(
+        mut,
+        seeds = [POOL_CONFIG_SEED, pool.key().as_ref()],
+        bump = pool_config.bump,
+    )]
+    pub pool_config: Option<Account<'info, PoolConfig>>,
+
+    /// Pool's circuit registry in zk-verifier (required for StageVk)
+    #[account(
+        seeds = [CIRCUIT_REGISTRY_SEED, pool.key().as_ref()],
+        seeds::program = zk_verifier::ID,
+        bump = circuit_registry.bump
+    )]
+    pub circuit_registry: Option<Account<'info, CircuitRegistry>>,
+
+    /// Pool admin: a signer, or the pool's AdminMultisig with co-signers as
+    /// remaining accounts
+    /// CHECK: Matched against pool.authority, authorized by require_admin
+    pub authority: UncheckedAccount<'info>,
+}
+
+pub fn handler(ctx: Context<UpdatePoolConfig>, update: PoolConfigUpdate) -> Result<()> {
+    require_admin(&ctx.accounts.authority, ctx.remaining_accounts)?;
+    let pool = &mut ctx.accounts.pool;
+
+    match update {
+        PoolConfigUpdate::DepositLimits {
+            min_deposit,
+            max_deposit,
+        } => {
+            pool.set_deposit_limits(min_deposit, max_deposit)?;
+            msg!("Deposit limits updated: {}..={}", min_deposit, max_deposit);
+        }
+        PoolConfigUpdate::Denominations { ref denominations } => {
+            pool.set_denominations(denominations)?;
+            msg!("Denominations updated: {:?}", denominations);
+        }
+        PoolConfigUpdate::AuthorityDelaySlots { slots } => {
+            // SECURITY: Decreases wait out the current delay
+            let effective_slot = pool.set_authority_delay_slots(slots, Clock::get()?.slot)?;
+            msg!(
+                "Authority change delay updated: {} slots (effective slot {})",
+                slots,
+                effective_slot
+            );
+        }
+        PoolConfigUpdate::Guardians { ref guardians } => {
+            pool.set_guardians(guardians)?;
+            msg!("Guardians updated: {:?}", guardians);
+        }
+        PoolConfigUpdate::OutflowLimit {
+            limit_bps,
+            epoch_slots,
+        } => {
+            pool.set_outflow_limit(limit_bps, epoch_slots)?;
+            msg!(
+                "Outflow limit updated: {} bps per {} slots",
+                limit_bps,
+                epoch_slots
+            );
+        }
+        PoolConfigUpdate::StageVk {
+            circuit_id,
+            vk_hash,
+            version,
+        } => {
+            // SECURITY (MEDIUM-02): Only registered circuits get a key
+            let circuit = ctx
+                .accounts
+                .circuit_registry
+                .as_ref()
+                .ok_or(PoolError::CircuitRegistryRequired)?
+                .get(&circuit_id)
+                .ok_or(PoolError::InvalidVerificationKey)?;
+            let circuit_name = format!("{}.{}", circuit.name, circuit.version);
+
+            let entry = pool.stage_vk(circuit_id, vk_hash, version, Clock::get()?.slot)?;
+            emit!(VkStagedEvent {
+                pool: pool.key(),
+                circuit_id,
+                vk_hash,
+                version,
+                activation_slot: entry.activation_slot,
+                timestamp: Clock::get()?.unix_timestamp,
+            });
+            msg!("Verification key v{} staged for {}", version, circuit_name);
+        }
+        PoolConfigUpdate::MaxRootAgeSlots { slots } => {
+            let pool_config = ctx
+                .accounts
+                .pool_config
+                .as_mut()
+                .ok_or(PoolError::PoolConfigRequired)?;
+            pool_config.set_max_root_age_slots(slots)?;
+            msg!("Root age window updated: {} slots", slots);
+        }
+        PoolConfigUpdate::MinNullifierAgeForCleanup { slots } => {
+            let pool_config = ctx
+                .accounts
+                .pool_config
+                .as_mut()
+                .ok_or(PoolError::PoolConfigRequired)?;
+            pool_config.set_min_nullifier_age_for_cleanup(slots)?;
+            msg!("Nullifier cleanup age updated: {} slots", slots);
+        }
+    }
+
+    emit!(PoolConfigUpdatedEvent {
+        pool: pool.key(),
+        authority: ctx.accounts.authority.key(),
+        update,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -1,13 +1,14 @@
 use crate::errors::PoolError;
-use crate::events::WithdrawEvent;
+use crate::events::{OutflowLimitExceededEvent, WithdrawEvent};
 use crate::state::*;
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::keccak;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
 use zk_verifier::cpi;
-use zk_verifier::cpi::accounts::VerifyProof;
+use zk_verifier::cpi::accounts::VerifyPrepared;
 use zk_verifier::program::ZkVerifier;
-use zk_verifier::state::VerificationKey;
+use zk_verifier::registry::{CircuitRegistry, CIRCUIT_REGISTRY_SEED};
+use zk_verifier::state::{PreparedVerificationKey, VerificationKey, PREPARED_VK_SEED};
 
 #[derive(Accounts)]
 #[instruction(proof_data: WithdrawProofData, recipient: Pubkey)]
@@ -21,20 +22,52 @@ pub struct Withdraw<'info> {
     )]
     pub pool: Account<'info, PoolState>,
 
-    /// Pool's token vault
+    /// Pool's token vault (SPL pools only)
     #[account(
         mut,
         seeds = [b"vault", pool.key().as_ref()],
         bump
     )]
-    pub pool_vault: Account<'info, TokenAccount>,
+    pub pool_vault: Option<InterfaceAccount<'info, TokenAccount>>,
 
-    /// Recipient's token account
+    /// Recipient's token account (SPL pools only)
+    /// SECURITY: Must be owned by the proof-bound recipient
     #[account(
         mut,
-        constraint = recipient_token_account.mint == pool.token_mint @ PoolError::InvalidMint
+        constraint = recipient_token_account.mint == pool.token_mint @ PoolError::InvalidMint,
+        constraint = recipient_token_account.owner == recipient @ PoolError::InvalidRecipient
     )]
-    pub recipient_token_account: Account<'info, TokenAccount>,
+    pub recipient_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Relayer's token account (receives `fee`; required when fee > 0)
+    /// Lets a third party submit the withdrawal and pay the nullifier rent,
+    /// so the recipient wallet never appears as a fee payer
+    #[account(
+        mut,
+        constraint = relayer_token_account.mint == pool.token_mint @ PoolError::InvalidMint
+    )]
+    pub relayer_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// SOL vault PDA (source, native SOL pools only)
+    #[account(
+        mut,
+        seeds = [SOL_VAULT_SEED, pool.key().as_ref()],
+        bump
+    )]
+    pub sol_vault: Option<SystemAccount<'info>>,
+
+    /// Recipient wallet receiving lamports (native SOL pools only)
+    /// CHECK: Address must equal the proof-bound recipient
+    #[account(
+        mut,
+        address = recipient @ PoolError::InvalidRecipient
+    )]
+    pub recipient_wallet: Option<UncheckedAccount<'info>>,
+
+    /// Relayer wallet receiving the fee in lamports (native SOL pools, fee > 0)
+    /// CHECK: Address checked against the proof-bound relayer in the handler
+    #[account(mut)]
+    pub relayer_wallet: Option<UncheckedAccount<'info>>,
 
     /// Nullifier PDA (created to mark as spent)
     #[account(
@@ -50,14 +83,34 @@ pub struct Withdraw<'info> {
     /// SECURITY: Verified to be for this pool and withdraw circuit
     #[account(
         constraint = verification_key.pool == pool.key() @ PoolError::InvalidVerificationKey,
-        constraint = verification_key.circuit_id == proof::circuit_ids::WITHDRAW @ PoolError::InvalidVerificationKey
+        constraint = circuit_registry.is_registered_as(
+            &verification_key.circuit_id,
+            proof::circuit_ids::names::WITHDRAW
+        ) @ PoolError::InvalidVerificationKey
     )]
     pub verification_key: Account<'info, VerificationKey>,
 
+    /// Verify-ready copy of verification_key, read zero-copy by verify_prepared
+    #[account(
+        seeds = [PREPARED_VK_SEED, verification_key.key().as_ref()],
+        seeds::program = zk_verifier::ID,
+        bump = prepared_vk.load()?.bump
+    )]
+    pub prepared_vk: AccountLoader<'info, PreparedVerificationKey>,
+
+    /// Pool's circuit registry (zk-verifier), resolves which circuit IDs are
+    /// withdraw circuits
+    #[account(
+        seeds = [CIRCUIT_REGISTRY_SEED, pool.key().as_ref()],
+        seeds::program = zk_verifier::ID,
+        bump = circuit_registry.bump
+    )]
+    pub circuit_registry: Account<'info, CircuitRegistry>,
+
     /// ZK Verifier program (for CPI verification)
     pub verifier_program: Program<'info, ZkVerifier>,
 
-    /// Payer for nullifier account creation
+    /// Payer for nullifier account creation (the relayer, if one is used)
     #[account(mut)]
     pub payer: Signer<'info>,
 
@@ -79,12 +132,24 @@ pub struct Withdraw<'info> {
     )]
     pub historical_roots: Option<Account<'info, HistoricalRoots>>,
 
-    pub token_program: Program<'info, Token>,
+    /// Pool config PDA (root-age window; mandatory once initialized)
+    #[account(
+        seeds = [POOL_CONFIG_SEED, pool.key().as_ref()],
+        bump = pool_config.bump,
+    )]
+    pub pool_config: Option<Account<'info, PoolConfig>>,
+
+    /// Pool's token mint (needed by transfer_checked, SPL pools only)
+    #[account(address = pool.token_mint @ PoolError::InvalidMint)]
+    pub token_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// SPL Token or Token-2022 program
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler(
-    ctx: Context<Withdraw>,
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, Withdraw<'info>>,
     proof_data: WithdrawProofData,
     recipient: Pubkey,
 ) -> Result<()> {
@@ -97,12 +162,23 @@ pub fn handler(
     let proof_recipient = Pubkey::new_from_array(proof_data.recipient);
     require!(recipient == proof_recipient, PoolError::InvalidRecipient);
 
-    // 1. Extract amount from proof (convert from field back to u64)
+    // 1. Extract amount and relayer fee from proof (convert from field back to u64)
     let amount = field_to_u64(&proof_data.amount)?;
+    let fee = field_to_u64(&proof_data.fee)?;
+    require!(fee <= amount, PoolError::FeeExceedsAmount);
+
+    // Fixed-denomination pools only release listed amounts (fee included)
+    pool.check_denomination(amount)?;
+
+    // SECURITY: The fee can only be paid to the relayer bound in the proof
+    let relayer = Pubkey::new_from_array(proof_data.relayer);
 
     // 2. SECURITY (CRITICAL-02 + HIGH-01): Validate old_root with expiration enforcement
+    let max_root_age_slots =
+        PoolConfig::max_root_age_slots(pool, ctx.accounts.pool_config.as_deref())?;
     // First check the pool's internal historical_roots (32 slots)
-    let root_valid_in_pool = pool.is_valid_root_with_expiration(&proof_data.old_root, current_slot);
+    let root_valid_in_pool =
+        pool.is_valid_root_within(&proof_data.old_root, current_slot, max_root_age_slots);
 
     // If historical_roots PDA is provided, also check the extended buffer (900 slots)
     let root_valid_in_extended = if let Some(ref historical_roots) = ctx.accounts.historical_roots {
@@ -111,7 +187,7 @@ pub fn handler(
             historical_roots.pool == pool.key(),
             PoolError::InvalidVerificationKey
         );
-        historical_roots.contains_with_expiration(&proof_data.old_root, current_slot)
+        historical_roots.contains_within(&proof_data.old_root, current_slot, max_root_age_slots)
     } else {
         false
     };
@@ -129,72 +205,195 @@ pub fn handler(
         current_slot
     );
 
-    // 3. SECURITY (HIGH-02): Verify VK hash matches pool's expected VK
+    // 3. SECURITY (HIGH-02): Verify VK hash is one the pool accepts for this circuit
     // This prevents VK substitution attacks if admin key is compromised
     let vk_data = ctx.accounts.verification_key.try_to_vec()?;
     let vk_hash = keccak::hash(&vk_data);
-    require!(
-        pool.vk_hash == vk_hash.to_bytes(),
-        PoolError::VerificationKeyHashMismatch
-    );
+    pool.require_vk(
+        &ctx.accounts.verification_key.circuit_id,
+        &vk_hash.to_bytes(),
+        current_slot,
+    )?;
 
     // 4. Request compute budget for ZK verification (~600k CU)
     msg!("Verifying withdrawal proof (estimated 600k CU)");
 
-    // 5. Verify ZK proof via CPI to zk-verifier program
+    // 5. Verify ZK proof via CPI to zk-verifier (verify_prepared on the key hashed above)
     let verify_cpi_ctx = CpiContext::new(
         ctx.accounts.verifier_program.to_account_info(),
-        VerifyProof {
-            verification_key: ctx.accounts.verification_key.to_account_info(),
+        VerifyPrepared {
+            prepared_vk: ctx.accounts.prepared_vk.to_account_info(),
         },
     );
 
     let public_inputs = proof_data.public_inputs();
-    cpi::verify(verify_cpi_ctx, proof_data.proof, public_inputs)?;
+    cpi::verify_prepared(verify_cpi_ctx, proof_data.proof, public_inputs)?;
 
     msg!("ZK proof verified successfully");
 
-    // 6. Record nullifier (account creation proves uniqueness)
+    // 6. Circuit breaker: pause instead of paying out past the outflow limit
+    if !pool.record_outflow(amount, current_slot) {
+        pool.paused = true;
+        emit!(OutflowLimitExceededEvent {
+            pool: pool.key(),
+            attempted_amount: amount,
+            epoch_outflow: pool.outflow_epoch_amount,
+            epoch_limit: pool.outflow_epoch_limit(),
+            epoch_start_slot: pool.outflow_epoch_start_slot,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        msg!("Outflow limit exceeded: pool paused, withdrawal not processed");
+
+        // Release the nullifier so the balance stays spendable once resumed
+        return ctx
+            .accounts
+            .nullifier_entry
+            .close(ctx.accounts.payer.to_account_info());
+    }
+
+    // 7. Record nullifier (account creation proves uniqueness)
     // This MUST be done after proof verification to prevent double-spend
     let nullifier_entry = &mut ctx.accounts.nullifier_entry;
     nullifier_entry.nullifier = nullifier;
     nullifier_entry.slot = current_slot;
     nullifier_entry.bump = ctx.bumps.nullifier_entry;
 
-    // 7. SECURITY (CRITICAL-07): Verify pool has sufficient balance before transfer
+    // 8. SECURITY (CRITICAL-07): Verify pool has sufficient balance before transfer
     require!(
         pool.total_shielded >= amount,
         PoolError::InsufficientPoolBalance
     );
 
-    require!(
-        ctx.accounts.pool_vault.amount >= amount,
-        PoolError::InsufficientVaultBalance
-    );
+    let vault_balance = if pool.native_sol {
+        let sol_vault = ctx
+            .accounts
+            .sol_vault
+            .as_ref()
+            .ok_or(PoolError::InvalidPoolMode)?;
+        available_lamports(sol_vault)?
+    } else {
+        ctx.accounts
+            .pool_vault
+            .as_ref()
+            .ok_or(PoolError::InvalidPoolMode)?
+            .amount
+    };
+    require!(vault_balance >= amount, PoolError::InsufficientVaultBalance);
 
     msg!(
         "Balance check passed: pool={}, vault={}",
         pool.total_shielded,
-        ctx.accounts.pool_vault.amount
+        vault_balance
     );
 
-    // 8. Transfer tokens from pool to recipient
+    // 9. Transfer from pool to recipient (amount - fee) and relayer (fee)
     let pool_key = pool.key();
-    let authority_seeds = &[b"authority", pool_key.as_ref(), &[ctx.bumps.pool_authority]];
-    let signer_seeds = &[&authority_seeds[..]];
-
-    let transfer_ctx = CpiContext::new_with_signer(
-        ctx.accounts.token_program.to_account_info(),
-        Transfer {
-            from: ctx.accounts.pool_vault.to_account_info(),
-            to: ctx.accounts.recipient_token_account.to_account_info(),
-            authority: ctx.accounts.pool_authority.to_account_info(),
-        },
-        signer_seeds,
-    );
-    token::transfer(transfer_ctx, amount)?;
-
-    // 9. Update pool state with new merkle root from proof
+    let recipient_amount = amount.checked_sub(fee).ok_or(PoolError::Underflow)?;
+
+    if pool.native_sol {
+        // Native SOL: pay lamports straight from the SOL vault PDA
+        let sol_vault = ctx
+            .accounts
+            .sol_vault
+            .as_ref()
+            .ok_or(PoolError::InvalidPoolMode)?;
+        let sol_vault_bump = ctx.bumps.sol_vault.ok_or(PoolError::InvalidPoolMode)?;
+        let recipient_wallet = ctx
+            .accounts
+            .recipient_wallet
+            .as_ref()
+            .ok_or(PoolError::InvalidPoolMode)?;
+        let system_program = ctx.accounts.system_program.to_account_info();
+
+        transfer_from_sol_vault(
+            sol_vault,
+            recipient_wallet,
+            &system_program,
+            &pool_key,
+            sol_vault_bump,
+            recipient_amount,
+        )?;
+
+        if fee > 0 {
+            let relayer_wallet = ctx
+                .accounts
+                .relayer_wallet
+                .as_ref()
+                .ok_or(PoolError::InvalidRelayer)?;
+            require!(relayer_wallet.key() == relayer, PoolError::InvalidRelayer);
+            transfer_from_sol_vault(
+                sol_vault,
+                relayer_wallet,
+                &system_program,
+                &pool_key,
+                sol_vault_bump,
+                fee,
+            )?;
+        }
+    } else {
+        let authority_seeds = &[b"authority", pool_key.as_ref(), &[ctx.bumps.pool_authority]];
+        let signer_seeds = &[&authority_seeds[..]];
+
+        let recipient_token_account = ctx
+            .accounts
+            .recipient_token_account
+            .as_ref()
+            .ok_or(PoolError::InvalidPoolMode)?;
+        let pool_vault = ctx
+            .accounts
+            .pool_vault
+            .as_ref()
+            .ok_or(PoolError::InvalidPoolMode)?;
+        let token_mint = ctx
+            .accounts
+            .token_mint
+            .as_ref()
+            .ok_or(PoolError::InvalidPoolMode)?;
+        let transfer_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: pool_vault.to_account_info(),
+                mint: token_mint.to_account_info(),
+                to: recipient_token_account.to_account_info(),
+                authority: ctx.accounts.pool_authority.to_account_info(),
+            },
+            signer_seeds,
+        );
+        token_interface::transfer_checked(
+            transfer_ctx.with_remaining_accounts(ctx.remaining_accounts.to_vec()),
+            recipient_amount,
+            token_mint.decimals,
+        )?;
+
+        if fee > 0 {
+            let relayer_token_account = ctx
+                .accounts
+                .relayer_token_account
+                .as_ref()
+                .ok_or(PoolError::InvalidRelayer)?;
+            require!(
+                relayer_token_account.owner == relayer,
+                PoolError::InvalidRelayer
+            );
+            let fee_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: pool_vault.to_account_info(),
+                    mint: token_mint.to_account_info(),
+                    to: relayer_token_account.to_account_info(),
+                    authority: ctx.accounts.pool_authority.to_account_info(),
+                },
+                signer_seeds,
+            );
+            token_interface::transfer_checked(
+                fee_ctx.with_remaining_accounts(ctx.remaining_accounts.to_vec()),
+                fee,
+                token_mint.decimals,
+            )?;
+        }
+    }
+
+    // 10. Update pool state with new merkle root from proof
     let new_root = proof_data.new_root;
     pool.update_root(new_root, current_slot);
     pool.total_shielded = pool
@@ -212,20 +411,24 @@ pub fn handler(
         .checked_add(1)
         .ok_or(PoolError::Overflow)?;
 
-    // 10. Emit event
+    // 11. Emit event
     emit!(WithdrawEvent {
         pool: pool.key(),
         nullifier,
         amount,
         recipient,
+        relayer,
+        fee,
         new_root,
         timestamp: Clock::get()?.unix_timestamp,
     });
 
     msg!(
-        "Withdrawal successful: {} tokens to {}, new root: {:?}",
-        amount,
+        "Withdrawal successful: {} tokens to {} ({} fee to relayer {}), new root: {:?}",
+        recipient_amount,
         recipient,
+        fee,
+        relayer,
         new_root
     );
     Ok(())
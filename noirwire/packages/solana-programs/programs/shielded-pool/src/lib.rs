@@ -8,6 +8,7 @@ declare_id!("NWRZDZJMfUAd3iVvdMhpsKht5bgHZGPzynHhQ2JssQ2");
 pub mod errors;
 pub mod events;
 pub mod instructions;
+pub mod poseidon2;
 pub mod state;
 
 use instructions::*;
@@ -18,13 +19,25 @@ pub mod shielded_pool {
 
     /// Initialize a new shielded pool
     /// SECURITY (CRITICAL-05): per_authority is the only address authorized to call settle_batch
+    /// `native_sol` selects native SOL mode (wrapped SOL mint, lamports held in the SOL vault PDA)
+    /// Deposit limits are in base units and validated against the mint's decimals
+    #[allow(clippy::too_many_arguments)]
     pub fn initialize(
         ctx: Context<Initialize>,
         token_mint: Pubkey,
-        vk_hash: [u8; 32],
         per_authority: Pubkey,
+        native_sol: bool,
+        min_deposit: u64,
+        max_deposit: u64,
     ) -> Result<()> {
-        instructions::initialize::handler(ctx, token_mint, vk_hash, per_authority)
+        instructions::initialize::handler(
+            ctx,
+            token_mint,
+            per_authority,
+            native_sol,
+            min_deposit,
+            max_deposit,
+        )
     }
 
     /// Initialize the Historical Roots PDA for production-scale merkle root storage
@@ -39,8 +52,10 @@ pub mod shielded_pool {
     /// Requires a valid ZK proof that the commitment is correctly formed
     /// SECURITY (MEDIUM-04): Enforces minimum deposit amount
     /// SECURITY (HIGH-02): Validates VK hash
-    pub fn deposit(
-        ctx: Context<Deposit>,
+    /// Token-2022 transfer-fee mints credit the post-fee amount; transfer-hook
+    /// extra accounts are passed as remaining accounts
+    pub fn deposit<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Deposit<'info>>,
         amount: u64,
         proof_data: state::DepositProofData,
     ) -> Result<()> {
@@ -51,26 +66,38 @@ pub mod shielded_pool {
     /// Requires a valid ZK proof of ownership and sufficient balance
     /// SECURITY (HIGH-01): Enforces root expiration (MAX_ROOT_AGE_SLOTS)
     /// SECURITY (HIGH-02): Validates VK hash
-    pub fn withdraw(
-        ctx: Context<Withdraw>,
+    pub fn withdraw<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Withdraw<'info>>,
         proof_data: state::WithdrawProofData,
         recipient: Pubkey,
     ) -> Result<()> {
         instructions::withdraw::handler(ctx, proof_data, recipient)
     }
 
+    /// Private transfer between shielded balances (L1 fallback when the PER is unavailable)
+    /// Spends the sender's balance and inserts the remainder and receiver commitments
+    /// SECURITY (HIGH-01): Enforces root expiration (MAX_ROOT_AGE_SLOTS)
+    /// SECURITY (HIGH-02): Validates VK hash
+    pub fn transfer(ctx: Context<Transfer>, proof_data: state::TransferProofData) -> Result<()> {
+        instructions::transfer::handler(ctx, proof_data)
+    }
+
     /// Batch settlement from PER (multiple nullifiers + new root)
     /// SECURITY (CRITICAL-03): Verifies batch ZK proof before updating state
-    pub fn settle_batch(
-        ctx: Context<SettleBatch>,
+    /// Pays out the batch's public withdrawals (bound by the proof's withdrawals_hash)
+    /// Recipient token accounts are passed as remaining accounts
+    pub fn settle_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SettleBatch<'info>>,
         proof_data: state::BatchSettlementProofData,
+        withdrawals: Vec<state::BatchWithdrawal>,
     ) -> Result<()> {
-        instructions::settle_batch::handler(ctx, proof_data)
+        instructions::settle_batch::handler(ctx, proof_data, withdrawals)
     }
 
     /// Record individual nullifier after batch settlement
     /// SECURITY (CRITICAL-04): Creates nullifier PDA with merkle proof verification
     /// Called by indexer/PER after settle_batch to prevent double-spend
+    /// The proof must be exactly the settled batch's tree depth and open a leaf below nullifier_count
     pub fn record_nullifier(
         ctx: Context<RecordNullifier>,
         nullifier: [u8; 32],
@@ -87,13 +114,102 @@ pub mod shielded_pool {
         )
     }
 
+    /// Record several nullifiers from one batch settlement in a single instruction
+    /// SECURITY (CRITICAL-04): One multi-proof against nullifiers_root covers every leaf
+    /// Nullifier PDAs are passed as remaining accounts (at most MAX_NULLIFIERS_PER_RECORD_BATCH)
+    pub fn record_nullifiers_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RecordNullifiersBatch<'info>>,
+        nullifiers_root: [u8; 32],
+        nullifiers: Vec<[u8; 32]>,
+        leaf_indices: Vec<u32>,
+        proof: Vec<[u8; 32]>,
+        depth: u8,
+    ) -> Result<()> {
+        instructions::record_nullifiers_batch::handler(
+            ctx,
+            nullifiers_root,
+            nullifiers,
+            leaf_indices,
+            proof,
+            depth,
+        )
+    }
+
     /// Clean up old nullifier PDAs to recover rent
     /// SECURITY (LOW-02): Only cleans nullifiers older than MIN_NULLIFIER_AGE_FOR_CLEANUP
     pub fn cleanup_nullifier(ctx: Context<CleanupNullifier>) -> Result<()> {
         instructions::cleanup_nullifier::handler(ctx)
     }
 
-    /// Emergency pause (admin only)
+    /// Migrate a version-2 pool account to the current layout (admin only)
+    /// Appends the post-v2 fields and sets the deposit limits
+    pub fn migrate_pool_state(
+        ctx: Context<MigratePoolState>,
+        min_deposit: u64,
+        max_deposit: u64,
+    ) -> Result<()> {
+        instructions::migrate_pool_state::handler(ctx, min_deposit, max_deposit)
+    }
+
+    /// Initialize the PoolConfig PDA (admin only)
+    ///
+    /// Moves the root-age window and nullifier cleanup age from compile-time
+    /// constants into an account that update_pool_config can tune.
+    pub fn init_pool_config(ctx: Context<InitializePoolConfig>) -> Result<()> {
+        instructions::init_pool_config::handler(ctx)
+    }
+
+    /// Update pool configuration (admin only)
+    /// Emits PoolConfigUpdatedEvent with the applied change
+    pub fn update_pool_config(
+        ctx: Context<UpdatePoolConfig>,
+        update: state::PoolConfigUpdate,
+    ) -> Result<()> {
+        instructions::update_pool_config::handler(ctx, update)
+    }
+
+    /// Create the pool's M-of-N admin multisig (admin only)
+    /// Adopt it as pool authority with propose_authority / accept_authority
+    pub fn create_admin_multisig(
+        ctx: Context<CreateAdminMultisig>,
+        signers: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        instructions::admin_multisig::create_handler(ctx, signers, threshold)
+    }
+
+    /// Replace the admin multisig's signers and threshold
+    /// Requires `threshold` of the current signers as signing remaining accounts
+    pub fn update_admin_multisig(
+        ctx: Context<UpdateAdminMultisig>,
+        signers: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        instructions::admin_multisig::update_handler(ctx, signers, threshold)
+    }
+
+    /// Propose a new pool authority (step 1 of 2, admin only)
+    /// Proposing the zero key cancels a pending transfer
+    pub fn propose_authority(ctx: Context<ProposeAuthority>, new_authority: Pubkey) -> Result<()> {
+        instructions::authority::propose_authority_handler(ctx, new_authority)
+    }
+
+    /// Accept a proposed pool authority (step 2 of 2, signed by the new authority)
+    /// Only after authority_delay_slots have elapsed since the proposal
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        instructions::authority::accept_authority_handler(ctx)
+    }
+
+    /// Rotate the PER authority allowed to call settle_batch (admin only)
+    /// Queued behind authority_delay_slots when a delay is configured
+    pub fn rotate_per_authority(
+        ctx: Context<RotatePerAuthority>,
+        new_per_authority: Pubkey,
+    ) -> Result<()> {
+        instructions::authority::rotate_per_authority_handler(ctx, new_per_authority)
+    }
+
+    /// Emergency pause (admin, or guardians for pausing only)
     pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
         instructions::set_paused::handler(ctx, paused)
     }
@@ -105,10 +221,25 @@ pub mod shielded_pool {
         instructions::set_paused::set_emergency_mode_handler(ctx, emergency_mode)
     }
 
-    /// Emergency withdrawal (bypasses ZK verification)
+    /// Commit to an emergency claim and its recipient (step 1 of a claim)
+    /// Allowed as soon as emergency mode is enabled, including the timelock
+    pub fn commit_emergency_claim(
+        ctx: Context<CommitEmergencyClaim>,
+        claim_hash: [u8; 32],
+    ) -> Result<()> {
+        instructions::emergency_claim::commit_handler(ctx, claim_hash)
+    }
+
+    /// Emergency claim (bypasses ZK verification, no admin signature)
     /// SECURITY (LOW-01): Only available when pool is in emergency_mode
-    /// Admin must authorize each withdrawal
-    pub fn emergency_withdraw(ctx: Context<EmergencyWithdraw>, amount: u64) -> Result<()> {
-        instructions::emergency_withdraw::handler(ctx, amount)
+    /// Opens after a timelock; the claimant reveals a committed claim, proves
+    /// their commitment against the frozen root and consumes its nullifier
+    pub fn emergency_claim<'info>(
+        ctx: Context<'_, '_, 'info, 'info, EmergencyClaim<'info>>,
+        nullifier: [u8; 32],
+        claim: state::EmergencyClaimData,
+        salt: [u8; 32],
+    ) -> Result<()> {
+        instructions::emergency_claim::handler(ctx, nullifier, claim, salt)
     }
 }
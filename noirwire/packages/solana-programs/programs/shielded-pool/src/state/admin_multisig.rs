@@ -0,0 +1,201 @@
+use crate::errors::PoolError;
+use anchor_lang::prelude::*;
+
+/// Seeds for deriving the AdminMultisig PDA: ["admin_multisig", pool]
+pub const ADMIN_MULTISIG_SEED: &[u8] = b"admin_multisig";
+
+/// Current version for AdminMultisig account
+/// SECURITY (LOW-03): Versioning for future-proof upgrades
+pub const ADMIN_MULTISIG_VERSION: u8 = 1;
+
+/// Maximum number of admin signers (bounded by transaction account limits)
+pub const MAX_ADMIN_SIGNERS: usize = 10;
+
+/// M-of-N pool admin
+///
+/// A pool's `authority` can be set to this account (via propose/accept
+/// authority) so no single key controls pooled funds. Admin instructions then
+/// take the multisig as `authority` (not a signer) plus at least `threshold`
+/// of `signers` as signing remaining accounts. See `require_admin`.
+///
+/// LAYOUT: zk-verifier reads this account's raw bytes to authorize VK changes
+/// (`zk_verifier::admin`); field order and sizes must not change without
+/// updating it.
+#[account]
+#[derive(InitSpace)]
+pub struct AdminMultisig {
+    /// Account structure version
+    /// SECURITY (LOW-03): Versioning for future-proof upgrades
+    pub version: u8,
+
+    /// The pool this multisig was created for
+    pub pool: Pubkey,
+
+    /// Approvals required for an admin action
+    pub threshold: u8,
+
+    /// Number of active entries in `signers`
+    pub signer_count: u8,
+
+    /// Admin signer keys (first `signer_count` entries are active)
+    pub signers: [Pubkey; MAX_ADMIN_SIGNERS],
+
+    /// Bump seed
+    pub bump: u8,
+}
+
+impl AdminMultisig {
+    /// Active signer keys
+    pub fn active_signers(&self) -> &[Pubkey] {
+        &self.signers[..self.signer_count as usize]
+    }
+
+    /// Validate and apply a new signer set
+    ///
+    /// SECURITY: Rejects empty, duplicate or zero keys and thresholds that
+    /// could never be met
+    pub fn set_signers(&mut self, signers: &[Pubkey], threshold: u8) -> Result<()> {
+        require!(
+            !signers.is_empty() && signers.len() <= MAX_ADMIN_SIGNERS,
+            PoolError::InvalidAdminSigners
+        );
+        require!(
+            threshold > 0 && threshold as usize <= signers.len(),
+            PoolError::InvalidAdminSigners
+        );
+        for (i, signer) in signers.iter().enumerate() {
+            require!(
+                *signer != Pubkey::default() && !signers[..i].contains(signer),
+                PoolError::InvalidAdminSigners
+            );
+        }
+
+        self.signers = [Pubkey::default(); MAX_ADMIN_SIGNERS];
+        self.signers[..signers.len()].copy_from_slice(signers);
+        self.signer_count = signers.len() as u8;
+        self.threshold = threshold;
+        Ok(())
+    }
+
+    /// Number of distinct active signers that signed among `accounts`
+    pub fn approvals(&self, accounts: &[AccountInfo]) -> usize {
+        self.active_signers()
+            .iter()
+            .filter(|signer| {
+                accounts
+                    .iter()
+                    .any(|account| account.is_signer && account.key == *signer)
+            })
+            .count()
+    }
+
+    /// Require `threshold` approvals among `accounts`
+    pub fn require_approvals(&self, accounts: &[AccountInfo]) -> Result<()> {
+        require!(
+            self.approvals(accounts) >= self.threshold as usize,
+            PoolError::InsufficientApprovals
+        );
+        Ok(())
+    }
+}
+
+/// Authorize an admin action for an `authority` already matched against the pool
+///
+/// - A signing `authority` is a single-key admin
+/// - Otherwise `authority` must be an AdminMultisig owned by this program, and
+///   `remaining_accounts` must carry `threshold` of its signers
+pub fn require_admin(authority: &AccountInfo, remaining_accounts: &[AccountInfo]) -> Result<()> {
+    if authority.is_signer {
+        return Ok(());
+    }
+
+    // SECURITY: Only a multisig created by this program can stand in for a signature
+    require!(authority.owner == &crate::ID, PoolError::Unauthorized);
+    let multisig = AdminMultisig::try_deserialize(&mut &authority.try_borrow_data()?[..])
+        .map_err(|_| error!(PoolError::Unauthorized))?;
+    multisig.require_approvals(remaining_accounts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn empty_multisig() -> AdminMultisig {
+        AdminMultisig {
+            version: ADMIN_MULTISIG_VERSION,
+            pool: Pubkey::new_unique(),
+            threshold: 0,
+            signer_count: 0,
+            signers: [Pubkey::default(); MAX_ADMIN_SIGNERS],
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn test_set_signers_validation() {
+        let mut multisig = empty_multisig();
+        let a = Pubkey::new_unique();
+        let b = Pubkey::new_unique();
+
+        assert!(multisig.set_signers(&[], 1).is_err());
+        assert!(multisig.set_signers(&[a, b], 0).is_err());
+        assert!(multisig.set_signers(&[a, b], 3).is_err());
+        assert!(multisig.set_signers(&[a, a], 1).is_err());
+        assert!(multisig.set_signers(&[a, Pubkey::default()], 1).is_err());
+        assert!(multisig
+            .set_signers(&[Pubkey::new_unique(); MAX_ADMIN_SIGNERS + 1], 1)
+            .is_err());
+
+        multisig.set_signers(&[a, b], 2).unwrap();
+        assert_eq!(multisig.active_signers(), &[a, b]);
+        assert_eq!(multisig.threshold, 2);
+    }
+
+    #[test]
+    fn test_approvals_counts_distinct_signers() {
+        let mut multisig = empty_multisig();
+        let keys = [
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        ];
+        multisig.set_signers(&keys[..2], 2).unwrap();
+
+        let owner = Pubkey::default();
+        let [mut l0, mut l1, mut l2, mut l3] = [0u64; 4];
+        let [mut d0, mut d1, mut d2, mut d3] = [[0u8; 0]; 4];
+        let accounts = [
+            AccountInfo::new(&keys[0], true, false, &mut l0, &mut d0, &owner, false, 0),
+            // Same signer twice only counts once
+            AccountInfo::new(&keys[0], true, false, &mut l1, &mut d1, &owner, false, 0),
+            // Not a member of the signer set
+            AccountInfo::new(&keys[2], true, false, &mut l2, &mut d2, &owner, false, 0),
+            // Member, but did not sign
+            AccountInfo::new(&keys[1], false, false, &mut l3, &mut d3, &owner, false, 0),
+        ];
+
+        assert_eq!(multisig.approvals(&accounts), 1);
+        assert!(multisig.require_approvals(&accounts).is_err());
+        assert!(multisig.require_approvals(&accounts[..0]).is_err());
+    }
+
+    #[test]
+    fn test_layout_matches_zk_verifier_parser() {
+        let mut multisig = empty_multisig();
+        let signers = [
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        ];
+        multisig.set_signers(&signers, 2).unwrap();
+
+        let mut data = Vec::new();
+        multisig.try_serialize(&mut data).unwrap();
+        assert_eq!(data.len(), 8 + AdminMultisig::INIT_SPACE);
+
+        assert_eq!(zk_verifier::admin::SHIELDED_POOL_PROGRAM_ID, crate::ID);
+        let view = zk_verifier::admin::AdminMultisigView::parse(&data).unwrap();
+        assert_eq!(view.threshold, 2);
+        assert_eq!(view.signers, signers);
+    }
+}
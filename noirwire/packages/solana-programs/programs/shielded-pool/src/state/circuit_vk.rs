@@ -0,0 +1,129 @@
+use crate::errors::PoolError;
+use anchor_lang::prelude::*;
+
+/// Maximum number of circuits a pool tracks verification keys for
+pub const MAX_POOL_CIRCUITS: usize = 6;
+
+/// Delay between staging a new verification key and accepting it (~1 hour)
+/// Gives users and watchers time to review the new key before it goes live
+pub const VK_ACTIVATION_DELAY_SLOTS: u64 = 9_000;
+
+/// How long the previous key keeps verifying after a new one activates (~1 hour)
+/// Proofs generated against the old key before the switch can still land
+pub const VK_GRACE_SLOTS: u64 = 9_000;
+
+/// Verification key rotation state for one circuit
+///
+/// A key is identified by the keccak hash of its VerificationKey account data.
+/// Rotation is lazy: acceptance is a pure function of the current slot, and a
+/// live pending key is only folded into `vk_hash` when the next one is staged.
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace,
+)]
+pub struct CircuitVk {
+    /// Circuit this entry is for (zero = unused entry)
+    pub circuit_id: [u8; 32],
+
+    /// Hash and version of the current key (zero hash = none yet)
+    pub vk_hash: [u8; 32],
+    pub version: u32,
+
+    /// Staged replacement (zero hash = none)
+    pub pending_vk_hash: [u8; 32],
+    pub pending_version: u32,
+
+    /// Slot from which the pending key is accepted
+    pub activation_slot: u64,
+}
+
+impl CircuitVk {
+    fn has_pending(&self) -> bool {
+        self.pending_vk_hash != [0u8; 32]
+    }
+
+    /// Whether a proof verified with the key hashing to `vk_hash` is accepted
+    ///
+    /// Before activation only the current key verifies. From `activation_slot`
+    /// the pending key verifies and the current one keeps verifying for
+    /// VK_GRACE_SLOTS.
+    pub fn accepts(&self, vk_hash: &[u8; 32], current_slot: u64) -> bool {
+        if *vk_hash == [0u8; 32] {
+            return false;
+        }
+        if self.has_pending() && current_slot >= self.activation_slot {
+            return *vk_hash == self.pending_vk_hash
+                || (*vk_hash == self.vk_hash
+                    && current_slot < self.activation_slot.saturating_add(VK_GRACE_SLOTS));
+        }
+        *vk_hash == self.vk_hash
+    }
+
+    /// Stage `vk_hash` as the next key for this circuit
+    ///
+    /// The first key of a circuit is accepted immediately; later keys activate
+    /// after VK_ACTIVATION_DELAY_SLOTS. Staging again before activation replaces
+    /// the pending key. Versions must strictly increase.
+    pub fn stage(&mut self, vk_hash: [u8; 32], version: u32, current_slot: u64) -> Result<()> {
+        require!(vk_hash != [0u8; 32], PoolError::InvalidConfigValue);
+
+        // Fold an activated pending key into the current one once its
+        // predecessor's grace period is over
+        if self.has_pending() && current_slot >= self.activation_slot {
+            require!(
+                current_slot >= self.activation_slot.saturating_add(VK_GRACE_SLOTS),
+                PoolError::VkRotationInProgress
+            );
+            self.vk_hash = self.pending_vk_hash;
+            self.version = self.pending_version;
+            self.pending_vk_hash = [0u8; 32];
+            self.pending_version = 0;
+            self.activation_slot = 0;
+        }
+
+        require!(version > self.version, PoolError::InvalidVkVersion);
+
+        if self.vk_hash == [0u8; 32] {
+            self.vk_hash = vk_hash;
+            self.version = version;
+        } else {
+            self.pending_vk_hash = vk_hash;
+            self.pending_version = version;
+            self.activation_slot = current_slot.saturating_add(VK_ACTIVATION_DELAY_SLOTS);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_staged_rotation_with_grace_period() {
+        let (v1, v2, v3) = ([1u8; 32], [2u8; 32], [3u8; 32]);
+        let mut entry = CircuitVk::default();
+        assert!(!entry.accepts(&[0u8; 32], 0));
+
+        // First key is live immediately
+        entry.stage(v1, 1, 100).unwrap();
+        assert!(entry.accepts(&v1, 100));
+        assert!(entry.stage(v2, 1, 100).is_err());
+
+        // Second key waits for activation, then overlaps with the first
+        entry.stage(v2, 2, 1_000).unwrap();
+        let activation = 1_000 + VK_ACTIVATION_DELAY_SLOTS;
+        assert!(entry.accepts(&v1, activation - 1));
+        assert!(!entry.accepts(&v2, activation - 1));
+        assert!(entry.accepts(&v1, activation));
+        assert!(entry.accepts(&v2, activation));
+        assert!(!entry.accepts(&v1, activation + VK_GRACE_SLOTS));
+        assert!(entry.accepts(&v2, activation + VK_GRACE_SLOTS));
+
+        // No new rotation while the old key is still in its grace period
+        assert!(entry.stage(v3, 3, activation).is_err());
+        entry.stage(v3, 3, activation + VK_GRACE_SLOTS).unwrap();
+        assert_eq!((entry.vk_hash, entry.version), (v2, 2));
+        assert!(entry.accepts(&v2, activation + VK_GRACE_SLOTS));
+        assert!(!entry.accepts(&v3, activation + VK_GRACE_SLOTS));
+    }
+}
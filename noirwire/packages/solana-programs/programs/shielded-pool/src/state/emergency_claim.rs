@@ -0,0 +1,44 @@
+//! Emergency claim commitments
+//!
+//! An emergency claim reveals the opening of a balance commitment, and that
+//! opening is not bound to a recipient by any proof. Claims therefore go
+//! through commit-reveal: the claimant first records
+//! `keccak256(opening || recipient || salt)` with `commit_emergency_claim`,
+//! and `emergency_claim` only pays the committed recipient, no sooner than
+//! EMERGENCY_CLAIM_REVEAL_DELAY_SLOTS later.
+//!
+//! A front-runner only learns the opening from the reveal itself, so its own
+//! commitment is always younger than the honest one and cannot be revealed
+//! before the honest reveal has landed or expired.
+
+use anchor_lang::prelude::*;
+
+/// Seeds for an emergency claim commitment PDA: ["emergency_claim", pool, claim_hash]
+pub const EMERGENCY_CLAIM_COMMITMENT_SEED: &[u8] = b"emergency_claim";
+
+/// Minimum age of a commitment before it can be revealed (~1 min)
+/// Matches the lifetime of a recent blockhash, so a reveal transaction lands
+/// or expires before a commitment made after seeing it could be revealed
+pub const EMERGENCY_CLAIM_REVEAL_DELAY_SLOTS: u64 = 150;
+
+/// Commitment to an emergency claim and its recipient (PDA per claim hash)
+#[account]
+pub struct EmergencyClaimCommitment {
+    /// keccak256(opening || recipient || salt), see `EmergencyClaimData::claim_hash`
+    pub claim_hash: [u8; 32],
+
+    /// Slot the commitment was made
+    pub slot: u64,
+
+    /// Bump seed
+    pub bump: u8,
+}
+
+impl EmergencyClaimCommitment {
+    pub const SIZE: usize = 8 + 32 + 8 + 1;
+
+    /// Whether the commitment may be revealed at `current_slot`
+    pub fn is_revealable(&self, current_slot: u64) -> bool {
+        current_slot >= self.slot.saturating_add(EMERGENCY_CLAIM_REVEAL_DELAY_SLOTS)
+    }
+}
@@ -93,6 +93,14 @@ impl HistoricalRoots {
     /// - Checks if root exists in the buffer
     /// - Verifies root is not older than MAX_ROOT_AGE_SLOTS
     pub fn contains_with_expiration(&self, root: &[u8; 32], current_slot: u64) -> bool {
+        self.contains_within(root, current_slot, MAX_ROOT_AGE_SLOTS)
+    }
+
+    /// Check if a root exists and is at most `max_age_slots` old
+    ///
+    /// Same as `contains_with_expiration`, with the window taken from the
+    /// pool's PoolConfig instead of MAX_ROOT_AGE_SLOTS.
+    pub fn contains_within(&self, root: &[u8; 32], current_slot: u64, max_age_slots: u64) -> bool {
         // Zero roots are invalid (cleared slots)
         if *root == [0u8; 32] {
             return false;
@@ -108,7 +116,7 @@ impl HistoricalRoots {
                 }
 
                 // Check expiration
-                return current_slot.saturating_sub(root_slot) <= MAX_ROOT_AGE_SLOTS;
+                return current_slot.saturating_sub(root_slot) <= max_age_slots;
             }
         }
 
@@ -0,0 +1,113 @@
+//! Token-2022 mint extension handling
+//!
+//! Pools accept both SPL Token and Token-2022 mints. Most Token-2022 extensions
+//! are harmless for a shielded pool, but a few break custody or privacy and are
+//! rejected when the pool is created.
+
+use crate::errors::PoolError;
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022::extension::transfer_fee::TransferFeeConfig;
+use anchor_spl::token_2022::spl_token_2022::extension::{
+    BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+};
+use anchor_spl::token_2022::spl_token_2022::state::Mint;
+
+/// Mint extensions a pool refuses to hold
+///
+/// - PermanentDelegate: the delegate can move tokens out of the pool vault
+/// - NonTransferable: deposits could never be withdrawn
+/// - ConfidentialTransfer*: encrypted balances bypass the vault accounting
+/// - ConfidentialMintBurn: supply changes that the pool can't observe
+pub const UNSUPPORTED_MINT_EXTENSIONS: [ExtensionType; 5] = [
+    ExtensionType::PermanentDelegate,
+    ExtensionType::NonTransferable,
+    ExtensionType::ConfidentialTransferMint,
+    ExtensionType::ConfidentialTransferFeeConfig,
+    ExtensionType::ConfidentialMintBurn,
+];
+
+/// Reject mints carrying an extension from `UNSUPPORTED_MINT_EXTENSIONS`
+///
+/// Plain SPL Token mints have no extensions and always pass.
+pub fn validate_mint_extensions(mint_info: &AccountInfo) -> Result<()> {
+    let data = mint_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<Mint>::unpack(&data)?;
+
+    for extension in mint.get_extension_types()? {
+        require!(
+            !UNSUPPORTED_MINT_EXTENSIONS.contains(&extension),
+            PoolError::UnsupportedMintExtension
+        );
+    }
+    Ok(())
+}
+
+/// Transfer fee withheld when moving `amount` tokens of this mint in the current epoch
+///
+/// Returns 0 for mints without the TransferFeeConfig extension.
+pub fn transfer_fee(mint_info: &AccountInfo, amount: u64) -> Result<u64> {
+    let data = mint_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<Mint>::unpack(&data)?;
+
+    match mint.get_extension::<TransferFeeConfig>() {
+        Ok(config) => config
+            .calculate_epoch_fee(Clock::get()?.epoch, amount)
+            .ok_or_else(|| PoolError::Overflow.into()),
+        Err(_) => Ok(0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_spl::token_2022::spl_token_2022::extension::permanent_delegate::PermanentDelegate;
+    use anchor_spl::token_2022::spl_token_2022::extension::{
+        BaseStateWithExtensionsMut, StateWithExtensionsMut,
+    };
+    use anchor_spl::token_2022::spl_token_2022::ID as TOKEN_2022_ID;
+
+    fn mint_data(with_permanent_delegate: bool) -> Vec<u8> {
+        let extensions: &[ExtensionType] = if with_permanent_delegate {
+            &[ExtensionType::PermanentDelegate]
+        } else {
+            &[]
+        };
+        let len = ExtensionType::try_calculate_account_len::<Mint>(extensions).unwrap();
+        let mut data = vec![0u8; len];
+        let mut state = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut data).unwrap();
+        if with_permanent_delegate {
+            state.init_extension::<PermanentDelegate>(true).unwrap();
+        }
+        state.base.decimals = 6;
+        state.base.is_initialized = true;
+        state.pack_base();
+        state.init_account_type().unwrap();
+        data
+    }
+
+    fn validate(mut data: Vec<u8>) -> Result<()> {
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let info = AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &TOKEN_2022_ID,
+            false,
+            0,
+        );
+        validate_mint_extensions(&info)
+    }
+
+    #[test]
+    fn test_mint_without_extensions_is_accepted() {
+        assert!(validate(mint_data(false)).is_ok());
+    }
+
+    #[test]
+    fn test_permanent_delegate_mint_is_rejected() {
+        assert!(validate(mint_data(true)).is_err());
+    }
+}
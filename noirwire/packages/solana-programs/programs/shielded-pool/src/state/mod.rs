@@ -1,9 +1,23 @@
+pub mod admin_multisig;
+pub mod circuit_vk;
+pub mod emergency_claim;
 pub mod historical_roots;
+pub mod mint_extensions;
 pub mod nullifier;
+pub mod pool_config;
 pub mod pool_state;
 pub mod proof;
+pub mod settled_batch;
+pub mod sol_vault;
 
+pub use admin_multisig::*;
+pub use circuit_vk::*;
+pub use emergency_claim::*;
 pub use historical_roots::*;
+pub use mint_extensions::*;
 pub use nullifier::*;
+pub use pool_config::*;
 pub use pool_state::*;
 pub use proof::*;
+pub use settled_batch::*;
+pub use sol_vault::*;
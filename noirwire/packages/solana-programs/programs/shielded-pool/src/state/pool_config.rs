@@ -0,0 +1,185 @@
+use super::pool_state::{PoolState, MAX_ROOT_AGE_SLOTS};
+use crate::errors::PoolError;
+use anchor_lang::prelude::*;
+
+/// Seeds for deriving the PoolConfig PDA: ["pool_config", pool]
+pub const POOL_CONFIG_SEED: &[u8] = b"pool_config";
+
+/// Current version for PoolConfig account
+/// SECURITY (LOW-03): Versioning for future-proof upgrades
+pub const POOL_CONFIG_VERSION: u8 = 1;
+
+/// Default minimum age before a nullifier can be cleaned up (in slots)
+///
+/// SECURITY (LOW-02): Nullifier cleanup strategy
+/// - ~2 hours at 0.4s per slot = 18000 slots
+/// - Must be significantly longer than MAX_ROOT_AGE_SLOTS (900)
+/// - Ensures nullifiers cannot be reused even if someone holds old proofs
+pub const MIN_NULLIFIER_AGE_FOR_CLEANUP: u64 = 18000;
+
+/// Bounds for `max_root_age_slots`: ~1 minute to ~1 hour at 0.4s per slot
+pub const ROOT_AGE_SLOTS_BOUNDS: (u64, u64) = (150, 9_000);
+
+/// Upper bound for `min_nullifier_age_for_cleanup` (~10 days at 0.4s per slot)
+pub const MAX_NULLIFIER_CLEANUP_AGE_SLOTS: u64 = 2_160_000;
+
+/// Cleanup age must stay at least this many times the root-age window
+/// SECURITY (LOW-02): Defense in depth against proofs built on old roots
+pub const MIN_CLEANUP_TO_ROOT_AGE_RATIO: u64 = 10;
+
+/// Tunable pool parameters
+///
+/// Values that used to be compile-time constants live here so operators can
+/// adjust them through `update_pool_config` without redeploying the program.
+///
+/// The other tunables `update_pool_config` sets stay in PoolState on purpose:
+/// - Deposit limits, denominations and the outflow limit are checked on
+///   every deposit and withdrawal next to the counters they bound
+///   (denomination_deposits, the outflow epoch), so keeping them in PoolState
+///   costs those hot paths no extra account
+/// - authority_delay_slots and its queued decrease belong to the authority
+///   rotation state (pending_authority, pending_per_authority) they gate
+///
+/// New tunables go here, so they need no PoolState migration.
+///
+/// LIFECYCLE:
+/// - Created by `init_pool_config` with the compile-time defaults
+/// - Once created, `PoolState.pool_config_initialized` is set and every
+///   instruction that reads these values requires the account
+#[account]
+#[derive(InitSpace)]
+pub struct PoolConfig {
+    /// Account structure version
+    /// SECURITY (LOW-03): Versioning for future-proof upgrades
+    pub version: u8,
+
+    /// The pool this config belongs to
+    pub pool: Pubkey,
+
+    /// Maximum age of a merkle root for spending proofs (in slots)
+    /// SECURITY (HIGH-01): Replaces MAX_ROOT_AGE_SLOTS
+    pub max_root_age_slots: u64,
+
+    /// Minimum age before a nullifier can be cleaned up (in slots)
+    /// SECURITY (LOW-02): Replaces MIN_NULLIFIER_AGE_FOR_CLEANUP
+    pub min_nullifier_age_for_cleanup: u64,
+
+    /// Bump seed
+    pub bump: u8,
+
+    /// Reserved for future upgrades
+    #[max_len(64)]
+    pub _reserved: Vec<u8>,
+}
+
+impl PoolConfig {
+    /// Initialize with the compile-time defaults
+    pub fn init(&mut self, pool: Pubkey, bump: u8) {
+        self.version = POOL_CONFIG_VERSION;
+        self.pool = pool;
+        self.max_root_age_slots = MAX_ROOT_AGE_SLOTS;
+        self.min_nullifier_age_for_cleanup = MIN_NULLIFIER_AGE_FOR_CLEANUP;
+        self.bump = bump;
+        self._reserved = Vec::new();
+    }
+
+    /// Validate and apply a new root-age window
+    pub fn set_max_root_age_slots(&mut self, slots: u64) -> Result<()> {
+        let (min, max) = ROOT_AGE_SLOTS_BOUNDS;
+        require!((min..=max).contains(&slots), PoolError::InvalidConfigValue);
+        Self::check_cleanup_ratio(slots, self.min_nullifier_age_for_cleanup)?;
+        self.max_root_age_slots = slots;
+        Ok(())
+    }
+
+    /// Validate and apply a new nullifier cleanup age
+    pub fn set_min_nullifier_age_for_cleanup(&mut self, slots: u64) -> Result<()> {
+        require!(
+            slots <= MAX_NULLIFIER_CLEANUP_AGE_SLOTS,
+            PoolError::InvalidConfigValue
+        );
+        Self::check_cleanup_ratio(self.max_root_age_slots, slots)?;
+        self.min_nullifier_age_for_cleanup = slots;
+        Ok(())
+    }
+
+    fn check_cleanup_ratio(max_root_age_slots: u64, cleanup_age: u64) -> Result<()> {
+        let min_cleanup_age = max_root_age_slots
+            .checked_mul(MIN_CLEANUP_TO_ROOT_AGE_RATIO)
+            .ok_or(PoolError::Overflow)?;
+        require!(
+            cleanup_age >= min_cleanup_age,
+            PoolError::InvalidConfigValue
+        );
+        Ok(())
+    }
+
+    /// Root-age window for `pool`, from its config when one exists
+    ///
+    /// SECURITY: Once a pool has a PoolConfig it must be passed, so callers
+    /// can't fall back to the defaults by omitting it.
+    pub fn max_root_age_slots(pool: &PoolState, config: Option<&PoolConfig>) -> Result<u64> {
+        Ok(Self::resolve(pool, config)?.map_or(MAX_ROOT_AGE_SLOTS, |c| c.max_root_age_slots))
+    }
+
+    /// Nullifier cleanup age for `pool`, from its config when one exists
+    pub fn min_nullifier_age_for_cleanup(
+        pool: &PoolState,
+        config: Option<&PoolConfig>,
+    ) -> Result<u64> {
+        Ok(
+            Self::resolve(pool, config)?.map_or(MIN_NULLIFIER_AGE_FOR_CLEANUP, |c| {
+                c.min_nullifier_age_for_cleanup
+            }),
+        )
+    }
+
+    fn resolve<'a>(
+        pool: &PoolState,
+        config: Option<&'a PoolConfig>,
+    ) -> Result<Option<&'a PoolConfig>> {
+        if pool.pool_config_initialized {
+            require!(config.is_some(), PoolError::PoolConfigRequired);
+        }
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_config() -> PoolConfig {
+        let mut config = PoolConfig {
+            version: 0,
+            pool: Pubkey::default(),
+            max_root_age_slots: 0,
+            min_nullifier_age_for_cleanup: 0,
+            bump: 0,
+            _reserved: Vec::new(),
+        };
+        config.init(Pubkey::default(), 255);
+        config
+    }
+
+    #[test]
+    fn test_root_age_bounds() {
+        let mut config = create_test_config();
+        assert!(config.set_max_root_age_slots(149).is_err());
+        assert!(config.set_max_root_age_slots(1_800).is_ok());
+        assert_eq!(config.max_root_age_slots, 1_800);
+
+        // 18000 cleanup age caps the root age at 1800 (10x ratio)
+        assert!(config.set_max_root_age_slots(1_801).is_err());
+    }
+
+    #[test]
+    fn test_cleanup_age_keeps_ratio() {
+        let mut config = create_test_config();
+        assert!(config.set_min_nullifier_age_for_cleanup(8_999).is_err());
+        assert!(config.set_min_nullifier_age_for_cleanup(9_000).is_ok());
+        assert!(config
+            .set_min_nullifier_age_for_cleanup(MAX_NULLIFIER_CLEANUP_AGE_SLOTS + 1)
+            .is_err());
+    }
+}
@@ -1,3 +1,5 @@
+use crate::errors::PoolError;
+use crate::state::circuit_vk::{CircuitVk, MAX_POOL_CIRCUITS};
 use anchor_lang::prelude::*;
 
 /// Configuration: Inline historical roots ring buffer size (in PoolState)
@@ -34,10 +36,30 @@ pub const MAX_ROOT_AGE_SLOTS: u64 = 900;
 /// - Also helps with compute unit exhaustion attacks
 pub const MIN_DEPOSIT_LAMPORTS: u64 = 1_000_000;
 
-/// Minimum deposit amount for SPL tokens (1000 units with 6 decimals = 0.001)
+/// Smallest configurable minimum deposit, as a fraction of one whole token
 ///
-/// For tokens with different decimals, this should be adjusted
-pub const MIN_DEPOSIT_SPL_UNITS: u64 = 1_000;
+/// SECURITY (MEDIUM-04): `min_deposit` may not go below 10^decimals / 1000
+/// (0.001 tokens), e.g. 1000 units at 6 decimals, 1 unit at 0 decimals
+pub const MIN_DEPOSIT_TOKEN_FRACTION: u64 = 1_000;
+
+/// Upper bound for the authority change delay (~1 day at 0.4s per slot)
+pub const MAX_AUTHORITY_DELAY_SLOTS: u64 = 216_000;
+
+/// Timelock between enabling emergency mode and the first emergency claim
+/// (~1 day at 0.4s per slot), giving users time to react before funds can leave
+pub const EMERGENCY_CLAIM_DELAY_SLOTS: u64 = 216_000;
+
+/// Basis-point denominator for the outflow limit
+pub const OUTFLOW_LIMIT_BPS_DENOMINATOR: u16 = 10_000;
+
+/// Bounds for `outflow_epoch_slots`: ~1 minute to ~1 week at 0.4s per slot
+pub const OUTFLOW_EPOCH_SLOTS_BOUNDS: (u64, u64) = (150, 1_512_000);
+
+/// Maximum number of pause-only guardians
+pub const MAX_GUARDIANS: usize = 4;
+
+/// Maximum number of fixed denominations a pool can enable
+pub const MAX_DENOMINATIONS: usize = 8;
 
 #[account]
 #[derive(InitSpace)]
@@ -82,14 +104,14 @@ pub struct PoolState {
     /// Pool's token vault (holds all shielded tokens)
     pub token_vault: Pubkey,
 
-    /// Verification key hash (ensures correct circuit)
-    /// SECURITY (HIGH-02): Must match loaded VK at verification time
-    pub vk_hash: [u8; 32],
+    /// Single verification key hash of version-2 pools, superseded by
+    /// circuit_vks. Unused; kept so those accounts keep their layout
+    pub legacy_vk_hash: [u8; 32],
 
     /// Pause flag for emergencies
     pub paused: bool,
 
-    /// Emergency mode - allows emergency withdrawals when true
+    /// Emergency mode - allows emergency claims when true
     /// SECURITY (LOW-01): Emergency withdrawal mechanism
     pub emergency_mode: bool,
 
@@ -99,20 +121,447 @@ pub struct PoolState {
     pub total_nullifiers: u64,
 
     /// Last nullifiers root for batch verification
+    /// Most recent batch only - per-batch history lives in SettledBatch PDAs
     pub last_nullifiers_root: [u8; 32],
 
     /// Bump seed for PDA
     pub bump: u8,
 
+    // Fields below were added after version 2. Only append here, so
+    // `migrate_pool_state` can extend a version-2 account in place.
+    /// Proposed new authority (zero if none), set by propose_authority
+    pub pending_authority: Pubkey,
+
+    /// First slot at which pending_authority may accept
+    pub pending_authority_slot: u64,
+
+    /// Queued PER authority rotation (zero if none), set by rotate_per_authority
+    pub pending_per_authority: Pubkey,
+
+    /// First slot at which pending_per_authority takes effect
+    pub pending_per_authority_slot: u64,
+
+    /// Delay (in slots) between proposing and applying an authority change
+    /// Gives watchers time to react to a compromised admin key
+    pub authority_delay_slots: u64,
+
+    /// Queued decrease of authority_delay_slots (see `set_authority_delay_slots`)
+    pub pending_authority_delay_slots: u64,
+
+    /// First slot at which pending_authority_delay_slots applies (zero if none)
+    pub pending_authority_delay_slot: u64,
+
+    /// Guardians may only pause the pool (e.g. hot keys held by monitors)
+    /// SECURITY: Cannot unpause, enter emergency mode or move funds
+    pub guardians: [Pubkey; MAX_GUARDIANS],
+    pub guardian_count: u8,
+
+    /// Native SOL mode: lamports are held in the SOL vault PDA instead of
+    /// `token_vault`, and deposits/withdrawals move plain SOL
+    pub native_sol: bool,
+
+    /// Decimals of `token_mint` (deposit limits are validated against it)
+    pub token_decimals: u8,
+
+    /// Minimum credited amount per deposit (base units)
+    /// SECURITY (MEDIUM-04): Spam protection, see `min_deposit_floor`
+    pub min_deposit: u64,
+
+    /// Maximum credited amount per deposit (base units)
+    pub max_deposit: u64,
+
+    /// Fixed-denomination mode: when denomination_count > 0, deposits and
+    /// withdrawals must equal one of denominations[..denomination_count]
+    /// Stronger anonymity: amounts no longer fingerprint individual deposits
+    pub denominations: [u64; MAX_DENOMINATIONS],
+    pub denomination_count: u8,
+
+    /// Deposits per denomination (anonymity-set size shown by integrators)
+    pub denomination_deposits: [u64; MAX_DENOMINATIONS],
+
+    /// Accepted verification key hashes per circuit, with staged rotation
+    /// SECURITY (HIGH-02): The loaded VK must hash to an accepted key
+    pub circuit_vks: [CircuitVk; MAX_POOL_CIRCUITS],
+
+    /// Outflow circuit breaker: at most outflow_limit_bps of the epoch's opening
+    /// total_shielded may leave the vault per outflow_epoch_slots (0 = disabled)
+    /// SECURITY: A payout that would exceed it pauses the pool instead
+    pub outflow_limit_bps: u16,
+    pub outflow_epoch_slots: u64,
+
+    /// Current outflow epoch: start slot (0 = none open yet), opening
+    /// total_shielded and amount paid out so far
+    pub outflow_epoch_start_slot: u64,
+    pub outflow_epoch_base: u64,
+    pub outflow_epoch_amount: u64,
+
+    /// Commitment root frozen when emergency mode was enabled
+    /// Emergency claims prove membership against this root
+    pub emergency_root: [u8; 32],
+
+    /// Slot at which emergency mode was enabled (claims open after the timelock)
+    pub emergency_activated_slot: u64,
+
+    /// Number of emergency claims paid out
+    /// SECURITY: Once non-zero, emergency mode can no longer be disabled
+    pub emergency_claims: u64,
+
+    /// Sequence number of the next batch settlement (= number of settled batches)
+    /// Seeds the SettledBatch PDA created by settle_batch
+    pub batch_sequence: u64,
+
+    /// Set once `init_pool_config` created the PoolConfig PDA
+    /// SECURITY: From then on the PoolConfig account is mandatory wherever it's read
+    pub pool_config_initialized: bool,
+
     /// Reserved for future upgrades
     #[max_len(64)]
     pub _reserved: Vec<u8>,
 }
 
 /// Current account version
-pub const POOL_STATE_VERSION: u8 = 2;
+pub const POOL_STATE_VERSION: u8 = 13;
+
+/// Version of the pools created before the post-v2 fields were appended
+/// (see `migrate_pool_state`)
+pub const LEGACY_POOL_STATE_VERSION: u8 = 2;
 
 impl PoolState {
+    /// Bring a version-2 pool, already resized with its new fields zeroed, to
+    /// the current version
+    ///
+    /// Zero is the initialize default of every appended field except the mint
+    /// decimals and deposit limits. Version-2 pools are SPL pools with one
+    /// global VK hash, so their circuit keys must be staged afterwards.
+    pub fn migrate_from_v2(
+        &mut self,
+        token_decimals: u8,
+        min_deposit: u64,
+        max_deposit: u64,
+    ) -> Result<()> {
+        require!(
+            self.version == LEGACY_POOL_STATE_VERSION,
+            PoolError::UnsupportedPoolVersion
+        );
+        self.token_decimals = token_decimals;
+        self.set_deposit_limits(min_deposit, max_deposit)?;
+        self.version = POOL_STATE_VERSION;
+        self._reserved = Vec::new();
+        Ok(())
+    }
+
+    /// Lowest `min_deposit` allowed for this pool
+    /// SECURITY (MEDIUM-04): Native SOL pools keep the MIN_DEPOSIT_LAMPORTS floor
+    pub fn min_deposit_floor(&self) -> u64 {
+        if self.native_sol {
+            return MIN_DEPOSIT_LAMPORTS;
+        }
+        min_deposit_floor(self.token_decimals)
+    }
+
+    /// Validate and apply new deposit limits
+    ///
+    /// Active denominations must stay within the new limits.
+    pub fn set_deposit_limits(&mut self, min_deposit: u64, max_deposit: u64) -> Result<()> {
+        require!(
+            min_deposit >= self.min_deposit_floor() && max_deposit >= min_deposit,
+            PoolError::InvalidDepositLimits
+        );
+        require!(
+            self.active_denominations()
+                .iter()
+                .all(|d| (min_deposit..=max_deposit).contains(d)),
+            PoolError::InvalidDepositLimits
+        );
+        self.min_deposit = min_deposit;
+        self.max_deposit = max_deposit;
+        Ok(())
+    }
+
+    /// Denominations in use (empty when the pool accepts arbitrary amounts)
+    pub fn active_denominations(&self) -> &[u64] {
+        &self.denominations[..self.denomination_count as usize]
+    }
+
+    /// Validate and apply the outflow circuit breaker (0 bps disables it)
+    ///
+    /// Restarts the epoch, so raising the limit also clears a tripped breaker
+    pub fn set_outflow_limit(&mut self, limit_bps: u16, epoch_slots: u64) -> Result<()> {
+        require!(
+            limit_bps <= OUTFLOW_LIMIT_BPS_DENOMINATOR,
+            PoolError::InvalidConfigValue
+        );
+        if limit_bps > 0 {
+            let (min, max) = OUTFLOW_EPOCH_SLOTS_BOUNDS;
+            require!(
+                (min..=max).contains(&epoch_slots),
+                PoolError::InvalidConfigValue
+            );
+        }
+
+        self.outflow_limit_bps = limit_bps;
+        self.outflow_epoch_slots = epoch_slots;
+        self.outflow_epoch_start_slot = 0;
+        self.outflow_epoch_base = 0;
+        self.outflow_epoch_amount = 0;
+        Ok(())
+    }
+
+    /// Maximum outflow of the current epoch
+    pub fn outflow_epoch_limit(&self) -> u64 {
+        (self.outflow_epoch_base as u128 * self.outflow_limit_bps as u128
+            / OUTFLOW_LIMIT_BPS_DENOMINATOR as u128) as u64
+    }
+
+    /// Count `amount` against the outflow limit
+    ///
+    /// Opens a new epoch (snapshotting total_shielded) when the previous one
+    /// ended. Returns false, without recording anything, if `amount` would
+    /// exceed the epoch limit; callers then pause the pool instead of paying out.
+    pub fn record_outflow(&mut self, amount: u64, current_slot: u64) -> bool {
+        if self.outflow_limit_bps == 0 {
+            return true;
+        }
+
+        let epoch_end = self
+            .outflow_epoch_start_slot
+            .saturating_add(self.outflow_epoch_slots);
+        if self.outflow_epoch_start_slot == 0 || current_slot >= epoch_end {
+            self.outflow_epoch_start_slot = current_slot;
+            self.outflow_epoch_base = self.total_shielded;
+            self.outflow_epoch_amount = 0;
+        }
+
+        match self.outflow_epoch_amount.checked_add(amount) {
+            Some(total) if total <= self.outflow_epoch_limit() => {
+                self.outflow_epoch_amount = total;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Active guardian keys
+    pub fn active_guardians(&self) -> &[Pubkey] {
+        &self.guardians[..self.guardian_count as usize]
+    }
+
+    /// Verification key state for `circuit_id`, if any key was staged
+    pub fn circuit_vk(&self, circuit_id: &[u8; 32]) -> Option<&CircuitVk> {
+        self.circuit_vks
+            .iter()
+            .find(|entry| entry.circuit_id == *circuit_id)
+    }
+
+    /// SECURITY (HIGH-02): Require the VK used for a `circuit_id` proof to be
+    /// one the pool currently accepts
+    pub fn require_vk(
+        &self,
+        circuit_id: &[u8; 32],
+        vk_hash: &[u8; 32],
+        current_slot: u64,
+    ) -> Result<()> {
+        require!(
+            self.circuit_vk(circuit_id)
+                .is_some_and(|entry| entry.accepts(vk_hash, current_slot)),
+            PoolError::VerificationKeyHashMismatch
+        );
+        Ok(())
+    }
+
+    /// Stage a verification key for a circuit (see CircuitVk::stage)
+    ///
+    /// The caller checks that the circuit is registered in the pool's
+    /// CircuitRegistry.
+    pub fn stage_vk(
+        &mut self,
+        circuit_id: [u8; 32],
+        vk_hash: [u8; 32],
+        version: u32,
+        current_slot: u64,
+    ) -> Result<CircuitVk> {
+        let index = match self
+            .circuit_vks
+            .iter()
+            .position(|entry| entry.circuit_id == circuit_id)
+        {
+            Some(index) => index,
+            None => self
+                .circuit_vks
+                .iter()
+                .position(|entry| entry.circuit_id == [0u8; 32])
+                .ok_or(PoolError::InvalidConfigValue)?,
+        };
+
+        let entry = &mut self.circuit_vks[index];
+        entry.circuit_id = circuit_id;
+        entry.stage(vk_hash, version, current_slot)?;
+        Ok(*entry)
+    }
+
+    /// Check if `key` is a guardian
+    pub fn is_guardian(&self, key: &Pubkey) -> bool {
+        self.active_guardians().contains(key)
+    }
+
+    /// Authority change delay in force at `current_slot`
+    ///
+    /// Applies a queued decrease once it has matured.
+    pub fn authority_delay(&mut self, current_slot: u64) -> u64 {
+        if self.pending_authority_delay_slot != 0
+            && current_slot >= self.pending_authority_delay_slot
+        {
+            self.authority_delay_slots = self.pending_authority_delay_slots;
+            self.pending_authority_delay_slots = 0;
+            self.pending_authority_delay_slot = 0;
+        }
+        self.authority_delay_slots
+    }
+
+    /// Validate and apply a new authority change delay, returning the slot it
+    /// takes effect
+    ///
+    /// Increases apply immediately. A decrease is queued behind the current
+    /// delay, so a compromised admin key cannot lower the delay and push an
+    /// authority change through before watchers see it. A new call replaces
+    /// any queued decrease.
+    pub fn set_authority_delay_slots(&mut self, slots: u64, current_slot: u64) -> Result<u64> {
+        require!(
+            slots <= MAX_AUTHORITY_DELAY_SLOTS,
+            PoolError::InvalidConfigValue
+        );
+
+        let current_delay = self.authority_delay(current_slot);
+        self.pending_authority_delay_slots = 0;
+        self.pending_authority_delay_slot = 0;
+        if slots >= current_delay {
+            self.authority_delay_slots = slots;
+            return Ok(current_slot);
+        }
+
+        let effective_slot = current_slot
+            .checked_add(current_delay)
+            .ok_or(PoolError::Overflow)?;
+        self.pending_authority_delay_slots = slots;
+        self.pending_authority_delay_slot = effective_slot;
+        Ok(effective_slot)
+    }
+
+    /// Propose `new_authority` (Pubkey::default() cancels), returning the
+    /// first slot it may accept
+    pub fn propose_authority(&mut self, new_authority: Pubkey, current_slot: u64) -> Result<u64> {
+        let effective_slot = current_slot
+            .checked_add(self.authority_delay(current_slot))
+            .ok_or(PoolError::Overflow)?;
+        self.pending_authority = new_authority;
+        self.pending_authority_slot = effective_slot;
+        Ok(effective_slot)
+    }
+
+    /// Hand the pool to the pending authority once its delay has elapsed
+    pub fn accept_authority(&mut self, current_slot: u64) -> Result<()> {
+        require!(
+            self.pending_authority != Pubkey::default(),
+            PoolError::NoPendingAuthority
+        );
+        require!(
+            current_slot >= self.pending_authority_slot,
+            PoolError::AuthorityDelayNotElapsed
+        );
+        self.authority = self.pending_authority;
+        self.pending_authority = Pubkey::default();
+        self.pending_authority_slot = 0;
+        Ok(())
+    }
+
+    /// Queue or apply a PER authority rotation (see `rotate_per_authority`),
+    /// returning whether it was applied and its effective slot
+    pub fn rotate_per_authority(
+        &mut self,
+        new_per_authority: Pubkey,
+        current_slot: u64,
+    ) -> Result<(bool, u64)> {
+        // CRITICAL-05: Same validation as initialize
+        require!(
+            new_per_authority != Pubkey::default(),
+            PoolError::InvalidPerAuthority
+        );
+
+        let queued = self.pending_per_authority == new_per_authority;
+        if !queued {
+            self.pending_per_authority = new_per_authority;
+            self.pending_per_authority_slot = current_slot
+                .checked_add(self.authority_delay(current_slot))
+                .ok_or(PoolError::Overflow)?;
+        }
+
+        let effective_slot = self.pending_per_authority_slot;
+        let applied = current_slot >= effective_slot;
+        if applied {
+            self.per_authority = new_per_authority;
+            self.pending_per_authority = Pubkey::default();
+            self.pending_per_authority_slot = 0;
+        } else {
+            require!(!queued, PoolError::AuthorityDelayNotElapsed);
+        }
+        Ok((applied, effective_slot))
+    }
+
+    /// Validate and apply a new guardian list (empty removes all guardians)
+    pub fn set_guardians(&mut self, guardians: &[Pubkey]) -> Result<()> {
+        require!(
+            guardians.len() <= MAX_GUARDIANS,
+            PoolError::InvalidGuardians
+        );
+        for (i, guardian) in guardians.iter().enumerate() {
+            require!(
+                *guardian != Pubkey::default() && !guardians[..i].contains(guardian),
+                PoolError::InvalidGuardians
+            );
+        }
+
+        self.guardians = [Pubkey::default(); MAX_GUARDIANS];
+        self.guardians[..guardians.len()].copy_from_slice(guardians);
+        self.guardian_count = guardians.len() as u8;
+        Ok(())
+    }
+
+    /// Validate and apply a new denomination list (empty disables the mode)
+    ///
+    /// SECURITY: Only allowed before the first deposit, so per-denomination
+    /// counters always describe the notes actually in the tree
+    pub fn set_denominations(&mut self, denominations: &[u64]) -> Result<()> {
+        require!(self.total_deposits == 0, PoolError::DenominationsLocked);
+        require!(
+            denominations.len() <= MAX_DENOMINATIONS
+                && denominations.windows(2).all(|w| w[0] < w[1])
+                && denominations
+                    .iter()
+                    .all(|d| (self.min_deposit..=self.max_deposit).contains(d)),
+            PoolError::InvalidDenominations
+        );
+
+        self.denominations = [0u64; MAX_DENOMINATIONS];
+        self.denominations[..denominations.len()].copy_from_slice(denominations);
+        self.denomination_count = denominations.len() as u8;
+        self.denomination_deposits = [0u64; MAX_DENOMINATIONS];
+        Ok(())
+    }
+
+    /// Check `amount` against the denomination list
+    ///
+    /// Returns the denomination index, or None when the mode is disabled.
+    pub fn check_denomination(&self, amount: u64) -> Result<Option<usize>> {
+        if self.denomination_count == 0 {
+            return Ok(None);
+        }
+        self.active_denominations()
+            .iter()
+            .position(|d| *d == amount)
+            .map(Some)
+            .ok_or_else(|| PoolError::InvalidDenomination.into())
+    }
+
     /// Check if a root is valid (current or in history) - DEPRECATED
     ///
     /// WARNING: This method does NOT enforce root expiration.
@@ -143,10 +592,23 @@ impl PoolState {
     /// * `true` if root is valid AND not expired
     /// * `false` if root doesn't exist OR is expired
     pub fn is_valid_root_with_expiration(&self, root: &[u8; 32], current_slot: u64) -> bool {
+        self.is_valid_root_within(root, current_slot, MAX_ROOT_AGE_SLOTS)
+    }
+
+    /// Check if a root is valid and at most `max_age_slots` old
+    ///
+    /// Same as `is_valid_root_with_expiration`, with the window taken from
+    /// the pool's PoolConfig instead of MAX_ROOT_AGE_SLOTS.
+    pub fn is_valid_root_within(
+        &self,
+        root: &[u8; 32],
+        current_slot: u64,
+        max_age_slots: u64,
+    ) -> bool {
         // Check current root first (most common case)
         if self.commitment_root == *root {
             // Verify current root is not too old
-            return current_slot.saturating_sub(self.commitment_root_slot) <= MAX_ROOT_AGE_SLOTS;
+            return current_slot.saturating_sub(self.commitment_root_slot) <= max_age_slots;
         }
 
         // Search historical roots with expiration check
@@ -161,7 +623,7 @@ impl PoolState {
                 }
 
                 // Check expiration
-                return current_slot.saturating_sub(root_slot) <= MAX_ROOT_AGE_SLOTS;
+                return current_slot.saturating_sub(root_slot) <= max_age_slots;
             }
         }
 
@@ -193,10 +655,370 @@ impl PoolState {
         self.commitment_root_slot = current_slot;
     }
 
-    /// Check if pool allows emergency withdrawals
+    /// Check if pool allows emergency claims at `current_slot`
     ///
     /// SECURITY (LOW-01): Emergency withdrawal mechanism
-    pub fn allows_emergency_withdrawal(&self) -> bool {
+    /// Claims only open EMERGENCY_CLAIM_DELAY_SLOTS after emergency mode was enabled
+    pub fn allows_emergency_claim(&self, current_slot: u64) -> bool {
         self.emergency_mode
+            && current_slot
+                >= self
+                    .emergency_activated_slot
+                    .saturating_add(EMERGENCY_CLAIM_DELAY_SLOTS)
+    }
+}
+
+/// Lowest `min_deposit` for a mint with `decimals` (0.001 tokens, at least 1 unit)
+pub fn min_deposit_floor(decimals: u8) -> u64 {
+    10u64
+        .checked_pow(decimals as u32)
+        .map_or(u64::MAX, |one_token| {
+            (one_token / MIN_DEPOSIT_TOKEN_FRACTION).max(1)
+        })
+}
+
+/// Authority-gated pool configuration change (see `update_pool_config`)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum PoolConfigUpdate {
+    /// Per-deposit limits in base units of the pool's mint
+    DepositLimits { min_deposit: u64, max_deposit: u64 },
+    /// Fixed denominations, strictly increasing (empty list disables the mode)
+    Denominations { denominations: Vec<u64> },
+    /// Delay in slots for authority changes (bounded by MAX_AUTHORITY_DELAY_SLOTS;
+    /// decreases only apply after the current delay)
+    AuthorityDelaySlots { slots: u64 },
+    /// Pause-only guardian keys (at most MAX_GUARDIANS, empty removes all)
+    Guardians { guardians: Vec<Pubkey> },
+    /// Outflow circuit breaker (0 bps disables; epoch within OUTFLOW_EPOCH_SLOTS_BOUNDS)
+    OutflowLimit { limit_bps: u16, epoch_slots: u64 },
+    /// Stage a verification key for a circuit (first key live immediately,
+    /// later ones after VK_ACTIVATION_DELAY_SLOTS; versions strictly increase)
+    StageVk {
+        circuit_id: [u8; 32],
+        vk_hash: [u8; 32],
+        version: u32,
+    },
+    /// Root-age window in slots (PoolConfig, bounded by ROOT_AGE_SLOTS_BOUNDS)
+    MaxRootAgeSlots { slots: u64 },
+    /// Nullifier cleanup age in slots (PoolConfig, >= 10x the root-age window)
+    MinNullifierAgeForCleanup { slots: u64 },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_min_deposit_floor_scales_with_decimals() {
+        assert_eq!(min_deposit_floor(0), 1);
+        assert_eq!(min_deposit_floor(6), 1_000);
+        assert_eq!(min_deposit_floor(9), MIN_DEPOSIT_LAMPORTS);
+        assert_eq!(min_deposit_floor(20), u64::MAX);
+    }
+
+    fn create_test_pool() -> PoolState {
+        PoolState {
+            version: POOL_STATE_VERSION,
+            authority: Pubkey::default(),
+            per_authority: Pubkey::default(),
+            pending_authority: Pubkey::default(),
+            pending_authority_slot: 0,
+            pending_per_authority: Pubkey::default(),
+            pending_per_authority_slot: 0,
+            authority_delay_slots: 0,
+            pending_authority_delay_slots: 0,
+            pending_authority_delay_slot: 0,
+            guardians: [Pubkey::default(); MAX_GUARDIANS],
+            guardian_count: 0,
+            commitment_root: [0u8; 32],
+            commitment_root_slot: 0,
+            historical_roots: [[0u8; 32]; HISTORICAL_ROOTS_SIZE],
+            historical_roots_slots: [0u64; HISTORICAL_ROOTS_SIZE],
+            roots_index: 0,
+            total_shielded: 0,
+            token_mint: Pubkey::default(),
+            token_vault: Pubkey::default(),
+            legacy_vk_hash: [0u8; 32],
+            native_sol: false,
+            token_decimals: 6,
+            min_deposit: 1_000,
+            max_deposit: u64::MAX,
+            denominations: [0u64; MAX_DENOMINATIONS],
+            denomination_count: 0,
+            denomination_deposits: [0u64; MAX_DENOMINATIONS],
+            circuit_vks: [CircuitVk::default(); MAX_POOL_CIRCUITS],
+            paused: false,
+            outflow_limit_bps: 0,
+            outflow_epoch_slots: 0,
+            outflow_epoch_start_slot: 0,
+            outflow_epoch_base: 0,
+            outflow_epoch_amount: 0,
+            emergency_mode: false,
+            emergency_root: [0u8; 32],
+            emergency_activated_slot: 0,
+            emergency_claims: 0,
+            total_deposits: 0,
+            total_withdrawals: 0,
+            total_nullifiers: 0,
+            last_nullifiers_root: [0u8; 32],
+            batch_sequence: 0,
+            pool_config_initialized: false,
+            bump: 255,
+            _reserved: Vec::new(),
+        }
+    }
+
+    /// PoolState as deployed at version 2
+    #[derive(AnchorSerialize)]
+    struct PoolStateV2 {
+        version: u8,
+        authority: Pubkey,
+        per_authority: Pubkey,
+        commitment_root: [u8; 32],
+        commitment_root_slot: u64,
+        historical_roots: [[u8; 32]; HISTORICAL_ROOTS_SIZE],
+        historical_roots_slots: [u64; HISTORICAL_ROOTS_SIZE],
+        roots_index: u16,
+        total_shielded: u64,
+        token_mint: Pubkey,
+        token_vault: Pubkey,
+        vk_hash: [u8; 32],
+        paused: bool,
+        emergency_mode: bool,
+        total_deposits: u64,
+        total_withdrawals: u64,
+        total_nullifiers: u64,
+        last_nullifiers_root: [u8; 32],
+        bump: u8,
+        _reserved: Vec<u8>,
+    }
+
+    /// Account size of a version-2 pool (8 + its INIT_SPACE)
+    const POOL_STATE_V2_SIZE: usize = 506;
+
+    #[test]
+    fn test_migrate_from_v2() {
+        let v2 = PoolStateV2 {
+            version: LEGACY_POOL_STATE_VERSION,
+            authority: Pubkey::new_unique(),
+            per_authority: Pubkey::new_unique(),
+            commitment_root: [1u8; 32],
+            commitment_root_slot: 100,
+            historical_roots: [[2u8; 32]; HISTORICAL_ROOTS_SIZE],
+            historical_roots_slots: [90u64; HISTORICAL_ROOTS_SIZE],
+            roots_index: 3,
+            total_shielded: 5_000,
+            token_mint: Pubkey::new_unique(),
+            token_vault: Pubkey::new_unique(),
+            vk_hash: [3u8; 32],
+            paused: true,
+            emergency_mode: false,
+            total_deposits: 7,
+            total_withdrawals: 2,
+            total_nullifiers: 2,
+            last_nullifiers_root: [4u8; 32],
+            bump: 254,
+            _reserved: Vec::new(),
+        };
+        let mut data = PoolState::DISCRIMINATOR.to_vec();
+        v2.serialize(&mut data).unwrap();
+        assert!(data.len() <= POOL_STATE_V2_SIZE);
+
+        // migrate_pool_state: the account grows in one instruction, zero-extended
+        let new_len = 8 + PoolState::INIT_SPACE;
+        assert!(new_len - POOL_STATE_V2_SIZE <= 10_240);
+        data.resize(new_len, 0);
+
+        let mut pool = PoolState::try_deserialize(&mut &data[..]).unwrap();
+        assert_eq!(pool.authority, v2.authority);
+        assert_eq!(pool.per_authority, v2.per_authority);
+        assert_eq!(pool.commitment_root, v2.commitment_root);
+        assert_eq!(pool.historical_roots_slots, v2.historical_roots_slots);
+        assert_eq!(pool.total_shielded, v2.total_shielded);
+        assert_eq!(pool.token_mint, v2.token_mint);
+        assert_eq!(pool.token_vault, v2.token_vault);
+        assert_eq!(pool.legacy_vk_hash, v2.vk_hash);
+        assert!(pool.paused);
+        assert_eq!(pool.last_nullifiers_root, v2.last_nullifiers_root);
+        assert_eq!(pool.bump, v2.bump);
+        assert_eq!(pool.pending_authority, Pubkey::default());
+        assert_eq!(pool.circuit_vks, [CircuitVk::default(); MAX_POOL_CIRCUITS]);
+        assert!(!pool.pool_config_initialized);
+
+        // Deposit limits are validated against the mint decimals
+        assert!(pool.migrate_from_v2(6, 999, 1_000_000).is_err());
+        pool.migrate_from_v2(6, 1_000, 1_000_000).unwrap();
+        assert_eq!(pool.version, POOL_STATE_VERSION);
+        assert_eq!((pool.min_deposit, pool.max_deposit), (1_000, 1_000_000));
+
+        // Only once
+        assert!(pool.migrate_from_v2(6, 1_000, 1_000_000).is_err());
+    }
+
+    #[test]
+    fn test_denominations() {
+        let mut pool = create_test_pool();
+        assert_eq!(pool.check_denomination(12_345).unwrap(), None);
+
+        pool.set_denominations(&[100_000, 1_000_000, 10_000_000])
+            .unwrap();
+        assert_eq!(pool.check_denomination(1_000_000).unwrap(), Some(1));
+        assert!(pool.check_denomination(12_345).is_err());
+
+        // Must be strictly increasing
+        assert!(pool.set_denominations(&[1_000_000, 100_000]).is_err());
+
+        // Locked once deposits exist
+        pool.total_deposits = 1;
+        assert!(pool.set_denominations(&[]).is_err());
+    }
+
+    #[test]
+    fn test_guardians() {
+        let mut pool = create_test_pool();
+        let a = Pubkey::new_unique();
+        let b = Pubkey::new_unique();
+
+        assert!(pool.set_guardians(&[a, a]).is_err());
+        assert!(pool.set_guardians(&[Pubkey::default()]).is_err());
+        assert!(pool
+            .set_guardians(&[Pubkey::new_unique(); MAX_GUARDIANS + 1])
+            .is_err());
+
+        pool.set_guardians(&[a, b]).unwrap();
+        assert!(pool.is_guardian(&a));
+        assert!(!pool.is_guardian(&Pubkey::default()));
+
+        pool.set_guardians(&[]).unwrap();
+        assert!(!pool.is_guardian(&a));
+    }
+
+    #[test]
+    fn test_outflow_limit() {
+        let mut pool = create_test_pool();
+        pool.total_shielded = 1_000_000;
+
+        // Disabled: anything goes
+        assert!(pool.record_outflow(1_000_000, 100));
+
+        assert!(pool.set_outflow_limit(10_001, 1_000).is_err());
+        assert!(pool.set_outflow_limit(1_000, 10).is_err());
+        pool.set_outflow_limit(1_000, 1_000).unwrap(); // 10% per 1000 slots
+
+        assert!(pool.record_outflow(60_000, 100));
+        assert!(pool.record_outflow(40_000, 500));
+        // Would exceed 100_000 in this epoch: rejected and not recorded
+        assert!(!pool.record_outflow(1, 1_099));
+        assert_eq!(pool.outflow_epoch_amount, 100_000);
+
+        // Next epoch snapshots the new total_shielded
+        pool.total_shielded = 900_000;
+        assert!(pool.record_outflow(90_000, 1_100));
+        assert!(!pool.record_outflow(1, 1_100));
+
+        // Reconfiguring restarts the epoch
+        pool.set_outflow_limit(2_000, 1_000).unwrap();
+        assert!(pool.record_outflow(180_000, 1_200));
+    }
+
+    #[test]
+    fn test_authority_delay_decrease_is_queued() {
+        let mut pool = create_test_pool();
+        let attacker = Pubkey::new_unique();
+        let per = Pubkey::new_unique();
+
+        assert!(pool
+            .set_authority_delay_slots(MAX_AUTHORITY_DELAY_SLOTS + 1, 0)
+            .is_err());
+        // Increases apply at once
+        assert_eq!(pool.set_authority_delay_slots(1_000, 100).unwrap(), 100);
+        assert_eq!(pool.authority_delay(100), 1_000);
+
+        // A proposal made before the decrease keeps its full delay
+        assert_eq!(pool.propose_authority(attacker, 200).unwrap(), 1_200);
+        assert_eq!(pool.set_authority_delay_slots(0, 300).unwrap(), 1_300);
+        assert!(pool.accept_authority(1_199).is_err());
+
+        // So does one proposed while the decrease is queued
+        assert_eq!(pool.propose_authority(attacker, 301).unwrap(), 1_301);
+        assert!(pool.accept_authority(1_300).is_err());
+        let (applied, effective_slot) = pool.rotate_per_authority(per, 301).unwrap();
+        assert!(!applied);
+        assert_eq!(effective_slot, 1_301);
+        assert!(pool.rotate_per_authority(per, 1_300).is_err());
+
+        pool.accept_authority(1_301).unwrap();
+        assert_eq!(pool.authority, attacker);
+        assert_eq!(
+            pool.rotate_per_authority(per, 1_301).unwrap(),
+            (true, 1_301)
+        );
+        assert_eq!(pool.per_authority, per);
+
+        // Once matured, the lower delay applies to new changes
+        assert_eq!(pool.authority_delay(1_300), 0);
+        assert_eq!(pool.propose_authority(attacker, 1_400).unwrap(), 1_400);
+
+        // An increase replaces a queued decrease
+        pool.set_authority_delay_slots(500, 1_500).unwrap();
+        pool.set_authority_delay_slots(10, 1_600).unwrap();
+        pool.set_authority_delay_slots(800, 1_700).unwrap();
+        assert_eq!(pool.authority_delay(5_000), 800);
+    }
+
+    #[test]
+    fn test_zk_verifier_reads_authority() {
+        let mut pool = create_test_pool();
+        pool.authority = Pubkey::new_unique();
+
+        let mut data = Vec::new();
+        pool.try_serialize(&mut data).unwrap();
+
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let info = AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &crate::ID,
+            false,
+            0,
+        );
+        assert_eq!(
+            zk_verifier::admin::pool_authority(&info).unwrap(),
+            pool.authority
+        );
+    }
+
+    #[test]
+    fn test_vk_per_circuit() {
+        use crate::state::proof::circuit_ids;
+
+        let mut pool = create_test_pool();
+        let (deposit_vk, withdraw_vk) = ([1u8; 32], [2u8; 32]);
+
+        assert!(pool
+            .require_vk(&circuit_ids::DEPOSIT, &deposit_vk, 0)
+            .is_err());
+
+        pool.stage_vk(circuit_ids::DEPOSIT, deposit_vk, 1, 0)
+            .unwrap();
+        pool.stage_vk(circuit_ids::WITHDRAW, withdraw_vk, 1, 0)
+            .unwrap();
+        pool.require_vk(&circuit_ids::DEPOSIT, &deposit_vk, 0)
+            .unwrap();
+        pool.require_vk(&circuit_ids::WITHDRAW, &withdraw_vk, 0)
+            .unwrap();
+        assert!(pool
+            .require_vk(&circuit_ids::WITHDRAW, &deposit_vk, 0)
+            .is_err());
+
+        // The table holds MAX_POOL_CIRCUITS circuits
+        for i in 2..MAX_POOL_CIRCUITS {
+            pool.stage_vk([i as u8; 32], deposit_vk, 1, 0).unwrap();
+        }
+        assert!(pool.stage_vk([0xff; 32], deposit_vk, 1, 0).is_err());
     }
 }
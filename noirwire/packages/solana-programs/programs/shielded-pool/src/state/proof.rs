@@ -6,15 +6,15 @@ pub use zk_verifier::groth16::Groth16Proof;
 /// Circuit identifiers for different proof types
 ///
 /// SECURITY (MEDIUM-02): Circuit ID Generation
-/// These IDs should be computed as keccak256 hash of the compiled circuit.
+/// Circuit IDs are derived and registered on-chain in the pool's zk-verifier
+/// CircuitRegistry:
+///   circuit_id = keccak256("noirwire.{name}.{version}" || vk_commitment)
 ///
-/// The expected format is:
-///   circuit_id = keccak256(circuit_name || circuit_version || vk_commitment)
+/// Instructions accept a verification key if its circuit is registered under
+/// the instruction's circuit name (see `names`), so new circuit versions or
+/// VK-bound IDs need no program upgrade.
 ///
-/// For production, these values MUST be regenerated after final Noir circuit compilation
-/// using the `compute_circuit_id` helper function below.
-///
-/// Current values are derived from:
+/// The constants below are the unbound v2 IDs, kept for clients and tests:
 /// - DEPOSIT: keccak256("noirwire.deposit.v2")
 /// - WITHDRAW: keccak256("noirwire.withdraw.v2")
 /// - TRANSFER: keccak256("noirwire.transfer.v2")
@@ -22,33 +22,14 @@ pub use zk_verifier::groth16::Groth16Proof;
 ///
 /// See: Security Audit 2026-01-26 MEDIUM-02
 pub mod circuit_ids {
-    use anchor_lang::solana_program::keccak;
+    pub use zk_verifier::registry::compute_circuit_id;
 
-    /// Compute circuit ID from circuit name, version, and VK commitment
-    ///
-    /// # Arguments
-    /// * `circuit_name` - Name of the circuit (e.g., "deposit", "withdraw")
-    /// * `version` - Circuit version string (e.g., "v2")
-    /// * `vk_commitment` - Optional keccak hash of the verification key data
-    ///
-    /// # Returns
-    /// A 32-byte circuit identifier
-    pub fn compute_circuit_id(
-        circuit_name: &str,
-        version: &str,
-        vk_commitment: Option<&[u8; 32]>,
-    ) -> [u8; 32] {
-        let base_string = format!("noirwire.{}.{}", circuit_name, version);
-
-        if let Some(vk) = vk_commitment {
-            // Include VK commitment for full circuit binding
-            let mut data = base_string.into_bytes();
-            data.extend_from_slice(vk);
-            keccak::hash(&data).to_bytes()
-        } else {
-            // Base circuit ID without VK binding
-            keccak::hash(base_string.as_bytes()).to_bytes()
-        }
+    /// Registry names of the circuits each instruction accepts
+    pub mod names {
+        pub const DEPOSIT: &str = "deposit";
+        pub const WITHDRAW: &str = "withdraw";
+        pub const TRANSFER: &str = "transfer";
+        pub const BATCH_SETTLEMENT: &str = "batch_settlement";
     }
 
     /// Deposit circuit: proves creation of a private balance commitment
@@ -82,26 +63,6 @@ pub mod circuit_ids {
         0x04, 0xaf, 0xca, 0xaf, 0x92, 0x74, 0x54, 0xaf, 0xb7, 0x8a, 0xf8, 0x0b, 0x7f, 0xdb, 0x6b,
         0xc4, 0x28,
     ];
-
-    /// Validate that a circuit ID matches one of the known circuits
-    pub fn is_valid_circuit_id(id: &[u8; 32]) -> bool {
-        *id == DEPOSIT || *id == WITHDRAW || *id == TRANSFER || *id == BATCH_SETTLEMENT
-    }
-
-    /// Get circuit name from ID (for logging/debugging)
-    pub fn circuit_name(id: &[u8; 32]) -> &'static str {
-        if *id == DEPOSIT {
-            "deposit"
-        } else if *id == WITHDRAW {
-            "withdraw"
-        } else if *id == TRANSFER {
-            "transfer"
-        } else if *id == BATCH_SETTLEMENT {
-            "batch_settlement"
-        } else {
-            "unknown"
-        }
-    }
 }
 
 /// Proof data for deposit operation
@@ -137,7 +98,7 @@ impl DepositProofData {
 }
 
 /// Proof data for withdrawal operation
-/// Public inputs: [amount, recipient, nullifier, old_root, new_root]
+/// Public inputs: [amount, recipient, nullifier, old_root, new_root, relayer, fee]
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct WithdrawProofData {
     /// Groth16 proof (A, B, C points on BN254)
@@ -152,6 +113,10 @@ pub struct WithdrawProofData {
     pub old_root: [u8; 32],
     /// Public input: merkle root after withdrawal (nullifier leaf zeroed)
     pub new_root: [u8; 32],
+    /// Public input: relayer address paid the fee (zero if self-submitted)
+    pub relayer: [u8; 32],
+    /// Public input: fee paid to the relayer out of `amount`
+    pub fee: [u8; 32],
 }
 
 impl WithdrawProofData {
@@ -163,43 +128,59 @@ impl WithdrawProofData {
             self.nullifier,
             self.old_root,
             self.new_root,
+            self.relayer,
+            self.fee,
         ]
     }
 }
 
 /// Proof data for private transfer operation
-/// Public inputs: [amount, sender_old_root, sender_new_root, receiver_old_root, receiver_new_root]
+/// Public inputs: [nullifier, old_root, new_root, new_sender_commitment, receiver_commitment]
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct TransferProofData {
     /// Groth16 proof
     pub proof: Groth16Proof,
-    /// Public input: amount being transferred
-    pub amount: [u8; 32],
-    /// Public input: sender's merkle root before transfer
-    pub sender_old_root: [u8; 32],
-    /// Public input: sender's merkle root after transfer
-    pub sender_new_root: [u8; 32],
-    /// Public input: receiver's merkle root before transfer
-    pub receiver_old_root: [u8; 32],
-    /// Public input: receiver's merkle root after transfer
-    pub receiver_new_root: [u8; 32],
+    /// Public input: nullifier of the sender's spent balance
+    pub nullifier: [u8; 32],
+    /// Public input: merkle root the sender's balance is proven against
+    pub old_root: [u8; 32],
+    /// Public input: merkle root after nullifying the sender and inserting both commitments
+    pub new_root: [u8; 32],
+    /// Public input: sender's remainder commitment (zero if the balance was fully spent)
+    pub new_sender_commitment: [u8; 32],
+    /// Public input: receiver's new balance commitment
+    pub receiver_commitment: [u8; 32],
 }
 
 impl TransferProofData {
     /// Extract public inputs as array for verification
     pub fn public_inputs(&self) -> Vec<[u8; 32]> {
         vec![
-            self.amount,
-            self.sender_old_root,
-            self.sender_new_root,
-            self.receiver_old_root,
-            self.receiver_new_root,
+            self.nullifier,
+            self.old_root,
+            self.new_root,
+            self.new_sender_commitment,
+            self.receiver_commitment,
         ]
     }
 }
 
+/// Maximum public withdrawals paid out by a single batch settlement
+/// Bounded by compute (one token transfer each) and by transaction account limits
+pub const MAX_BATCH_WITHDRAWALS: usize = 8;
+
+/// Public withdrawal executed inside the PER and paid out on L1 by settle_batch
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct BatchWithdrawal {
+    /// Owner of the token account receiving the payout
+    pub recipient: Pubkey,
+    /// Amount paid out from the pool vault
+    pub amount: u64,
+}
+
 /// Proof data for batch settlement operation
-/// Public inputs: [old_root, new_root, nullifiers_root, nullifier_count]
+/// Public inputs: [old_root, new_root, nullifiers_root, nullifier_count, withdrawals_hash]
+/// (the `main` order of the noir-circuits batch_N packages)
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct BatchSettlementProofData {
     /// Groth16 proof
@@ -212,6 +193,9 @@ pub struct BatchSettlementProofData {
     pub nullifiers_root: [u8; 32],
     /// Public input: number of nullifiers in the batch
     pub nullifier_count: [u8; 32],
+    /// Public input: commitment to the batch's public withdrawals
+    /// See `compute_withdrawals_hash` for the encoding
+    pub withdrawals_hash: [u8; 32],
 }
 
 impl BatchSettlementProofData {
@@ -222,10 +206,116 @@ impl BatchSettlementProofData {
             self.new_root,
             self.nullifiers_root,
             self.nullifier_count,
+            self.withdrawals_hash,
         ]
     }
 }
 
+/// Poseidon2 inputs of `compute_withdrawals_hash`: the count, then three
+/// fields per withdrawal slot
+pub const WITHDRAWALS_HASH_INPUTS: usize = 1 + 3 * MAX_BATCH_WITHDRAWALS;
+
+/// Commitment to a batch's public withdrawals, bound by the batch proof
+///
+/// Encoding (circuit side: `primitives::withdrawals::compute_withdrawals_hash`):
+/// - Poseidon2 over [count, recipient_hi, recipient_lo, amount, ...], padded
+///   with zeros to MAX_BATCH_WITHDRAWALS slots (WITHDRAWALS_HASH_INPUTS fields)
+/// - The recipient pubkey is split into two 128-bit halves so each fits the
+///   BN254 field
+/// - An empty list commits to zero
+pub fn compute_withdrawals_hash(withdrawals: &[BatchWithdrawal]) -> Result<[u8; 32]> {
+    if withdrawals.is_empty() {
+        return Ok([0u8; 32]);
+    }
+    require!(
+        withdrawals.len() <= MAX_BATCH_WITHDRAWALS,
+        crate::errors::PoolError::TooManyBatchWithdrawals
+    );
+
+    let mut fields = [[0u8; 32]; WITHDRAWALS_HASH_INPUTS];
+    fields[0] = u64_to_field(withdrawals.len() as u64);
+    for (withdrawal, slot) in withdrawals.iter().zip(fields[1..].chunks_mut(3)) {
+        let recipient = withdrawal.recipient.to_bytes();
+        slot[0][16..].copy_from_slice(&recipient[..16]);
+        slot[1][16..].copy_from_slice(&recipient[16..]);
+        slot[2] = u64_to_field(withdrawal.amount);
+    }
+
+    crate::poseidon2::hash(&fields)
+}
+
+/// Domain tag of balance commitments (`primitives::commitment::COMMITMENT_DOMAIN`)
+pub const COMMITMENT_DOMAIN: u64 = 0x01;
+
+/// Domain tag of nullifiers (`primitives::nullifier::NULLIFIER_DOMAIN`)
+pub const NULLIFIER_DOMAIN: u64 = 0x02;
+
+/// Opening of a balance commitment, revealed to claim it in emergency mode
+///
+/// Carries exactly the private inputs the withdraw circuit uses to spend a
+/// balance, so an emergency claim requires the same knowledge as a withdrawal.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct EmergencyClaimData {
+    /// Balance owner (Poseidon2 hash of the owner's secret key)
+    pub owner: [u8; 32],
+    /// Full balance amount, paid out to the recipient
+    pub amount: u64,
+    /// 0 for solo users, vault hash for members
+    pub vault_id: [u8; 32],
+    /// Commitment blinding factor
+    pub blinding: [u8; 32],
+    /// Secret bound into the balance's nullifier
+    pub nullifier_secret: [u8; 32],
+    /// Nullifier nonce
+    pub nonce: [u8; 32],
+    /// Merkle siblings from leaf to root (TREE_DEPTH entries)
+    pub merkle_proof: Vec<[u8; 32]>,
+    /// 0 = current node is the left child, 1 = right child
+    pub path_indices: Vec<u8>,
+}
+
+impl EmergencyClaimData {
+    /// `compute_commitment`: Poseidon2([COMMITMENT_DOMAIN, owner, amount, vault_id, blinding])
+    pub fn commitment(&self) -> Result<[u8; 32]> {
+        crate::poseidon2::hash(&[
+            u64_to_field(COMMITMENT_DOMAIN),
+            self.owner,
+            u64_to_field(self.amount),
+            self.vault_id,
+            self.blinding,
+        ])
+    }
+
+    /// `compute_nullifier`: Poseidon2([NULLIFIER_DOMAIN, commitment, nullifier_secret, nonce])
+    pub fn nullifier(&self, commitment: &[u8; 32]) -> Result<[u8; 32]> {
+        crate::poseidon2::hash(&[
+            u64_to_field(NULLIFIER_DOMAIN),
+            *commitment,
+            self.nullifier_secret,
+            self.nonce,
+        ])
+    }
+
+    /// keccak256(owner || amount || vault_id || blinding || nullifier_secret ||
+    /// nonce || recipient || salt), recorded by `commit_emergency_claim`
+    ///
+    /// Covers the secrets of the opening, so nobody can commit to a claim
+    /// before its owner reveals it.
+    pub fn claim_hash(&self, recipient: &Pubkey, salt: &[u8; 32]) -> [u8; 32] {
+        anchor_lang::solana_program::keccak::hashv(&[
+            &self.owner,
+            &u64_to_field(self.amount),
+            &self.vault_id,
+            &self.blinding,
+            &self.nullifier_secret,
+            &self.nonce,
+            recipient.as_ref(),
+            salt,
+        ])
+        .to_bytes()
+    }
+}
+
 /// Helper function to convert u64 to big-endian [u8; 32]
 pub fn u64_to_field(value: u64) -> [u8; 32] {
     let mut result = [0u8; 32];
@@ -282,3 +372,47 @@ pub fn field_to_u64(field: &[u8; 32]) -> Result<u64> {
 
     Ok(value)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Circuit-side encoding, whose tests assert the vector below
+    const NOIR_WITHDRAWALS: &str =
+        include_str!("../../../../../noir-circuits/circuits/src/primitives/withdrawals.nr");
+
+    fn withdrawal(recipient: [u8; 32], amount: u64) -> BatchWithdrawal {
+        BatchWithdrawal {
+            recipient: Pubkey::new_from_array(recipient),
+            amount,
+        }
+    }
+
+    #[test]
+    fn test_withdrawals_hash_matches_noir() {
+        let recipient: [u8; 32] = core::array::from_fn(|i| i as u8 + 1);
+        let hash = compute_withdrawals_hash(&[
+            withdrawal(recipient, 1_000_000),
+            withdrawal([0xab; 32], 42),
+        ])
+        .unwrap();
+
+        let hex: String = hash.iter().map(|b| format!("{:02x}", b)).collect();
+        assert!(NOIR_WITHDRAWALS.contains(&format!("== 0x{}", hex)));
+        assert!(NOIR_WITHDRAWALS.contains(&format!(
+            "global MAX_BATCH_WITHDRAWALS: u32 = {};",
+            MAX_BATCH_WITHDRAWALS
+        )));
+    }
+
+    #[test]
+    fn test_withdrawals_hash_bounds() {
+        assert_eq!(compute_withdrawals_hash(&[]).unwrap(), [0u8; 32]);
+
+        let full = vec![withdrawal([1; 32], 1); MAX_BATCH_WITHDRAWALS];
+        assert_ne!(compute_withdrawals_hash(&full).unwrap(), [0u8; 32]);
+
+        let too_many = vec![withdrawal([1; 32], 1); MAX_BATCH_WITHDRAWALS + 1];
+        assert!(compute_withdrawals_hash(&too_many).is_err());
+    }
+}
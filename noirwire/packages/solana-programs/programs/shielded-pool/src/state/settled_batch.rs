@@ -0,0 +1,167 @@
+use crate::errors::PoolError;
+use crate::instructions::record_nullifiers_batch::MAX_NULLIFIER_TREE_DEPTH;
+use anchor_lang::prelude::*;
+
+/// Seeds for deriving a SettledBatch PDA: ["settled_batch", pool, sequence (u64 LE)]
+pub const SETTLED_BATCH_SEED: &[u8] = b"settled_batch";
+
+/// Record of one batch settlement
+///
+/// `settle_batch` creates one PDA per batch, keyed by the pool's batch sequence
+/// number, instead of overwriting a single `last_nullifiers_root`. Nullifiers of
+/// a batch can therefore still be recorded after later batches have settled.
+///
+/// LIFECYCLE:
+/// - Created by `settle_batch` with recorded_count = 0
+/// - `record_nullifier` / `record_nullifiers_batch` increment recorded_count,
+///   once per leaf proven at `depth`
+/// - Pending while recorded_count < nullifier_count
+#[account]
+#[derive(InitSpace)]
+pub struct SettledBatch {
+    /// The pool this batch was settled against
+    pub pool: Pubkey,
+
+    /// Batch sequence number (0-based, PoolState.batch_sequence at settlement)
+    pub sequence: u64,
+
+    /// Merkle root of all nullifiers in the batch
+    pub nullifiers_root: [u8; 32],
+
+    /// Number of nullifiers the batch proof committed to
+    pub nullifier_count: u32,
+
+    /// Depth of the batch nullifier tree (`nullifier_tree_depth(nullifier_count)`)
+    /// Nullifier proofs must be exactly this deep, so no internal node or
+    /// root can pass as a leaf
+    pub depth: u8,
+
+    /// Number of nullifier PDAs recorded so far
+    pub recorded_count: u32,
+
+    /// Slot when the batch was settled
+    pub settled_slot: u64,
+
+    /// Bump seed
+    pub bump: u8,
+}
+
+impl SettledBatch {
+    /// Check if the batch still has nullifiers to record
+    pub fn is_pending(&self) -> bool {
+        self.recorded_count < self.nullifier_count
+    }
+
+    /// Account for newly recorded leaves, proven at `depth`
+    ///
+    /// SECURITY: Only leaves count. A proof shorter than the batch tree would
+    /// open an internal node or the root, and positions at or past
+    /// nullifier_count are zero padding; neither may advance recorded_count.
+    /// Never records more nullifiers than the batch proof committed to.
+    pub fn record(&mut self, depth: usize, leaf_indices: &[u32]) -> Result<()> {
+        require!(
+            depth == self.depth as usize,
+            PoolError::InvalidNullifierProof
+        );
+        require!(
+            leaf_indices
+                .iter()
+                .all(|index| *index < self.nullifier_count),
+            PoolError::InvalidNullifierProof
+        );
+
+        let recorded = self
+            .recorded_count
+            .checked_add(leaf_indices.len() as u32)
+            .ok_or(PoolError::Overflow)?;
+        require!(
+            recorded <= self.nullifier_count,
+            PoolError::BatchFullyRecorded
+        );
+        self.recorded_count = recorded;
+        Ok(())
+    }
+}
+
+/// Depth of the nullifier tree over `nullifier_count` leaves
+///
+/// The batch circuit zero-pads its nullifiers to the next power of two, so
+/// the tree is ceil(log2(nullifier_count)) levels deep.
+pub fn nullifier_tree_depth(nullifier_count: u32) -> Result<u8> {
+    require!(
+        nullifier_count > 0 && nullifier_count <= 1u32 << MAX_NULLIFIER_TREE_DEPTH,
+        PoolError::InvalidNullifierCount
+    );
+    Ok(nullifier_count.next_power_of_two().trailing_zeros() as u8)
+}
+
+/// Helper function to derive a SettledBatch PDA
+pub fn find_settled_batch_pda(pool: &Pubkey, sequence: u64, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[SETTLED_BATCH_SEED, pool.as_ref(), &sequence.to_le_bytes()],
+        program_id,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_batch(nullifier_count: u32) -> SettledBatch {
+        SettledBatch {
+            pool: Pubkey::default(),
+            sequence: 0,
+            nullifiers_root: [1u8; 32],
+            nullifier_count,
+            depth: nullifier_tree_depth(nullifier_count).unwrap(),
+            recorded_count: 0,
+            settled_slot: 100,
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn test_record_until_complete() {
+        let mut batch = create_test_batch(3);
+        assert_eq!(batch.depth, 2);
+        assert!(batch.is_pending());
+
+        batch.record(2, &[0, 2]).unwrap();
+        assert!(batch.is_pending());
+
+        batch.record(2, &[1]).unwrap();
+        assert!(!batch.is_pending());
+    }
+
+    #[test]
+    fn test_record_only_counts_leaves() {
+        let mut batch = create_test_batch(3);
+
+        // Internal nodes (shorter proofs), the root and zero padding
+        assert!(batch.record(1, &[0, 1]).is_err());
+        assert!(batch.record(0, &[0]).is_err());
+        assert!(batch.record(3, &[0]).is_err());
+        assert!(batch.record(2, &[3]).is_err());
+        assert!(batch.record(2, &[0, 3]).is_err());
+        assert_eq!(batch.recorded_count, 0);
+    }
+
+    #[test]
+    fn test_nullifier_tree_depth() {
+        assert_eq!(nullifier_tree_depth(1).unwrap(), 0);
+        assert_eq!(nullifier_tree_depth(2).unwrap(), 1);
+        assert_eq!(nullifier_tree_depth(5).unwrap(), 3);
+        assert_eq!(nullifier_tree_depth(64).unwrap(), MAX_NULLIFIER_TREE_DEPTH);
+        assert!(nullifier_tree_depth(0).is_err());
+        assert!(nullifier_tree_depth(65).is_err());
+    }
+
+    #[test]
+    fn test_record_rejects_over_count() {
+        let mut batch = create_test_batch(2);
+        batch.record(1, &[0]).unwrap();
+
+        assert!(batch.record(1, &[0, 1]).is_err());
+        assert_eq!(batch.recorded_count, 1);
+    }
+}
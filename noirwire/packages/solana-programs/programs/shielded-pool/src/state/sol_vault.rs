@@ -0,0 +1,60 @@
+//! Native SOL vault helpers
+//!
+//! Native-SOL pools (`PoolState.native_sol`) keep shielded lamports in a
+//! system-owned PDA instead of a WSOL token account, so users deposit and
+//! withdraw plain SOL without wrapping.
+//!
+//! DESIGN:
+//! - `initialize` funds the vault with the rent-exempt minimum for 0 bytes
+//! - That reserve is never paid out; only lamports above it are withdrawable
+//! - Payouts are System Program transfers signed with the vault PDA seeds
+
+use crate::errors::PoolError;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+/// Seeds for deriving a pool's SOL vault PDA: ["sol_vault", pool]
+pub const SOL_VAULT_SEED: &[u8] = b"sol_vault";
+
+/// Lamports held by the SOL vault above its rent-exempt reserve
+pub fn available_lamports(sol_vault: &AccountInfo) -> Result<u64> {
+    let reserve = Rent::get()?.minimum_balance(0);
+    Ok(sol_vault.lamports().saturating_sub(reserve))
+}
+
+/// Pay `amount` lamports out of the pool's SOL vault PDA
+///
+/// SECURITY (CRITICAL-07): Fails if the payout would dip into the rent reserve
+pub fn transfer_from_sol_vault<'info>(
+    sol_vault: &AccountInfo<'info>,
+    to: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    pool: &Pubkey,
+    bump: u8,
+    amount: u64,
+) -> Result<()> {
+    require!(
+        available_lamports(sol_vault)? >= amount,
+        PoolError::InsufficientVaultBalance
+    );
+
+    let vault_seeds = &[SOL_VAULT_SEED, pool.as_ref(), &[bump]];
+    let signer_seeds = &[&vault_seeds[..]];
+
+    system_program::transfer(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            system_program::Transfer {
+                from: sol_vault.clone(),
+                to: to.clone(),
+            },
+            signer_seeds,
+        ),
+        amount,
+    )
+}
+
+/// Helper function to derive a pool's SOL vault PDA
+pub fn find_sol_vault_pda(pool: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[SOL_VAULT_SEED, pool.as_ref()], program_id)
+}
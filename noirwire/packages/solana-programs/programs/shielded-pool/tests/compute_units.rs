@@ -0,0 +1,1195 @@
+//! Compute-unit checks for the instructions that hash Poseidon2 on-chain, and
+//! program tests of native SOL pools
+//!
+//! Each compute-unit test runs the instruction at its largest accepted input
+//! and requires it to succeed under the 1.4M CU transaction limit.
+//!
+//! Runs against the SBF build: `cargo test-sbf -p shielded-pool -- --nocapture`
+//! (the deposit/withdraw test also loads `zk_verifier.so`, built by
+//! `anchor build`)
+
+#![cfg(feature = "test-sbf")]
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::keccak;
+use anchor_lang::system_program;
+use anchor_lang::{
+    AccountDeserialize, AccountSerialize, AnchorSerialize, Discriminator, InstructionData, Space,
+    ToAccountMetas,
+};
+use ark_bn254::{Fq, Fr, G1Affine, G2Affine};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{BigInteger, PrimeField};
+use shielded_pool::instructions::record_nullifiers_batch::{
+    MAX_NULLIFIERS_PER_RECORD_BATCH, MAX_NULLIFIER_TREE_DEPTH,
+};
+use shielded_pool::poseidon2;
+use shielded_pool::state::proof::circuit_ids::names;
+use shielded_pool::state::*;
+use solana_program_test::{BanksClient, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account, compute_budget::ComputeBudgetInstruction, hash::Hash, rent::Rent,
+    signature::Keypair, signer::Signer, transaction::Transaction,
+};
+use zk_verifier::registry::CIRCUIT_REGISTRY_SEED;
+use zk_verifier::registry::{compute_circuit_id, CircuitRegistry, RegisteredCircuit};
+use zk_verifier::state::{PreparedVerificationKey, VerificationKey, PREPARED_VK_SEED};
+
+/// Transaction compute limit
+const MAX_COMPUTE_UNITS: u32 = 1_400_000;
+
+/// Anchor account owned by `owner`
+fn owned_account<T: AccountSerialize>(owner: Pubkey, account: &T, space: usize) -> Account {
+    let mut data = Vec::with_capacity(space);
+    account.try_serialize(&mut data).unwrap();
+    data.resize(space, 0);
+    Account {
+        lamports: Rent::default().minimum_balance(space),
+        data,
+        owner,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// Anchor account owned by the shielded pool
+fn program_account<T: AccountSerialize>(account: &T, space: usize) -> Account {
+    owned_account(shielded_pool::ID, account, space)
+}
+
+/// Native SOL pool with `total_shielded` lamports
+fn pool_state(bump: u8, total_shielded: u64) -> PoolState {
+    PoolState {
+        version: POOL_STATE_VERSION,
+        authority: Pubkey::new_unique(),
+        per_authority: Pubkey::new_unique(),
+        pending_authority: Pubkey::default(),
+        pending_authority_slot: 0,
+        pending_per_authority: Pubkey::default(),
+        pending_per_authority_slot: 0,
+        authority_delay_slots: 0,
+        pending_authority_delay_slots: 0,
+        pending_authority_delay_slot: 0,
+        guardians: [Pubkey::default(); MAX_GUARDIANS],
+        guardian_count: 0,
+        commitment_root: [0u8; 32],
+        commitment_root_slot: 0,
+        historical_roots: [[0u8; 32]; HISTORICAL_ROOTS_SIZE],
+        historical_roots_slots: [0u64; HISTORICAL_ROOTS_SIZE],
+        roots_index: 0,
+        total_shielded,
+        token_mint: spl_token::native_mint::ID,
+        token_vault: Pubkey::default(),
+        legacy_vk_hash: [0u8; 32],
+        native_sol: true,
+        token_decimals: 9,
+        min_deposit: MIN_DEPOSIT_LAMPORTS,
+        max_deposit: u64::MAX,
+        denominations: [0u64; MAX_DENOMINATIONS],
+        denomination_count: 0,
+        denomination_deposits: [0u64; MAX_DENOMINATIONS],
+        circuit_vks: [CircuitVk::default(); MAX_POOL_CIRCUITS],
+        paused: false,
+        outflow_limit_bps: 0,
+        outflow_epoch_slots: 0,
+        outflow_epoch_start_slot: 0,
+        outflow_epoch_base: 0,
+        outflow_epoch_amount: 0,
+        emergency_mode: false,
+        emergency_root: [0u8; 32],
+        emergency_activated_slot: 0,
+        emergency_claims: 0,
+        total_deposits: 0,
+        total_withdrawals: 0,
+        total_nullifiers: 0,
+        last_nullifiers_root: [0u8; 32],
+        batch_sequence: 0,
+        pool_config_initialized: false,
+        bump,
+        _reserved: Vec::new(),
+    }
+}
+
+/// Accounts of a native SOL pool: pool and SOL vault (funded with
+/// `vault_lamports` above its reserve). No SPL vault or mint: native SOL
+/// instructions run without them.
+struct TestPool {
+    pool: Pubkey,
+    pool_authority: Pubkey,
+    sol_vault: Pubkey,
+}
+
+impl TestPool {
+    fn add(
+        program_test: &mut ProgramTest,
+        state: impl FnOnce(u8) -> PoolState,
+        vault_lamports: u64,
+    ) -> Self {
+        let (pool, bump) = Pubkey::find_program_address(
+            &[b"pool", spl_token::native_mint::ID.as_ref()],
+            &shielded_pool::ID,
+        );
+        let (pool_authority, _) =
+            Pubkey::find_program_address(&[b"authority", pool.as_ref()], &shielded_pool::ID);
+        let (sol_vault, _) =
+            Pubkey::find_program_address(&[SOL_VAULT_SEED, pool.as_ref()], &shielded_pool::ID);
+
+        program_test.add_account(
+            pool,
+            program_account(&state(bump), 8 + PoolState::INIT_SPACE),
+        );
+        program_test.add_account(
+            sol_vault,
+            Account {
+                lamports: Rent::default().minimum_balance(0) + vault_lamports,
+                data: Vec::new(),
+                owner: system_program::ID,
+                executable: false,
+                rent_epoch: 0,
+            },
+        );
+
+        TestPool {
+            pool,
+            pool_authority,
+            sol_vault,
+        }
+    }
+}
+
+/// Simulate `ix` under the full compute limit, returning the units consumed
+async fn simulate(
+    banks_client: &mut BanksClient,
+    payer: &Keypair,
+    blockhash: Hash,
+    ix: Instruction,
+) -> u64 {
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            ComputeBudgetInstruction::set_compute_unit_limit(MAX_COMPUTE_UNITS),
+            ix,
+        ],
+        Some(&payer.pubkey()),
+        &[payer],
+        blockhash,
+    );
+    let simulation = banks_client.simulate_transaction(tx).await.unwrap();
+    assert!(
+        matches!(simulation.result, Some(Ok(()))),
+        "{:?}",
+        simulation.result
+    );
+    simulation.simulation_details.unwrap().units_consumed
+}
+
+/// Whether `ix` fails under the full compute limit
+async fn simulate_fails(
+    banks_client: &mut BanksClient,
+    payer: &Keypair,
+    blockhash: Hash,
+    ix: Instruction,
+) -> bool {
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            ComputeBudgetInstruction::set_compute_unit_limit(MAX_COMPUTE_UNITS),
+            ix,
+        ],
+        Some(&payer.pubkey()),
+        &[payer],
+        blockhash,
+    );
+    let simulation = banks_client.simulate_transaction(tx).await.unwrap();
+    matches!(simulation.result, Some(Err(_)))
+}
+
+/// Settled batch 0 of `pool` with `nullifier_count` unrecorded nullifiers
+fn add_settled_batch(
+    program_test: &mut ProgramTest,
+    pool: &Pubkey,
+    nullifiers_root: [u8; 32],
+    nullifier_count: u32,
+) -> Pubkey {
+    let (settled_batch, bump) = find_settled_batch_pda(pool, 0, &shielded_pool::ID);
+    let batch = SettledBatch {
+        pool: *pool,
+        sequence: 0,
+        nullifiers_root,
+        nullifier_count,
+        depth: nullifier_tree_depth(nullifier_count).unwrap(),
+        recorded_count: 0,
+        settled_slot: 0,
+        bump,
+    };
+    program_test.add_account(
+        settled_batch,
+        program_account(&batch, 8 + SettledBatch::INIT_SPACE),
+    );
+    settled_batch
+}
+
+/// Advance to `slot` and return a fresh blockhash
+async fn warp(context: &mut ProgramTestContext, slot: u64) -> Hash {
+    context.warp_to_slot(slot).unwrap();
+    context.get_new_latest_blockhash().await.unwrap()
+}
+
+/// Merkle siblings and path indices for a leaf at `depth`, and the root they open to
+fn merkle_path(leaf: &[u8; 32], depth: usize) -> (Vec<[u8; 32]>, Vec<u8>, [u8; 32]) {
+    let siblings: Vec<[u8; 32]> = (0..depth).map(|i| u64_to_field(i as u64 + 1)).collect();
+    let path_indices: Vec<u8> = (0..depth).map(|i| (i % 2) as u8).collect();
+
+    let mut root = *leaf;
+    for (sibling, is_right) in siblings.iter().zip(path_indices.iter()) {
+        root = if *is_right != 0 {
+            poseidon2::hash_pair(sibling, &root).unwrap()
+        } else {
+            poseidon2::hash_pair(&root, sibling).unwrap()
+        };
+    }
+    (siblings, path_indices, root)
+}
+
+/// Full-depth emergency claim of `amount`, its nullifier and the emergency
+/// root it opens
+fn emergency_claim_data(amount: u64) -> (EmergencyClaimData, [u8; 32], [u8; 32]) {
+    let mut claim = EmergencyClaimData {
+        owner: u64_to_field(12345),
+        amount,
+        vault_id: [0u8; 32],
+        blinding: u64_to_field(99999),
+        nullifier_secret: u64_to_field(42),
+        nonce: u64_to_field(7),
+        merkle_proof: Vec::new(),
+        path_indices: Vec::new(),
+    };
+    let commitment = claim.commitment().unwrap();
+    let nullifier = claim.nullifier(&commitment).unwrap();
+    let (merkle_proof, path_indices, emergency_root) = merkle_path(&commitment, TREE_DEPTH);
+    claim.merkle_proof = merkle_proof;
+    claim.path_indices = path_indices;
+    (claim, nullifier, emergency_root)
+}
+
+/// Commit and reveal instructions of `claim` to `recipient`
+fn emergency_claim_ixs(
+    test_pool: &TestPool,
+    payer: Pubkey,
+    claim: &EmergencyClaimData,
+    nullifier: [u8; 32],
+    recipient: Pubkey,
+    salt: [u8; 32],
+) -> (Instruction, Instruction) {
+    let claim_hash = claim.claim_hash(&recipient, &salt);
+    let (claim_commitment, _) = Pubkey::find_program_address(
+        &[
+            EMERGENCY_CLAIM_COMMITMENT_SEED,
+            test_pool.pool.as_ref(),
+            &claim_hash,
+        ],
+        &shielded_pool::ID,
+    );
+    let (nullifier_entry, _) = Pubkey::find_program_address(
+        &[b"nullifier", test_pool.pool.as_ref(), &nullifier],
+        &shielded_pool::ID,
+    );
+
+    let commit = Instruction {
+        program_id: shielded_pool::ID,
+        accounts: shielded_pool::accounts::CommitEmergencyClaim {
+            pool: test_pool.pool,
+            claim_commitment,
+            payer,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: shielded_pool::instruction::CommitEmergencyClaim { claim_hash }.data(),
+    };
+    let reveal = Instruction {
+        program_id: shielded_pool::ID,
+        accounts: shielded_pool::accounts::EmergencyClaim {
+            pool: test_pool.pool,
+            nullifier_entry,
+            claim_commitment,
+            pool_vault: None,
+            recipient_token_account: None,
+            sol_vault: Some(test_pool.sol_vault),
+            pool_authority: test_pool.pool_authority,
+            recipient,
+            payer,
+            token_mint: None,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: shielded_pool::instruction::EmergencyClaim {
+            nullifier,
+            claim: claim.clone(),
+            salt,
+        }
+        .data(),
+    };
+    (commit, reveal)
+}
+
+/// Send `ix` signed by the context payer, under the full compute limit
+async fn process(context: &mut ProgramTestContext, blockhash: Hash, ix: Instruction) {
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            ComputeBudgetInstruction::set_compute_unit_limit(MAX_COMPUTE_UNITS),
+            ix,
+        ],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+}
+
+/// Full-depth emergency claim: TREE_DEPTH + 2 Poseidon2 permutations
+/// (commitment, nullifier and one per level)
+#[tokio::test]
+async fn emergency_claim_fits_compute_budget() {
+    let amount = 1_000_000_000;
+    let (claim, nullifier, emergency_root) = emergency_claim_data(amount);
+
+    let mut program_test = ProgramTest::new("shielded_pool", shielded_pool::ID, None);
+    let test_pool = TestPool::add(
+        &mut program_test,
+        |bump| PoolState {
+            paused: true,
+            emergency_mode: true,
+            emergency_root,
+            ..pool_state(bump, amount)
+        },
+        amount,
+    );
+    let mut context = program_test.start_with_context().await;
+
+    let (commit, reveal) = emergency_claim_ixs(
+        &test_pool,
+        context.payer.pubkey(),
+        &claim,
+        nullifier,
+        Pubkey::new_unique(),
+        [9u8; 32],
+    );
+
+    // Commit once the timelock has passed, reveal after the reveal delay
+    let blockhash = warp(&mut context, EMERGENCY_CLAIM_DELAY_SLOTS).await;
+    process(&mut context, blockhash, commit).await;
+
+    let blockhash = warp(
+        &mut context,
+        EMERGENCY_CLAIM_DELAY_SLOTS + EMERGENCY_CLAIM_REVEAL_DELAY_SLOTS + 1,
+    )
+    .await;
+    let units = simulate(&mut context.banks_client, &context.payer, blockhash, reveal).await;
+    println!("emergency_claim at depth {TREE_DEPTH}: {units} CU");
+    assert!(units < MAX_COMPUTE_UNITS as u64);
+}
+
+/// An emergency claim's nullifier can't be cleaned up, so the balance can't
+/// be claimed a second time against the same frozen root
+#[tokio::test]
+async fn emergency_claim_cannot_repeat_after_cleanup_age() {
+    let amount = 1_000_000_000;
+    let (claim, nullifier, emergency_root) = emergency_claim_data(amount);
+    let admin = Keypair::new();
+
+    let mut program_test = ProgramTest::new("shielded_pool", shielded_pool::ID, None);
+    let test_pool = TestPool::add(
+        &mut program_test,
+        |bump| PoolState {
+            authority: admin.pubkey(),
+            paused: true,
+            emergency_mode: true,
+            emergency_root,
+            ..pool_state(bump, 2 * amount)
+        },
+        2 * amount,
+    );
+    // Rent recipient of cleanup_nullifier
+    program_test.add_account(
+        admin.pubkey(),
+        Account {
+            lamports: Rent::default().minimum_balance(0),
+            data: Vec::new(),
+            owner: system_program::ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+    let mut context = program_test.start_with_context().await;
+    let recipient = Pubkey::new_unique();
+
+    // First claim goes through
+    let (commit, reveal) = emergency_claim_ixs(
+        &test_pool,
+        context.payer.pubkey(),
+        &claim,
+        nullifier,
+        recipient,
+        [9u8; 32],
+    );
+    let blockhash = warp(&mut context, EMERGENCY_CLAIM_DELAY_SLOTS).await;
+    process(&mut context, blockhash, commit).await;
+    let claim_slot = EMERGENCY_CLAIM_DELAY_SLOTS + EMERGENCY_CLAIM_REVEAL_DELAY_SLOTS + 1;
+    let blockhash = warp(&mut context, claim_slot).await;
+    process(&mut context, blockhash, reveal).await;
+
+    // Past the cleanup age, the nullifier still can't be closed
+    let (nullifier_entry, _) = Pubkey::find_program_address(
+        &[b"nullifier", test_pool.pool.as_ref(), &nullifier],
+        &shielded_pool::ID,
+    );
+    let cleanup = Instruction {
+        program_id: shielded_pool::ID,
+        accounts: shielded_pool::accounts::CleanupNullifier {
+            pool: test_pool.pool,
+            nullifier_entry,
+            rent_recipient: admin.pubkey(),
+            pool_config: None,
+            payer: context.payer.pubkey(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: shielded_pool::instruction::CleanupNullifier {}.data(),
+    };
+    let cleanup_slot = claim_slot + MIN_NULLIFIER_AGE_FOR_CLEANUP + 1;
+    let blockhash = warp(&mut context, cleanup_slot).await;
+    assert!(
+        simulate_fails(
+            &mut context.banks_client,
+            &context.payer,
+            blockhash,
+            cleanup
+        )
+        .await
+    );
+
+    // A fresh commitment to the same opening can't be revealed again
+    let (commit, reveal) = emergency_claim_ixs(
+        &test_pool,
+        context.payer.pubkey(),
+        &claim,
+        nullifier,
+        recipient,
+        [10u8; 32],
+    );
+    process(&mut context, blockhash, commit).await;
+    let blockhash = warp(
+        &mut context,
+        cleanup_slot + EMERGENCY_CLAIM_REVEAL_DELAY_SLOTS + 1,
+    )
+    .await;
+    assert!(simulate_fails(&mut context.banks_client, &context.payer, blockhash, reveal).await);
+}
+
+fn fq_bytes(x: &Fq) -> [u8; 32] {
+    x.into_bigint().to_bytes_be().try_into().unwrap()
+}
+
+fn g1_bytes(p: &G1Affine) -> [u8; 64] {
+    [fq_bytes(&p.x), fq_bytes(&p.y)]
+        .concat()
+        .try_into()
+        .unwrap()
+}
+
+fn g2_bytes(p: &G2Affine) -> [u8; 128] {
+    [
+        fq_bytes(&p.x.c1),
+        fq_bytes(&p.x.c0),
+        fq_bytes(&p.y.c1),
+        fq_bytes(&p.y.c0),
+    ]
+    .concat()
+    .try_into()
+    .unwrap()
+}
+
+/// Proof the synthetic keys of `TestVk` accept for `public_inputs`
+///
+/// Every G2 point is the generator, so the pairing check reduces to
+/// A + α + vk_x + C = 0 in G1 (same construction as the zk-verifier tests).
+fn synthetic_proof(public_inputs: &[[u8; 32]]) -> Groth16Proof {
+    let g1 = G1Affine::generator();
+    let vk_x = public_inputs
+        .iter()
+        .enumerate()
+        .fold(Fr::from(2u64), |acc, (i, input)| {
+            acc + Fr::from(i as u64 + 3) * Fr::from_be_bytes_mod_order(input)
+        });
+    let a = Fr::from(7u64);
+    let c = -(a + Fr::from(11u64) + vk_x);
+    Groth16Proof {
+        a: g1_bytes(&(g1 * a).into_affine()),
+        b: g2_bytes(&G2Affine::generator()),
+        c: g1_bytes(&(g1 * c).into_affine()),
+    }
+}
+
+/// Synthetic verification key of one pool circuit, its prepared copy and the
+/// hash the pool accepts (IC[i] = (i + 2) · G1, α = 11 · G1)
+struct TestVk {
+    name: &'static str,
+    circuit_id: [u8; 32],
+    verification_key: Pubkey,
+    prepared_vk: Pubkey,
+    vk_hash: [u8; 32],
+}
+
+impl TestVk {
+    fn add(
+        program_test: &mut ProgramTest,
+        pool: Pubkey,
+        name: &'static str,
+        nr_public_inputs: usize,
+    ) -> Self {
+        let circuit_id = compute_circuit_id(name, "v1", None);
+        let (verification_key, vk_bump) = Pubkey::find_program_address(
+            &[b"vk", pool.as_ref(), &circuit_id, &1u32.to_le_bytes()],
+            &zk_verifier::ID,
+        );
+        let (prepared_vk, prepared_bump) = Pubkey::find_program_address(
+            &[PREPARED_VK_SEED, verification_key.as_ref()],
+            &zk_verifier::ID,
+        );
+
+        let g1 = G1Affine::generator();
+        let g2 = g2_bytes(&G2Affine::generator());
+        let ic: Vec<[u8; 64]> = (0..=nr_public_inputs)
+            .map(|i| g1_bytes(&(g1 * Fr::from(i as u64 + 2)).into_affine()))
+            .collect();
+        let vk = VerificationKey {
+            pool,
+            circuit_id,
+            version: 1,
+            alpha_g1: g1_bytes(&(g1 * Fr::from(11u64)).into_affine()),
+            beta_g2: g2,
+            gamma_g2: g2,
+            delta_g2: g2,
+            ic_length: ic.len() as u8,
+            ic,
+            revoked_slot: 0,
+            bump: vk_bump,
+        };
+        let vk_hash = keccak::hash(&vk.try_to_vec().unwrap()).to_bytes();
+
+        let mut prepared: Box<PreparedVerificationKey> = Box::new(bytemuck::Zeroable::zeroed());
+        prepared.prepare(verification_key, &vk);
+        prepared.bump = prepared_bump;
+        let mut prepared_data = PreparedVerificationKey::DISCRIMINATOR.to_vec();
+        prepared_data.extend_from_slice(bytemuck::bytes_of(&*prepared));
+
+        program_test.add_account(
+            verification_key,
+            owned_account(
+                zk_verifier::ID,
+                &vk,
+                VerificationKey::size(nr_public_inputs + 1),
+            ),
+        );
+        program_test.add_account(
+            prepared_vk,
+            Account {
+                lamports: Rent::default().minimum_balance(prepared_data.len()),
+                data: prepared_data,
+                owner: zk_verifier::ID,
+                executable: false,
+                rent_epoch: 0,
+            },
+        );
+
+        TestVk {
+            name,
+            circuit_id,
+            verification_key,
+            prepared_vk,
+            vk_hash,
+        }
+    }
+}
+
+/// The pool's circuit registry with `vks` registered under their names
+fn add_circuit_registry(program_test: &mut ProgramTest, pool: Pubkey, vks: &[&TestVk]) -> Pubkey {
+    let (circuit_registry, bump) =
+        Pubkey::find_program_address(&[CIRCUIT_REGISTRY_SEED, pool.as_ref()], &zk_verifier::ID);
+    let registry = CircuitRegistry {
+        pool,
+        circuits: vks
+            .iter()
+            .map(|vk| RegisteredCircuit {
+                name: vk.name.to_string(),
+                version: "v1".to_string(),
+                vk_commitment: None,
+                circuit_id: vk.circuit_id,
+            })
+            .collect(),
+        bump,
+    };
+    program_test.add_account(
+        circuit_registry,
+        owned_account(zk_verifier::ID, &registry, 8 + CircuitRegistry::INIT_SPACE),
+    );
+    circuit_registry
+}
+
+/// A native SOL pool takes a deposit and pays it back out through withdraw,
+/// without the SPL vault or mint accounts
+#[tokio::test]
+async fn native_sol_deposit_then_withdraw() {
+    let amount = 1_000_000_000;
+    // Leading zero byte keeps the key below the BN254 modulus (it is a public input)
+    let mut recipient_bytes = Pubkey::new_unique().to_bytes();
+    recipient_bytes[0] = 0;
+    let recipient = Pubkey::new_from_array(recipient_bytes);
+
+    let mut program_test = ProgramTest::new("shielded_pool", shielded_pool::ID, None);
+    program_test.add_program("zk_verifier", zk_verifier::ID, None);
+    let (pool, _) = Pubkey::find_program_address(
+        &[b"pool", spl_token::native_mint::ID.as_ref()],
+        &shielded_pool::ID,
+    );
+    let deposit_vk = TestVk::add(&mut program_test, pool, names::DEPOSIT, 5);
+    let withdraw_vk = TestVk::add(&mut program_test, pool, names::WITHDRAW, 7);
+    let circuit_registry =
+        add_circuit_registry(&mut program_test, pool, &[&deposit_vk, &withdraw_vk]);
+    let test_pool = TestPool::add(
+        &mut program_test,
+        |bump| {
+            let mut state = pool_state(bump, 0);
+            for vk in [&deposit_vk, &withdraw_vk] {
+                state.stage_vk(vk.circuit_id, vk.vk_hash, 1, 0).unwrap();
+            }
+            state
+        },
+        0,
+    );
+    let mut context = program_test.start_with_context().await;
+    let payer = context.payer.pubkey();
+    let vault_reserve = Rent::default().minimum_balance(0);
+    let blockhash = context.last_blockhash;
+
+    // Deposit: lamports move from the depositor to the SOL vault
+    let mut deposit_proof = DepositProofData {
+        proof: synthetic_proof(&[]),
+        deposit_amount: u64_to_field(amount),
+        new_commitment: u64_to_field(1111),
+        leaf_index: u64_to_field(0),
+        old_root: [0u8; 32],
+        new_root: u64_to_field(2222),
+    };
+    deposit_proof.proof = synthetic_proof(&deposit_proof.public_inputs());
+    let deposit = Instruction {
+        program_id: shielded_pool::ID,
+        accounts: shielded_pool::accounts::Deposit {
+            pool,
+            user_token_account: None,
+            pool_vault: None,
+            sol_vault: Some(test_pool.sol_vault),
+            verification_key: deposit_vk.verification_key,
+            prepared_vk: deposit_vk.prepared_vk,
+            circuit_registry,
+            verifier_program: zk_verifier::ID,
+            depositor: payer,
+            historical_roots: None,
+            token_mint: None,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: shielded_pool::instruction::Deposit {
+            amount,
+            proof_data: deposit_proof.clone(),
+        }
+        .data(),
+    };
+    process(&mut context, blockhash, deposit).await;
+    assert_eq!(
+        context
+            .banks_client
+            .get_balance(test_pool.sol_vault)
+            .await
+            .unwrap(),
+        vault_reserve + amount
+    );
+
+    // Withdraw: the full balance goes to the proof-bound recipient wallet
+    let nullifier = u64_to_field(3333);
+    let mut withdraw_proof = WithdrawProofData {
+        proof: synthetic_proof(&[]),
+        amount: u64_to_field(amount),
+        recipient: recipient.to_bytes(),
+        nullifier,
+        old_root: deposit_proof.new_root,
+        new_root: u64_to_field(4444),
+        relayer: [0u8; 32],
+        fee: u64_to_field(0),
+    };
+    withdraw_proof.proof = synthetic_proof(&withdraw_proof.public_inputs());
+    let (nullifier_entry, _) = Pubkey::find_program_address(
+        &[b"nullifier", pool.as_ref(), &nullifier],
+        &shielded_pool::ID,
+    );
+    let withdraw = Instruction {
+        program_id: shielded_pool::ID,
+        accounts: shielded_pool::accounts::Withdraw {
+            pool,
+            pool_vault: None,
+            recipient_token_account: None,
+            relayer_token_account: None,
+            sol_vault: Some(test_pool.sol_vault),
+            recipient_wallet: Some(recipient),
+            relayer_wallet: None,
+            nullifier_entry,
+            verification_key: withdraw_vk.verification_key,
+            prepared_vk: withdraw_vk.prepared_vk,
+            circuit_registry,
+            verifier_program: zk_verifier::ID,
+            payer,
+            pool_authority: test_pool.pool_authority,
+            historical_roots: None,
+            pool_config: None,
+            token_mint: None,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: shielded_pool::instruction::Withdraw {
+            proof_data: withdraw_proof.clone(),
+            recipient,
+        }
+        .data(),
+    };
+    process(&mut context, blockhash, withdraw).await;
+
+    assert_eq!(
+        context.banks_client.get_balance(recipient).await.unwrap(),
+        amount
+    );
+    assert_eq!(
+        context
+            .banks_client
+            .get_balance(test_pool.sol_vault)
+            .await
+            .unwrap(),
+        vault_reserve
+    );
+    let pool_account = context
+        .banks_client
+        .get_account(pool)
+        .await
+        .unwrap()
+        .unwrap();
+    let state = PoolState::try_deserialize(&mut &pool_account.data[..]).unwrap();
+    assert_eq!(state.total_shielded, 0);
+    assert_eq!(state.commitment_root, withdraw_proof.new_root);
+    assert_eq!((state.total_deposits, state.total_withdrawals), (1, 1));
+}
+
+/// set_emergency_mode instruction signed by `admin`
+fn set_emergency_mode_ix(pool: Pubkey, admin: &Keypair, emergency_mode: bool) -> Instruction {
+    Instruction {
+        program_id: shielded_pool::ID,
+        accounts: shielded_pool::accounts::SetEmergencyMode {
+            pool,
+            authority: admin.pubkey(),
+        }
+        .to_account_metas(None)
+        .into_iter()
+        .map(|meta| AccountMeta {
+            is_signer: meta.is_signer || meta.pubkey == admin.pubkey(),
+            ..meta
+        })
+        .collect(),
+        data: shielded_pool::instruction::SetEmergencyMode { emergency_mode }.data(),
+    }
+}
+
+/// Enabling emergency mode again must not restart the claim timelock
+#[tokio::test]
+async fn set_emergency_mode_rejects_reenable() {
+    let admin = Keypair::new();
+    let mut program_test = ProgramTest::new("shielded_pool", shielded_pool::ID, None);
+    let test_pool = TestPool::add(
+        &mut program_test,
+        |bump| PoolState {
+            authority: admin.pubkey(),
+            paused: true,
+            emergency_mode: true,
+            emergency_activated_slot: 1,
+            ..pool_state(bump, 0)
+        },
+        0,
+    );
+    let mut context = program_test.start_with_context().await;
+    let blockhash = warp(&mut context, 100).await;
+
+    let signed = |context: &ProgramTestContext, ix: Instruction, blockhash: Hash| {
+        Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, &admin],
+            blockhash,
+        )
+    };
+
+    let reenable = signed(
+        &context,
+        set_emergency_mode_ix(test_pool.pool, &admin, true),
+        blockhash,
+    );
+    let simulation = context
+        .banks_client
+        .simulate_transaction(reenable)
+        .await
+        .unwrap();
+    assert!(matches!(simulation.result, Some(Err(_))));
+
+    // Disabling first (no claims yet) is the only way to restart it
+    for emergency_mode in [false, true] {
+        let tx = signed(
+            &context,
+            set_emergency_mode_ix(test_pool.pool, &admin, emergency_mode),
+            blockhash,
+        );
+        context.banks_client.process_transaction(tx).await.unwrap();
+    }
+}
+
+fn record_nullifier_ix(
+    pool: &Pubkey,
+    settled_batch: Pubkey,
+    payer: &Pubkey,
+    nullifier: [u8; 32],
+    nullifiers_root: [u8; 32],
+    merkle_proof: Vec<[u8; 32]>,
+    path_indices: Vec<u8>,
+) -> Instruction {
+    let (nullifier_entry, _) = Pubkey::find_program_address(
+        &[b"nullifier", pool.as_ref(), &nullifier],
+        &shielded_pool::ID,
+    );
+    Instruction {
+        program_id: shielded_pool::ID,
+        accounts: shielded_pool::accounts::RecordNullifier {
+            pool: *pool,
+            settled_batch,
+            nullifier_entry,
+            payer: *payer,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: shielded_pool::instruction::RecordNullifier {
+            nullifier,
+            nullifiers_root,
+            merkle_proof,
+            path_indices,
+        }
+        .data(),
+    }
+}
+
+/// record_nullifier with the longest accepted proof (one Poseidon2 hash per
+/// level) in a full batch; a proof one level deeper than the batch tree is
+/// rejected even against its own root
+#[tokio::test]
+async fn record_nullifier_fits_compute_budget() {
+    let nullifier = u64_to_field(0xdead);
+    let batch_depth = MAX_NULLIFIER_TREE_DEPTH as usize;
+
+    for (depth, accepted) in [(batch_depth, true), (batch_depth + 1, false)] {
+        let (merkle_proof, path_indices, nullifiers_root) = merkle_path(&nullifier, depth);
+        let mut program_test = ProgramTest::new("shielded_pool", shielded_pool::ID, None);
+        let test_pool = TestPool::add(&mut program_test, |bump| pool_state(bump, 0), 0);
+        let settled_batch = add_settled_batch(
+            &mut program_test,
+            &test_pool.pool,
+            nullifiers_root,
+            1 << batch_depth,
+        );
+        let (mut banks_client, payer, blockhash) = program_test.start().await;
+
+        let ix = record_nullifier_ix(
+            &test_pool.pool,
+            settled_batch,
+            &payer.pubkey(),
+            nullifier,
+            nullifiers_root,
+            merkle_proof,
+            path_indices,
+        );
+        if accepted {
+            let units = simulate(&mut banks_client, &payer, blockhash, ix).await;
+            println!("record_nullifier at depth {depth}: {units} CU");
+            assert!(units < MAX_COMPUTE_UNITS as u64);
+        } else {
+            assert!(simulate_fails(&mut banks_client, &payer, blockhash, ix).await);
+        }
+    }
+}
+
+/// Every level of a complete tree over `leaves`, leaves first and root last
+fn tree_levels(leaves: Vec<[u8; 32]>) -> Vec<Vec<[u8; 32]>> {
+    let mut levels = vec![leaves];
+    while levels.last().unwrap().len() > 1 {
+        let parents = levels
+            .last()
+            .unwrap()
+            .chunks(2)
+            .map(|pair| poseidon2::hash_pair(&pair[0], &pair[1]).unwrap())
+            .collect();
+        levels.push(parents);
+    }
+    levels
+}
+
+/// Multi-proof for `leaf_indices` in the order `compute_multiproof_root`
+/// consumes it: bottom-up, left-to-right, skipping known siblings
+fn multiproof(levels: &[Vec<[u8; 32]>], leaf_indices: &[u32]) -> Vec<[u8; 32]> {
+    let mut proof = Vec::new();
+    let mut known = leaf_indices.to_vec();
+    for level in &levels[..levels.len() - 1] {
+        let mut parents = Vec::new();
+        let mut i = 0;
+        while i < known.len() {
+            let index = known[i];
+            if index.is_multiple_of(2) && known.get(i + 1) == Some(&(index + 1)) {
+                i += 1;
+            } else {
+                proof.push(level[(index ^ 1) as usize]);
+            }
+            parents.push(index / 2);
+            i += 1;
+        }
+        known = parents;
+    }
+    proof
+}
+
+/// Merkle siblings and path indices of one leaf in `levels`
+fn single_proof(levels: &[Vec<[u8; 32]>], leaf_index: u32) -> (Vec<[u8; 32]>, Vec<u8>) {
+    levels[..levels.len() - 1]
+        .iter()
+        .enumerate()
+        .map(|(level, nodes)| {
+            let index = leaf_index >> level;
+            (nodes[(index ^ 1) as usize], (index & 1) as u8)
+        })
+        .unzip()
+}
+
+#[allow(clippy::too_many_arguments)]
+fn record_nullifiers_batch_ix(
+    pool: &Pubkey,
+    settled_batch: Pubkey,
+    payer: &Pubkey,
+    nullifiers_root: [u8; 32],
+    nullifiers: Vec<[u8; 32]>,
+    leaf_indices: Vec<u32>,
+    proof: Vec<[u8; 32]>,
+    depth: u8,
+) -> Instruction {
+    let mut accounts = shielded_pool::accounts::RecordNullifiersBatch {
+        pool: *pool,
+        settled_batch,
+        payer: *payer,
+        system_program: system_program::ID,
+    }
+    .to_account_metas(None);
+    accounts.extend(nullifiers.iter().map(|nullifier| {
+        let (nullifier_entry, _) = Pubkey::find_program_address(
+            &[b"nullifier", pool.as_ref(), nullifier],
+            &shielded_pool::ID,
+        );
+        AccountMeta::new(nullifier_entry, false)
+    }));
+    Instruction {
+        program_id: shielded_pool::ID,
+        accounts,
+        data: shielded_pool::instruction::RecordNullifiersBatch {
+            nullifiers_root,
+            nullifiers,
+            leaf_indices,
+            proof,
+            depth,
+        }
+        .data(),
+    }
+}
+
+/// A full record_nullifiers_batch (MAX_NULLIFIERS_PER_RECORD_BATCH leaves) at
+/// MAX_NULLIFIER_TREE_DEPTH, for the adjacent leaf layout and for leaves
+/// spread across the tree, which maximizes the number of Poseidon2 hashes
+#[tokio::test]
+async fn record_nullifiers_batch_fits_compute_budget() {
+    let depth = MAX_NULLIFIER_TREE_DEPTH;
+    let leaves: Vec<[u8; 32]> = (0..1u64 << depth).map(|i| u64_to_field(i + 1)).collect();
+    let levels = tree_levels(leaves);
+    let nullifiers_root = levels.last().unwrap()[0];
+
+    let batch = MAX_NULLIFIERS_PER_RECORD_BATCH as u32;
+    let stride = (1u32 << depth) / batch;
+    for (layout, leaf_indices) in [
+        ("adjacent", (0..batch).collect::<Vec<u32>>()),
+        ("spread", (0..batch).map(|i| i * stride).collect()),
+    ] {
+        let nullifiers: Vec<[u8; 32]> = leaf_indices
+            .iter()
+            .map(|index| levels[0][*index as usize])
+            .collect();
+        let proof = multiproof(&levels, &leaf_indices);
+
+        let mut program_test = ProgramTest::new("shielded_pool", shielded_pool::ID, None);
+        let test_pool = TestPool::add(&mut program_test, |bump| pool_state(bump, 0), 0);
+        let settled_batch = add_settled_batch(
+            &mut program_test,
+            &test_pool.pool,
+            nullifiers_root,
+            1 << depth,
+        );
+        let (mut banks_client, payer, blockhash) = program_test.start().await;
+
+        let proof_len = proof.len();
+        let ix = record_nullifiers_batch_ix(
+            &test_pool.pool,
+            settled_batch,
+            &payer.pubkey(),
+            nullifiers_root,
+            nullifiers,
+            leaf_indices,
+            proof,
+            depth,
+        );
+
+        let units = simulate(&mut banks_client, &payer, blockhash, ix).await;
+        println!(
+            "record_nullifiers_batch, {batch} {layout} leaves at depth {depth} \
+             ({proof_len} proof nodes): {units} CU"
+        );
+        assert!(units < MAX_COMPUTE_UNITS as u64);
+    }
+}
+
+/// Internal nodes, the root and the zero padding all open to nullifiers_root
+/// with a shorter (or empty) multi-proof; none of them may be recorded
+#[tokio::test]
+async fn record_nullifiers_batch_rejects_non_leaves() {
+    // 5 nullifiers, zero-padded to a depth-3 tree of 8 leaves
+    let nullifier_count = 5u32;
+    let mut leaves: Vec<[u8; 32]> = (0..nullifier_count as u64)
+        .map(|i| u64_to_field(i + 1))
+        .collect();
+    leaves.resize(8, [0u8; 32]);
+    let levels = tree_levels(leaves);
+    let nullifiers_root = levels[3][0];
+
+    let mut program_test = ProgramTest::new("shielded_pool", shielded_pool::ID, None);
+    let test_pool = TestPool::add(&mut program_test, |bump| pool_state(bump, 0), 0);
+    let settled_batch = add_settled_batch(
+        &mut program_test,
+        &test_pool.pool,
+        nullifiers_root,
+        nullifier_count,
+    );
+    let (mut banks_client, payer, blockhash) = program_test.start().await;
+
+    for (nullifiers, leaf_indices, depth) in [
+        // Both children of the root, as a depth-1 tree
+        (levels[2].clone(), vec![0u32, 1], 1u8),
+        // The root itself, as a depth-0 tree
+        (levels[3].clone(), vec![0u32], 0u8),
+        // Two level-1 nodes, as leaves of a depth-2 tree
+        (levels[1][..2].to_vec(), vec![0u32, 1], 2u8),
+        // A zero-padding leaf beyond nullifier_count, at the right depth
+        (vec![levels[0][6]], vec![6u32], 3u8),
+    ] {
+        let proof = multiproof(&levels[(3 - depth) as usize..], &leaf_indices);
+        let ix = record_nullifiers_batch_ix(
+            &test_pool.pool,
+            settled_batch,
+            &payer.pubkey(),
+            nullifiers_root,
+            nullifiers,
+            leaf_indices,
+            proof,
+            depth,
+        );
+        assert!(simulate_fails(&mut banks_client, &payer, blockhash, ix).await);
+    }
+
+    // The real leaves still record
+    let leaf_indices: Vec<u32> = (0..nullifier_count).collect();
+    let ix = record_nullifiers_batch_ix(
+        &test_pool.pool,
+        settled_batch,
+        &payer.pubkey(),
+        nullifiers_root,
+        levels[0][..nullifier_count as usize].to_vec(),
+        leaf_indices.clone(),
+        multiproof(&levels, &leaf_indices),
+        3,
+    );
+    simulate(&mut banks_client, &payer, blockhash, ix).await;
+}
+
+/// record_nullifier counterpart of record_nullifiers_batch_rejects_non_leaves
+#[tokio::test]
+async fn record_nullifier_rejects_non_leaves() {
+    // 5 nullifiers, zero-padded to a depth-3 tree of 8 leaves
+    let nullifier_count = 5u32;
+    let mut leaves: Vec<[u8; 32]> = (0..nullifier_count as u64)
+        .map(|i| u64_to_field(i + 1))
+        .collect();
+    leaves.resize(8, [0u8; 32]);
+    let levels = tree_levels(leaves);
+    let nullifiers_root = levels[3][0];
+
+    let mut program_test = ProgramTest::new("shielded_pool", shielded_pool::ID, None);
+    let test_pool = TestPool::add(&mut program_test, |bump| pool_state(bump, 0), 0);
+    let settled_batch = add_settled_batch(
+        &mut program_test,
+        &test_pool.pool,
+        nullifiers_root,
+        nullifier_count,
+    );
+    let (mut banks_client, payer, blockhash) = program_test.start().await;
+
+    for (first_level, index) in [
+        // A child of the root, one sibling short of a leaf proof
+        (2usize, 0u32),
+        // A level-1 node
+        (1, 1),
+        // The root, with an empty proof
+        (3, 0),
+        // A zero-padding leaf beyond nullifier_count, with a full proof
+        (0, 6),
+    ] {
+        let (merkle_proof, path_indices) = single_proof(&levels[first_level..], index);
+        let ix = record_nullifier_ix(
+            &test_pool.pool,
+            settled_batch,
+            &payer.pubkey(),
+            levels[first_level][index as usize],
+            nullifiers_root,
+            merkle_proof,
+            path_indices,
+        );
+        assert!(simulate_fails(&mut banks_client, &payer, blockhash, ix).await);
+    }
+
+    // A real leaf still records
+    let (merkle_proof, path_indices) = single_proof(&levels, 4);
+    let ix = record_nullifier_ix(
+        &test_pool.pool,
+        settled_batch,
+        &payer.pubkey(),
+        levels[0][4],
+        nullifiers_root,
+        merkle_proof,
+        path_indices,
+    );
+    simulate(&mut banks_client, &payer, blockhash, ix).await;
+}
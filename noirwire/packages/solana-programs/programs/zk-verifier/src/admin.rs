@@ -0,0 +1,201 @@
+use crate::errors::VerifierError;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash;
+
+/// Shielded pool program (owner of PoolState and AdminMultisig accounts)
+pub const SHIELDED_POOL_PROGRAM_ID: Pubkey = pubkey!("NWRZDZJMfUAd3iVvdMhpsKht5bgHZGPzynHhQ2JssQ2");
+
+/// Maximum number of admin signers in an AdminMultisig
+pub const MAX_ADMIN_SIGNERS: usize = 10;
+
+/// AdminMultisig layout (see shielded-pool `state::admin_multisig`):
+/// - 8 bytes: discriminator
+/// - 1 byte: version
+/// - 32 bytes: pool
+/// - 1 byte: threshold (offset 41)
+/// - 1 byte: signer_count (offset 42)
+/// - 10 × 32 bytes: signers (offset 43)
+/// - 1 byte: bump
+const THRESHOLD_OFFSET: usize = 41;
+const SIGNER_COUNT_OFFSET: usize = 42;
+const SIGNERS_OFFSET: usize = 43;
+const ADMIN_MULTISIG_LEN: usize = SIGNERS_OFFSET + MAX_ADMIN_SIGNERS * 32 + 1;
+
+/// M-of-N admin parsed from a shielded-pool AdminMultisig account
+#[derive(Debug, PartialEq, Eq)]
+pub struct AdminMultisigView {
+    pub threshold: u8,
+    pub signers: Vec<Pubkey>,
+}
+
+impl AdminMultisigView {
+    /// Parse raw account data, checking the Anchor discriminator
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        require!(
+            data.len() >= ADMIN_MULTISIG_LEN,
+            VerifierError::InvalidAdminMultisig
+        );
+        let discriminator = hash::hash(b"account:AdminMultisig").to_bytes();
+        require!(
+            data[..8] == discriminator[..8],
+            VerifierError::InvalidAdminMultisig
+        );
+
+        let threshold = data[THRESHOLD_OFFSET];
+        let signer_count = data[SIGNER_COUNT_OFFSET] as usize;
+        require!(
+            signer_count <= MAX_ADMIN_SIGNERS,
+            VerifierError::InvalidAdminMultisig
+        );
+
+        let signers = data[SIGNERS_OFFSET..SIGNERS_OFFSET + signer_count * 32]
+            .chunks_exact(32)
+            .map(|key| Pubkey::new_from_array(key.try_into().unwrap()))
+            .collect();
+
+        Ok(Self { threshold, signers })
+    }
+
+    /// Number of distinct signers that signed among `accounts`
+    pub fn approvals(&self, accounts: &[AccountInfo]) -> usize {
+        self.signers
+            .iter()
+            .filter(|signer| {
+                accounts
+                    .iter()
+                    .any(|account| account.is_signer && account.key == *signer)
+            })
+            .count()
+    }
+}
+
+/// PoolState layout prefix (see shielded-pool `state::pool_state`):
+/// - 8 bytes: discriminator
+/// - 1 byte: version
+/// - 32 bytes: authority (offset 9)
+const POOL_AUTHORITY_OFFSET: usize = 9;
+const POOL_STATE_MIN_LEN: usize = POOL_AUTHORITY_OFFSET + 32;
+
+/// Read the authority pubkey from a shielded-pool PoolState account
+///
+/// SECURITY: The account must be owned by the shielded pool program and carry
+/// the PoolState discriminator; otherwise anyone could pass an account of
+/// their own with their key at the authority offset
+pub fn pool_authority(pool: &AccountInfo) -> Result<Pubkey> {
+    require!(
+        pool.owner == &SHIELDED_POOL_PROGRAM_ID,
+        VerifierError::InvalidPoolAccount
+    );
+
+    let pool_data = pool.try_borrow_data()?;
+
+    // Verify account has enough data
+    require!(
+        pool_data.len() >= POOL_STATE_MIN_LEN,
+        VerifierError::InvalidPoolAccount
+    );
+    let discriminator = hash::hash(b"account:PoolState").to_bytes();
+    require!(
+        pool_data[..8] == discriminator[..8],
+        VerifierError::InvalidPoolAccount
+    );
+
+    let mut authority_bytes = [0u8; 32];
+    authority_bytes.copy_from_slice(&pool_data[POOL_AUTHORITY_OFFSET..POOL_STATE_MIN_LEN]);
+    Ok(Pubkey::new_from_array(authority_bytes))
+}
+
+/// Authorize a VK change for the pool's admin
+///
+/// SECURITY (CRITICAL-08): Only pool authority can store/update VKs
+/// - A single-key admin must sign as `authority`
+/// - An AdminMultisig admin is passed as `authority` (not a signer), with
+///   `threshold` of its signers as signing `remaining_accounts`
+pub fn require_pool_admin(
+    pool: &AccountInfo,
+    authority: &AccountInfo,
+    remaining_accounts: &[AccountInfo],
+) -> Result<()> {
+    require!(
+        pool_authority(pool)? == authority.key(),
+        VerifierError::Unauthorized
+    );
+
+    if authority.is_signer {
+        return Ok(());
+    }
+
+    // SECURITY: Only a shielded-pool AdminMultisig can stand in for a signature
+    require!(
+        authority.owner == &SHIELDED_POOL_PROGRAM_ID,
+        VerifierError::Unauthorized
+    );
+    let multisig = AdminMultisigView::parse(&authority.try_borrow_data()?)?;
+    require!(
+        multisig.threshold > 0
+            && multisig.approvals(remaining_accounts) >= multisig.threshold as usize,
+        VerifierError::InsufficientApprovals
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool_data(authority: &Pubkey) -> Vec<u8> {
+        let mut data = vec![0u8; POOL_STATE_MIN_LEN];
+        data[..8].copy_from_slice(&hash::hash(b"account:PoolState").to_bytes()[..8]);
+        data[POOL_AUTHORITY_OFFSET..].copy_from_slice(authority.as_ref());
+        data
+    }
+
+    #[test]
+    fn test_pool_authority_checks_owner_and_discriminator() {
+        let key = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        let other_program = Pubkey::new_unique();
+        let mut lamports = [0u64; 3];
+        let mut valid = pool_data(&authority);
+        let mut foreign = pool_data(&authority);
+        let mut wrong_type = pool_data(&authority);
+        wrong_type[0] ^= 1;
+        let [l0, l1, l2] = &mut lamports;
+
+        let pool = AccountInfo::new(
+            &key,
+            false,
+            false,
+            l0,
+            &mut valid,
+            &SHIELDED_POOL_PROGRAM_ID,
+            false,
+            0,
+        );
+        assert_eq!(pool_authority(&pool).unwrap(), authority);
+
+        let pool = AccountInfo::new(
+            &key,
+            false,
+            false,
+            l1,
+            &mut foreign,
+            &other_program,
+            false,
+            0,
+        );
+        assert!(pool_authority(&pool).is_err());
+
+        let pool = AccountInfo::new(
+            &key,
+            false,
+            false,
+            l2,
+            &mut wrong_type,
+            &SHIELDED_POOL_PROGRAM_ID,
+            false,
+            0,
+        );
+        assert!(pool_authority(&pool).is_err());
+    }
+}
@@ -0,0 +1,230 @@
+//! # Batched Groth16 Verification
+//!
+//! Checks N proofs for the same verification key with a single multi-pairing.
+//! Each proof's equation e(-Aᵢ, Bᵢ) · e(α, β) · e(vk_xᵢ, γ) · e(Cᵢ, δ) = 1 is
+//! raised to a random 128-bit coefficient rᵢ and the results multiplied:
+//! ```text
+//! Π e(rᵢ·(-Aᵢ), Bᵢ) · e((Σrᵢ)·α, β) · e(Σ rᵢ·vk_xᵢ, γ) · e(Σ rᵢ·Cᵢ, δ) = 1
+//! ```
+//! That is N + 3 pairings instead of 4N. If any single proof is invalid the
+//! combined product is one with probability at most 2⁻¹²⁸.
+//!
+//! ## Coefficients
+//!
+//! r₀ = 1 and the other rᵢ are derived Fiat-Shamir style from a keccak
+//! transcript of the key, every proof and every public input, so they are fixed
+//! only after the prover has committed to the whole batch.
+//!
+//! ## Compute Budget
+//!
+//! Each extra proof costs one pairing (instead of four) plus three G1 scalar
+//! multiplications for the coefficients, on top of the per-input
+//! multiplications that single verification also pays.
+
+use crate::errors::VerifierError;
+use crate::groth16::{Groth16Proof, Groth16VerifyingKey};
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak;
+use groth16_solana::groth16::is_less_than_bn254_field_size_be;
+use solana_bn254::prelude::{alt_bn128_addition, alt_bn128_multiplication, alt_bn128_pairing};
+
+/// Maximum number of proofs in one `verify_batch` call
+pub const MAX_BATCH_PROOFS: usize = 8;
+
+/// Domain separator for the coefficient transcript
+const BATCH_TRANSCRIPT_DOMAIN: &[u8] = b"noirwire:groth16-batch:v1";
+
+/// Bytes of randomness per coefficient (128-bit soundness)
+const COEFFICIENT_BYTES: usize = 16;
+
+/// Verify `proofs[i]` against `public_inputs[i]` for every i
+///
+/// Returns Ok(false) if the batch does not verify (at least one proof is
+/// invalid) and an error for malformed input, mirroring `verify_proof`.
+pub fn verify_batch(
+    vk: &Groth16VerifyingKey,
+    proofs: &[Groth16Proof],
+    public_inputs: &[Vec<[u8; 32]>],
+) -> Result<bool> {
+    // === VALIDATION PHASE ===
+
+    // 1. Batch size and one input vector per proof
+    require!(
+        !proofs.is_empty() && proofs.len() <= MAX_BATCH_PROOFS,
+        VerifierError::InvalidBatchSize
+    );
+    require!(
+        public_inputs.len() == proofs.len(),
+        VerifierError::InputCountMismatch
+    );
+
+    // 2. IC length matches the key's input count
+    let nr_public_inputs = vk.nr_public_inputs as usize;
+    require!(
+        vk.ic.len() == nr_public_inputs + 1,
+        VerifierError::InvalidVerificationKey
+    );
+
+    // 3. SECURITY: Inputs must be canonical field elements; the multiplication
+    // syscall would otherwise silently reduce them
+    for inputs in public_inputs {
+        require!(
+            inputs.len() == nr_public_inputs,
+            VerifierError::InputCountMismatch
+        );
+        require!(
+            inputs.iter().all(is_less_than_bn254_field_size_be),
+            VerifierError::PublicInputOutOfRange
+        );
+    }
+
+    // === VERIFICATION PHASE ===
+
+    let coefficients = batch_coefficients(vk, proofs, public_inputs);
+
+    let mut pairing_input = Vec::with_capacity((proofs.len() + 3) * 192);
+    let mut vk_x_sum = [0u8; 64];
+    let mut c_sum = [0u8; 64];
+    let mut coefficient_sum = 0u128;
+    let mut coefficient_carry = 0u128;
+
+    for ((proof, inputs), r) in proofs.iter().zip(public_inputs).zip(&coefficients) {
+        // vk_xᵢ = IC₀ + Σ(pubⱼ · ICⱼ₊₁)
+        let mut vk_x = vk.ic[0];
+        for (point, input) in vk.ic[1..].iter().zip(inputs) {
+            vk_x = g1_add(&vk_x, &g1_mul(point, input)?)?;
+        }
+
+        pairing_input.extend_from_slice(&scale(&proof.a, r)?);
+        pairing_input.extend_from_slice(&proof.b);
+        vk_x_sum = g1_add(&vk_x_sum, &scale(&vk_x, r)?)?;
+        c_sum = g1_add(&c_sum, &scale(&proof.c, r)?)?;
+
+        let (sum, overflow) = coefficient_sum.overflowing_add(*r);
+        coefficient_sum = sum;
+        coefficient_carry += overflow as u128;
+    }
+
+    let mut alpha_scalar = [0u8; 32];
+    alpha_scalar[..16].copy_from_slice(&coefficient_carry.to_be_bytes());
+    alpha_scalar[16..].copy_from_slice(&coefficient_sum.to_be_bytes());
+
+    pairing_input.extend_from_slice(&vk_x_sum);
+    pairing_input.extend_from_slice(&vk.gamma_g2);
+    pairing_input.extend_from_slice(&c_sum);
+    pairing_input.extend_from_slice(&vk.delta_g2);
+    pairing_input.extend_from_slice(&g1_mul(&vk.alpha_g1, &alpha_scalar)?);
+    pairing_input.extend_from_slice(&vk.beta_g2);
+
+    let result = alt_bn128_pairing(&pairing_input).map_err(|e| {
+        msg!("Batch pairing error: {:?}", e);
+        error!(VerifierError::PairingFailed)
+    })?;
+
+    Ok(result.last() == Some(&1))
+}
+
+/// r₀ = 1, rᵢ = first 128 bits of keccak(seed || i) where seed commits to the
+/// key, all proofs and all public inputs
+fn batch_coefficients(
+    vk: &Groth16VerifyingKey,
+    proofs: &[Groth16Proof],
+    public_inputs: &[Vec<[u8; 32]>],
+) -> Vec<u128> {
+    let mut transcript: Vec<&[u8]> = vec![
+        BATCH_TRANSCRIPT_DOMAIN,
+        &vk.alpha_g1,
+        &vk.beta_g2,
+        &vk.gamma_g2,
+        &vk.delta_g2,
+    ];
+    transcript.extend(vk.ic.iter().map(|point| point.as_slice()));
+    for (proof, inputs) in proofs.iter().zip(public_inputs) {
+        transcript.extend([proof.a.as_slice(), &proof.b, &proof.c]);
+        transcript.extend(inputs.iter().map(|input| input.as_slice()));
+    }
+    let seed = keccak::hashv(&transcript).to_bytes();
+
+    (0..proofs.len() as u32)
+        .map(|i| {
+            if i == 0 {
+                return 1;
+            }
+            let hash = keccak::hashv(&[&seed, &i.to_le_bytes()]).to_bytes();
+            u128::from_be_bytes(hash[..COEFFICIENT_BYTES].try_into().unwrap())
+        })
+        .collect()
+}
+
+/// r · P, skipping the syscall for r = 1
+fn scale(point: &[u8; 64], r: &u128) -> Result<[u8; 64]> {
+    if *r == 1 {
+        return Ok(*point);
+    }
+    let mut scalar = [0u8; 32];
+    scalar[16..].copy_from_slice(&r.to_be_bytes());
+    g1_mul(point, &scalar)
+}
+
+fn g1_mul(point: &[u8; 64], scalar: &[u8; 32]) -> Result<[u8; 64]> {
+    let result = alt_bn128_multiplication(&[&point[..], &scalar[..]].concat())
+        .map_err(|_| error!(VerifierError::Bn128Error))?;
+    result
+        .try_into()
+        .map_err(|_| error!(VerifierError::Bn128Error))
+}
+
+fn g1_add(p: &[u8; 64], q: &[u8; 64]) -> Result<[u8; 64]> {
+    let result = alt_bn128_addition(&[&p[..], &q[..]].concat())
+        .map_err(|_| error!(VerifierError::Bn128Error))?;
+    result
+        .try_into()
+        .map_err(|_| error!(VerifierError::Bn128Error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::synthetic_proof_seeded;
+
+    fn batch(n: usize, size: u64) -> (Groth16VerifyingKey, Vec<Groth16Proof>, Vec<Vec<[u8; 32]>>) {
+        let vk = synthetic_proof_seeded(n, 0).0;
+        let (proofs, inputs) = (0..size)
+            .map(|seed| {
+                let (_, proof, inputs) = synthetic_proof_seeded(n, seed);
+                (proof, inputs)
+            })
+            .unzip();
+        (vk, proofs, inputs)
+    }
+
+    #[test]
+    fn test_verify_batch() {
+        for (n, size) in [(3, 1), (3, 4), (17, MAX_BATCH_PROOFS as u64)] {
+            let (vk, proofs, mut inputs) = batch(n, size);
+            assert!(verify_batch(&vk, &proofs, &inputs).unwrap(), "n = {}", n);
+
+            // One bad proof fails the whole batch
+            let last = inputs.len() - 1;
+            inputs[last][0][31] ^= 1;
+            assert!(!verify_batch(&vk, &proofs, &inputs).unwrap(), "n = {}", n);
+        }
+    }
+
+    #[test]
+    fn test_verify_batch_rejects_swapped_inputs() {
+        let (vk, proofs, mut inputs) = batch(2, 2);
+        inputs.swap(0, 1);
+        assert!(!verify_batch(&vk, &proofs, &inputs).unwrap());
+    }
+
+    #[test]
+    fn test_verify_batch_rejects_malformed() {
+        let (vk, proofs, mut inputs) = batch(2, 2);
+        assert!(verify_batch(&vk, &[], &[]).is_err());
+        assert!(verify_batch(&vk, &proofs, &inputs[..1]).is_err());
+
+        inputs[1][0] = [0xff; 32];
+        assert!(verify_batch(&vk, &proofs, &inputs).is_err());
+    }
+}
@@ -35,4 +35,40 @@ pub enum VerifierError {
 
     #[msg("Invalid pool account data")]
     InvalidPoolAccount,
+
+    #[msg("Invalid admin multisig account data")]
+    InvalidAdminMultisig,
+
+    #[msg("Not enough admin multisig approvals")]
+    InsufficientApprovals,
+
+    #[msg("Batch must contain between 1 and MAX_BATCH_PROOFS proofs")]
+    InvalidBatchSize,
+
+    #[msg("Circuit is not registered in the pool's circuit registry")]
+    UnregisteredCircuit,
+
+    #[msg("Circuit is already registered")]
+    CircuitAlreadyRegistered,
+
+    #[msg("Circuit registry is full")]
+    CircuitRegistryFull,
+
+    #[msg("Circuit name or version is empty or too long")]
+    InvalidCircuitName,
+
+    #[msg("Verification key does not match the circuit's registered commitment")]
+    VkCommitmentMismatch,
+
+    #[msg("Verification key has been revoked")]
+    VerificationKeyRevoked,
+
+    #[msg("Verification key must be revoked before it can be closed")]
+    VerificationKeyNotRevoked,
+
+    #[msg("Verification key close delay has not elapsed")]
+    VkCloseDelayNotElapsed,
+
+    #[msg("Public input buffer would exceed MAX_PUBLIC_INPUTS")]
+    InputBufferFull,
 }
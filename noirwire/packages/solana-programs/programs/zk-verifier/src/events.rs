@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+
+#[event]
+pub struct ProofVerifiedEvent {
+    pub pool: Pubkey,
+    pub verification_key: Pubkey,
+    pub circuit_id: [u8; 32],
+    pub version: u32,
+    pub public_inputs_hash: [u8; 32],
+    pub compute_units: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VkRevokedEvent {
+    pub pool: Pubkey,
+    pub verification_key: Pubkey,
+    pub circuit_id: [u8; 32],
+    pub version: u32,
+    pub revoked_slot: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VkClosedEvent {
+    pub pool: Pubkey,
+    pub verification_key: Pubkey,
+    pub circuit_id: [u8; 32],
+    pub version: u32,
+    pub rent_recipient: Pubkey,
+    pub timestamp: i64,
+}
@@ -1,4 +1,5 @@
 use crate::errors::VerifierError;
+use crate::state::PreparedVerificationKey;
 use anchor_lang::prelude::*;
 use groth16_solana::{
     errors::Groth16Error,
@@ -42,6 +43,23 @@ use groth16_solana::{
 /// ```rust,ignore
 /// solana_program::compute_budget::request_units(250_000, 0)?;
 /// ```
+/// Maximum number of public inputs a verification key may have
+///
+/// Only MAX_DIRECT_PUBLIC_INPUTS fit inline in a verify transaction; larger
+/// input sets are staged in a PublicInputBuffer and checked by `verify_buffered`.
+pub const MAX_PUBLIC_INPUTS: usize = 128;
+
+/// Most public inputs `verify_prepared` can take inline
+///
+/// A legacy transaction is capped at 1232 bytes. With one signer, a compute
+/// budget instruction and the 256-byte proof, 22 inputs (32 bytes each) fit.
+pub const MAX_DIRECT_PUBLIC_INPUTS: usize = 22;
+
+/// Above 16 inputs, input counts are padded up to a multiple of this step
+/// (see `verify_padded`), bounding both the number of const generic
+/// instantiations and the wasted scalar multiplications (< 8 per proof)
+const PADDING_STEP: usize = 8;
+
 /// Groth16 proof structure for BN254 curve
 ///
 /// Points are in **uncompressed** format (not compressed as originally documented).
@@ -201,6 +219,63 @@ pub fn verify_proof(
     let result =
         verify_with_dynamic_inputs(&proof.a, &proof.b, &proof.c, public_inputs, &groth16_vk);
 
+    verification_result(result)
+}
+
+/// Verify a Groth16 proof against a PreparedVerificationKey
+///
+/// Same checks and pairing equation as `verify_proof`, but the key is read in
+/// place from the zero-copy account and its IC points are already padded, so
+/// no IC vector is cloned or resized.
+pub fn verify_prepared(
+    vk: &PreparedVerificationKey,
+    proof: &Groth16Proof,
+    public_inputs: &[[u8; 32]],
+) -> Result<bool> {
+    require!(
+        public_inputs.len() == vk.nr_public_inputs as usize,
+        VerifierError::InputCountMismatch
+    );
+
+    let padded_len = vk.padded_inputs as usize;
+    require!(
+        padded_len == padded_input_count(public_inputs.len()),
+        VerifierError::InvalidVerificationKey
+    );
+
+    let groth16_vk = Groth16Verifyingkey {
+        nr_pubinputs: padded_len,
+        vk_alpha_g1: vk.alpha_g1,
+        vk_beta_g2: vk.beta_g2,
+        vk_gamme_g2: vk.gamma_g2,
+        vk_delta_g2: vk.delta_g2,
+        vk_ic: &vk.ic[..=padded_len],
+    };
+
+    let result = if padded_len == public_inputs.len() {
+        verify_with_dynamic_inputs(&proof.a, &proof.b, &proof.c, public_inputs, &groth16_vk)
+    } else {
+        let mut padded_inputs = public_inputs.to_vec();
+        padded_inputs.resize(padded_len, [0u8; 32]);
+        verify_padded_count(&proof.a, &proof.b, &proof.c, &padded_inputs, &groth16_vk)
+    };
+
+    verification_result(result)
+}
+
+/// Input count the verifier dispatches on for `n` public inputs
+///
+/// Counts up to 16 are used as-is; larger counts round up to PADDING_STEP.
+pub fn padded_input_count(n: usize) -> usize {
+    if n <= 16 {
+        n
+    } else {
+        n.next_multiple_of(PADDING_STEP)
+    }
+}
+
+/// Map a library result to Ok(valid) or a VerifierError
+fn verification_result(result: core::result::Result<(), Groth16Error>) -> Result<bool> {
     match result {
         Ok(()) => Ok(true),
         Err(Groth16Error::ProofVerificationFailed) => Ok(false),
@@ -215,7 +290,7 @@ pub fn verify_proof(
 ///
 /// The groth16-solana library uses const generics for input count, but we need
 /// runtime flexibility. This function dispatches to the appropriate const generic
-/// instantiation or falls back to manual verification for large input counts.
+/// instantiation, padding counts above 16 (up to MAX_PUBLIC_INPUTS).
 ///
 /// **SECURITY NOTE**: This function is NOT public API. Only call via verify_proof.
 fn verify_with_dynamic_inputs(
@@ -248,8 +323,72 @@ fn verify_with_dynamic_inputs(
         15 => verify_with_count::<15>(proof_a, proof_b, proof_c, public_inputs, vk),
         16 => verify_with_count::<16>(proof_a, proof_b, proof_c, public_inputs, vk),
 
-        // For larger input counts, we could add more cases or implement
-        // a fallback using the library's prepare_inputs API directly
+        // Larger circuits (e.g. batch settlement) share padded instantiations
+        n if n <= MAX_PUBLIC_INPUTS => verify_padded(proof_a, proof_b, proof_c, public_inputs, vk),
+
+        _ => Err(Groth16Error::InvalidPublicInputsLength),
+    }
+}
+
+/// Verify with the input count padded up to the next multiple of PADDING_STEP
+///
+/// Padding appends zero public inputs and zero (point at infinity) IC points.
+/// Each padded term contributes 0 · O = O to vk_x = IC₀ + Σ(pubᵢ · ICᵢ₊₁), so
+/// the pairing check is unchanged and all curve arithmetic stays in the library.
+fn verify_padded(
+    proof_a: &[u8; 64],
+    proof_b: &[u8; 128],
+    proof_c: &[u8; 64],
+    public_inputs: &[[u8; 32]],
+    vk: &Groth16Verifyingkey,
+) -> core::result::Result<(), Groth16Error> {
+    if vk.vk_ic.len() != public_inputs.len() + 1 {
+        return Err(Groth16Error::InvalidPublicInputsLength);
+    }
+
+    let padded_len = padded_input_count(public_inputs.len());
+
+    let mut padded_inputs = public_inputs.to_vec();
+    padded_inputs.resize(padded_len, [0u8; 32]);
+
+    let mut padded_ic = vk.vk_ic.to_vec();
+    padded_ic.resize(padded_len + 1, [0u8; 64]);
+
+    let padded_vk = Groth16Verifyingkey {
+        nr_pubinputs: padded_len,
+        vk_alpha_g1: vk.vk_alpha_g1,
+        vk_beta_g2: vk.vk_beta_g2,
+        vk_gamme_g2: vk.vk_gamme_g2,
+        vk_delta_g2: vk.vk_delta_g2,
+        vk_ic: padded_ic.as_slice(),
+    };
+
+    verify_padded_count(proof_a, proof_b, proof_c, &padded_inputs, &padded_vk)
+}
+
+/// Dispatch an already padded input count (a multiple of PADDING_STEP above 16)
+fn verify_padded_count(
+    proof_a: &[u8; 64],
+    proof_b: &[u8; 128],
+    proof_c: &[u8; 64],
+    inputs: &[[u8; 32]],
+    padded_vk: &Groth16Verifyingkey,
+) -> core::result::Result<(), Groth16Error> {
+    match inputs.len() {
+        24 => verify_with_count::<24>(proof_a, proof_b, proof_c, inputs, padded_vk),
+        32 => verify_with_count::<32>(proof_a, proof_b, proof_c, inputs, padded_vk),
+        40 => verify_with_count::<40>(proof_a, proof_b, proof_c, inputs, padded_vk),
+        48 => verify_with_count::<48>(proof_a, proof_b, proof_c, inputs, padded_vk),
+        56 => verify_with_count::<56>(proof_a, proof_b, proof_c, inputs, padded_vk),
+        64 => verify_with_count::<64>(proof_a, proof_b, proof_c, inputs, padded_vk),
+        72 => verify_with_count::<72>(proof_a, proof_b, proof_c, inputs, padded_vk),
+        80 => verify_with_count::<80>(proof_a, proof_b, proof_c, inputs, padded_vk),
+        88 => verify_with_count::<88>(proof_a, proof_b, proof_c, inputs, padded_vk),
+        96 => verify_with_count::<96>(proof_a, proof_b, proof_c, inputs, padded_vk),
+        104 => verify_with_count::<104>(proof_a, proof_b, proof_c, inputs, padded_vk),
+        112 => verify_with_count::<112>(proof_a, proof_b, proof_c, inputs, padded_vk),
+        120 => verify_with_count::<120>(proof_a, proof_b, proof_c, inputs, padded_vk),
+        128 => verify_with_count::<128>(proof_a, proof_b, proof_c, inputs, padded_vk),
         _ => Err(Groth16Error::InvalidPublicInputsLength),
     }
 }
@@ -308,6 +447,7 @@ impl VerifierError {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_utils::synthetic_proof;
 
     /// Test vector validation
     ///
@@ -342,6 +482,102 @@ mod tests {
         assert_eq!(vk.ic.len(), (vk.nr_public_inputs + 1) as usize);
     }
 
+    #[test]
+    fn test_verify_beyond_sixteen_inputs() {
+        for n in [3, 16, 17, 24, 70, MAX_PUBLIC_INPUTS] {
+            let (vk, proof, mut inputs) = synthetic_proof(n);
+            assert!(verify_proof(&vk, &proof, &inputs).unwrap(), "n = {}", n);
+
+            // Any changed input must fail
+            inputs[n - 1][31] ^= 1;
+            assert!(!verify_proof(&vk, &proof, &inputs).unwrap(), "n = {}", n);
+        }
+    }
+
+    /// Solana's transaction size limit (PACKET_DATA_SIZE)
+    const PACKET_DATA_SIZE: usize = 1232;
+
+    /// Size of a signed transaction holding a compute budget instruction and
+    /// `verify_prepared` with `n` inline inputs
+    fn verify_prepared_tx_size(n: usize) -> usize {
+        use anchor_lang::solana_program::instruction::Instruction;
+        use anchor_lang::solana_program::message::Message;
+        use anchor_lang::{InstructionData, ToAccountMetas};
+
+        // SetComputeUnitLimit(1_400_000)
+        let compute_budget =
+            Instruction::new_with_bytes(Pubkey::new_unique(), &[2, 0xc0, 0x5c, 0x15, 0x00], vec![]);
+        let verify = Instruction {
+            program_id: crate::ID,
+            accounts: crate::accounts::VerifyPrepared {
+                prepared_vk: Pubkey::new_unique(),
+            }
+            .to_account_metas(None),
+            data: crate::instruction::VerifyPrepared {
+                proof: Groth16Proof {
+                    a: [0u8; 64],
+                    b: [0u8; 128],
+                    c: [0u8; 64],
+                },
+                public_inputs: vec![[0u8; 32]; n],
+            }
+            .data(),
+        };
+        let message = Message::new(&[compute_budget, verify], Some(&Pubkey::new_unique()));
+
+        // Signature count plus the payer's signature
+        1 + 64 + message.serialize().len()
+    }
+
+    #[test]
+    fn test_max_direct_inputs_fit_one_transaction() {
+        assert!(verify_prepared_tx_size(MAX_DIRECT_PUBLIC_INPUTS) <= PACKET_DATA_SIZE);
+        assert!(verify_prepared_tx_size(MAX_DIRECT_PUBLIC_INPUTS + 1) > PACKET_DATA_SIZE);
+    }
+
+    #[test]
+    fn test_reject_more_than_max_inputs() {
+        let (vk, proof, inputs) = synthetic_proof(MAX_PUBLIC_INPUTS + 1);
+        assert!(verify_proof(&vk, &proof, &inputs).is_err());
+    }
+
+    #[test]
+    fn test_verify_prepared_matches_verify_proof() {
+        for n in [3, 16, 17, 70, MAX_PUBLIC_INPUTS] {
+            let (vk, proof, mut inputs) = synthetic_proof(n);
+            let stored = crate::state::VerificationKey {
+                pool: Pubkey::default(),
+                circuit_id: [0u8; 32],
+                version: 1,
+                alpha_g1: vk.alpha_g1,
+                beta_g2: vk.beta_g2,
+                gamma_g2: vk.gamma_g2,
+                delta_g2: vk.delta_g2,
+                ic_length: vk.ic.len() as u8,
+                ic: vk.ic.clone(),
+                revoked_slot: 0,
+                bump: 0,
+            };
+            let mut prepared: Box<PreparedVerificationKey> = Box::new(bytemuck::Zeroable::zeroed());
+            prepared.prepare(Pubkey::new_unique(), &stored);
+            assert_eq!(prepared.padded_inputs as usize, padded_input_count(n));
+
+            assert!(
+                verify_prepared(&prepared, &proof, &inputs).unwrap(),
+                "n = {}",
+                n
+            );
+
+            inputs[0][31] ^= 1;
+            assert!(
+                !verify_prepared(&prepared, &proof, &inputs).unwrap(),
+                "n = {}",
+                n
+            );
+            assert!(verify_prepared(&prepared, &proof, &inputs[1..]).is_err());
+        }
+    }
+
     // Integration test with real proof vectors
     //
     // TODO: Replace with actual test vectors from your circuit:
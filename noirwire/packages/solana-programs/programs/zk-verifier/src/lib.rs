@@ -5,11 +5,21 @@ use anchor_lang::prelude::*;
 
 declare_id!("NWRNe5ezj9SxCXVqrXbycbpT8drAvuaBknX3ChgGbnx");
 
+pub mod admin;
+pub mod batch;
 pub mod errors;
+pub mod events;
 pub mod groth16;
+pub mod registry;
 pub mod state;
 
+#[cfg(test)]
+mod test_utils;
+
+use anchor_lang::solana_program::compute_units::sol_remaining_compute_units;
+use events::{ProofVerifiedEvent, VkClosedEvent, VkRevokedEvent};
 use groth16::{Groth16Proof, Groth16VerifyingKey};
+use registry::*;
 use state::*;
 
 #[program]
@@ -18,16 +28,55 @@ pub mod zk_verifier {
 
     /// Verify a Groth16 proof
     /// Requires ~150k-200k compute units
+    /// Returns a VerificationResult as return data
     pub fn verify(
         ctx: Context<VerifyProof>,
         proof: Groth16Proof,
         public_inputs: Vec<[u8; 32]>,
-    ) -> Result<()> {
+    ) -> Result<VerificationResult> {
+        let result = verify_with_result(&ctx.accounts.verification_key, &proof, &public_inputs)?;
+
+        msg!("Proof verified successfully");
+        Ok(result)
+    }
+
+    /// Verify a Groth16 proof and emit ProofVerifiedEvent
+    /// Same checks and return data as `verify`
+    pub fn verify_and_log(
+        ctx: Context<VerifyProof>,
+        proof: Groth16Proof,
+        public_inputs: Vec<[u8; 32]>,
+    ) -> Result<VerificationResult> {
+        let vk = &ctx.accounts.verification_key;
+        let result = verify_with_result(vk, &proof, &public_inputs)?;
+
+        emit!(ProofVerifiedEvent {
+            pool: vk.pool,
+            verification_key: vk.key(),
+            circuit_id: result.circuit_id,
+            version: vk.version,
+            public_inputs_hash: result.public_inputs_hash,
+            compute_units: result.compute_units,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Proof verified successfully");
+        Ok(result)
+    }
+
+    /// Verify several Groth16 proofs for the same circuit in one multi-pairing
+    /// Proof i is checked against public_inputs[i]; fails if any proof is invalid
+    /// Returns a BatchVerificationResult (one inputs hash per proof) as return data
+    pub fn verify_batch(
+        ctx: Context<VerifyProof>,
+        proofs: Vec<Groth16Proof>,
+        public_inputs: Vec<Vec<[u8; 32]>>,
+    ) -> Result<BatchVerificationResult> {
+        let start_units = sol_remaining_compute_units();
         let vk_account = &ctx.accounts.verification_key;
 
-        // Convert account data to Groth16VerifyingKey structure
         let vk = Groth16VerifyingKey {
-            nr_public_inputs: public_inputs.len() as u32,
+            nr_public_inputs: vk_account.ic.len().saturating_sub(1) as u32,
             alpha_g1: vk_account.alpha_g1,
             beta_g2: vk_account.beta_g2,
             gamma_g2: vk_account.gamma_g2,
@@ -35,54 +84,184 @@ pub mod zk_verifier {
             ic: vk_account.ic.clone(),
         };
 
-        // Verify proof using groth16-solana library
-        let result = groth16::verify_proof(&vk, &proof, &public_inputs)?;
+        let result = batch::verify_batch(&vk, &proofs, &public_inputs)?;
+
+        require!(result, errors::VerifierError::InvalidProof);
+
+        msg!("{} proofs verified successfully", proofs.len());
+        Ok(BatchVerificationResult {
+            circuit_id: vk_account.circuit_id,
+            public_inputs_hashes: public_inputs
+                .iter()
+                .map(|inputs| VerificationResult::hash_public_inputs(inputs))
+                .collect(),
+            compute_units: start_units.saturating_sub(sol_remaining_compute_units()),
+        })
+    }
+
+    /// Verify a Groth16 proof against the cached PreparedVerificationKey
+    /// Cheaper than `verify`: the key is read zero-copy instead of deserialized
+    /// Returns a VerificationResult as return data, like `verify`
+    pub fn verify_prepared(
+        ctx: Context<VerifyPrepared>,
+        proof: Groth16Proof,
+        public_inputs: Vec<[u8; 32]>,
+    ) -> Result<VerificationResult> {
+        let start_units = sol_remaining_compute_units();
+        let prepared_vk = ctx.accounts.prepared_vk.load()?;
+
+        let result = groth16::verify_prepared(&prepared_vk, &proof, &public_inputs)?;
 
         require!(result, errors::VerifierError::InvalidProof);
 
         msg!("Proof verified successfully");
+        Ok(VerificationResult::new(
+            prepared_vk.circuit_id,
+            &public_inputs,
+            start_units,
+        ))
+    }
+
+    /// Create an empty PublicInputBuffer owned by the signing authority
+    /// For keys with more public inputs than fit in one verify transaction
+    pub fn init_input_buffer(ctx: Context<InitInputBuffer>, buffer_id: u64) -> Result<()> {
+        let buffer = &mut ctx.accounts.input_buffer;
+        buffer.authority = ctx.accounts.authority.key();
+        buffer.buffer_id = buffer_id;
+        buffer.inputs = Vec::new();
+        buffer.bump = ctx.bumps.input_buffer;
+        Ok(())
+    }
+
+    /// Append public inputs to a buffer (up to MAX_DIRECT_PUBLIC_INPUTS per call)
+    pub fn write_input_buffer(ctx: Context<WriteInputBuffer>, inputs: Vec<[u8; 32]>) -> Result<()> {
+        let buffer = &mut ctx.accounts.input_buffer;
+        require!(
+            buffer.inputs.len() + inputs.len() <= groth16::MAX_PUBLIC_INPUTS,
+            errors::VerifierError::InputBufferFull
+        );
+        buffer.inputs.extend(inputs);
+
+        msg!("Input buffer holds {} inputs", buffer.inputs.len());
+        Ok(())
+    }
+
+    /// Verify a Groth16 proof against the cached PreparedVerificationKey, with
+    /// the public inputs read from a PublicInputBuffer
+    /// Returns a VerificationResult as return data, like `verify_prepared`
+    pub fn verify_buffered(
+        ctx: Context<VerifyBuffered>,
+        proof: Groth16Proof,
+    ) -> Result<VerificationResult> {
+        let start_units = sol_remaining_compute_units();
+        let prepared_vk = ctx.accounts.prepared_vk.load()?;
+        let public_inputs = &ctx.accounts.input_buffer.inputs;
+
+        let result = groth16::verify_prepared(&prepared_vk, &proof, public_inputs)?;
+
+        require!(result, errors::VerifierError::InvalidProof);
+
+        msg!("Proof verified successfully");
+        Ok(VerificationResult::new(
+            prepared_vk.circuit_id,
+            public_inputs,
+            start_units,
+        ))
+    }
+
+    /// Close a PublicInputBuffer, refunding its rent to the authority
+    pub fn close_input_buffer(_ctx: Context<CloseInputBuffer>) -> Result<()> {
+        Ok(())
+    }
+
+    /// Create the PreparedVerificationKey for a key stored before it existed
+    /// Permissionless: the cache is derived entirely from the stored key
+    pub fn prepare_vk(ctx: Context<PrepareVk>) -> Result<()> {
+        let vk = &ctx.accounts.verification_key;
+
+        let mut prepared_vk = ctx.accounts.prepared_vk.load_init()?;
+        prepared_vk.prepare(vk.key(), vk);
+        prepared_vk.bump = ctx.bumps.prepared_vk;
+
+        msg!("Prepared verification key for circuit: {:?}", vk.circuit_id);
+        Ok(())
+    }
+
+    /// Create the pool's circuit registry
+    /// SECURITY: Only pool authority can create the registry
+    pub fn init_circuit_registry(ctx: Context<InitCircuitRegistry>) -> Result<()> {
+        admin::require_pool_admin(
+            &ctx.accounts.pool,
+            &ctx.accounts.authority,
+            ctx.remaining_accounts,
+        )?;
+
+        let registry = &mut ctx.accounts.circuit_registry;
+        registry.pool = ctx.accounts.pool.key();
+        registry.circuits = Vec::new();
+        registry.bump = ctx.bumps.circuit_registry;
+
+        msg!("Circuit registry initialized for pool: {}", registry.pool);
+        Ok(())
+    }
+
+    /// Register a circuit; its ID is derived from name, version and the
+    /// optional VK commitment (see registry::compute_circuit_id)
+    /// SECURITY: Only pool authority can register circuits
+    pub fn register_circuit(
+        ctx: Context<RegisterCircuit>,
+        name: String,
+        version: String,
+        vk_commitment: Option<[u8; 32]>,
+    ) -> Result<()> {
+        admin::require_pool_admin(
+            &ctx.accounts.pool,
+            &ctx.accounts.authority,
+            ctx.remaining_accounts,
+        )?;
+
+        let circuit_id =
+            ctx.accounts
+                .circuit_registry
+                .register(name.clone(), version.clone(), vk_commitment)?;
+
+        msg!(
+            "Circuit {}.{} registered with ID: {:?}",
+            name,
+            version,
+            circuit_id
+        );
         Ok(())
     }
 
     /// Store a verification key for a circuit
     /// SECURITY (CRITICAL-08): Only pool authority can store/update VKs
+    /// SECURITY (MEDIUM-02): The circuit must be registered; a VK-bound
+    /// circuit's key must be stored whole (not extended with append_vk_ic)
+    /// Each version is its own account, so a new version can be staged in the
+    /// pool while the previous one keeps verifying
     pub fn store_vk(
         ctx: Context<StoreVk>,
         circuit_id: [u8; 32],
+        version: u32,
         vk_data: VerificationKeyData,
     ) -> Result<()> {
-        // SECURITY: Validate authority is pool admin
-        // Pool account data layout (from shielded-pool PoolState):
-        // - 8 bytes: discriminator
-        // - 32 bytes: authority pubkey (offset 8)
-        // - 32 bytes: per_authority (offset 40)
-        // - ... rest of fields
-        let pool_data = ctx.accounts.pool.try_borrow_data()?;
-
-        // Verify account has enough data
-        require!(
-            pool_data.len() >= 41,
-            errors::VerifierError::InvalidPoolAccount
-        );
-
-        // Extract authority pubkey from pool data (bytes 9-41)
-        // Layout: 8-byte discriminator + 1-byte version + 32-byte authority
-        let mut authority_bytes = [0u8; 32];
-        authority_bytes.copy_from_slice(&pool_data[9..41]);
-        let pool_authority = Pubkey::new_from_array(authority_bytes);
-
-        // Verify signer is pool authority
-        require!(
-            pool_authority == ctx.accounts.authority.key(),
-            errors::VerifierError::Unauthorized
-        );
+        // SECURITY: Validate authority is pool admin (single key or AdminMultisig)
+        admin::require_pool_admin(
+            &ctx.accounts.pool,
+            &ctx.accounts.authority,
+            ctx.remaining_accounts,
+        )?;
 
         msg!("Authorization verified: authority is pool admin");
 
+        VerificationKeyData::validate_ic_count(vk_data.ic.len())?;
+
         let vk = &mut ctx.accounts.verification_key;
 
         vk.pool = ctx.accounts.pool.key();
         vk.circuit_id = circuit_id;
+        vk.version = version;
         vk.alpha_g1 = vk_data.alpha_g1;
         vk.beta_g2 = vk_data.beta_g2;
         vk.gamma_g2 = vk_data.gamma_g2;
@@ -91,34 +270,37 @@ pub mod zk_verifier {
         vk.ic = vk_data.ic;
         vk.bump = ctx.bumps.verification_key;
 
-        msg!("Verification key stored for circuit: {:?}", circuit_id);
+        ctx.accounts.circuit_registry.require_registered_key(vk)?;
+
+        // Cache the verify-ready layout
+        let mut prepared_vk = ctx.accounts.prepared_vk.load_init()?;
+        prepared_vk.prepare(vk.key(), vk);
+        prepared_vk.bump = ctx.bumps.prepared_vk;
+
+        msg!(
+            "Verification key v{} stored for circuit: {:?}",
+            version,
+            circuit_id
+        );
         Ok(())
     }
 
     /// Update an existing verification key
     /// SECURITY (HIGH-05): Only pool authority can update VKs
+    /// Changes the key's hash: pools accepting this version stop accepting it
+    /// until the new hash is staged. Live circuits should get a new version.
     pub fn update_vk(ctx: Context<UpdateVk>, vk_data: VerificationKeyData) -> Result<()> {
         // SECURITY: Validate authority is pool admin (same as store_vk)
-        let pool_data = ctx.accounts.pool.try_borrow_data()?;
-
-        require!(
-            pool_data.len() >= 41,
-            errors::VerifierError::InvalidPoolAccount
-        );
-
-        // Extract authority pubkey from pool data (bytes 9-41)
-        // Layout: 8-byte discriminator + 1-byte version + 32-byte authority
-        let mut authority_bytes = [0u8; 32];
-        authority_bytes.copy_from_slice(&pool_data[9..41]);
-        let pool_authority = Pubkey::new_from_array(authority_bytes);
-
-        require!(
-            pool_authority == ctx.accounts.authority.key(),
-            errors::VerifierError::Unauthorized
-        );
+        admin::require_pool_admin(
+            &ctx.accounts.pool,
+            &ctx.accounts.authority,
+            ctx.remaining_accounts,
+        )?;
 
         msg!("Authorization verified: authority is pool admin");
 
+        VerificationKeyData::validate_ic_count(vk_data.ic.len())?;
+
         let vk = &mut ctx.accounts.verification_key;
 
         // Update VK data (pool and circuit_id remain unchanged)
@@ -129,50 +311,494 @@ pub mod zk_verifier {
         vk.ic_length = vk_data.ic.len() as u8;
         vk.ic = vk_data.ic;
 
+        ctx.accounts.circuit_registry.require_registered_key(vk)?;
+
+        ctx.accounts.prepared_vk.load_mut()?.prepare(vk.key(), vk);
+
         msg!("Verification key updated for circuit: {:?}", vk.circuit_id);
         Ok(())
     }
+
+    /// Append IC points to an existing verification key
+    /// Large VKs do not fit in one transaction: store/update the VK with the
+    /// first IC points, then upload the rest with one or more appends
+    pub fn append_vk_ic(ctx: Context<AppendVkIc>, ic: Vec<[u8; 64]>) -> Result<()> {
+        admin::require_pool_admin(
+            &ctx.accounts.pool,
+            &ctx.accounts.authority,
+            ctx.remaining_accounts,
+        )?;
+
+        let vk = &mut ctx.accounts.verification_key;
+        let ic_count = vk.ic.len() + ic.len();
+        VerificationKeyData::validate_ic_count(ic_count)?;
+
+        vk.ic.extend(ic);
+        vk.ic_length = ic_count as u8;
+
+        ctx.accounts.circuit_registry.require_registered_key(vk)?;
+
+        ctx.accounts.prepared_vk.load_mut()?.prepare(vk.key(), vk);
+
+        msg!(
+            "Verification key IC extended to {} points for circuit: {:?}",
+            ic_count,
+            vk.circuit_id
+        );
+        Ok(())
+    }
+
+    /// Revoke a verification key: every verify instruction rejects it from now on
+    /// SECURITY: Only pool authority can revoke VKs (same as update_vk)
+    /// Revocation is permanent; the key can be closed after VK_CLOSE_DELAY_SLOTS
+    pub fn revoke_vk(ctx: Context<RevokeVk>) -> Result<()> {
+        admin::require_pool_admin(
+            &ctx.accounts.pool,
+            &ctx.accounts.authority,
+            ctx.remaining_accounts,
+        )?;
+
+        let clock = Clock::get()?;
+        let vk = &mut ctx.accounts.verification_key;
+        // Slot 0 marks an active key
+        vk.revoked_slot = clock.slot.max(1);
+
+        ctx.accounts.prepared_vk.load_mut()?.revoked = 1;
+
+        emit!(VkRevokedEvent {
+            pool: vk.pool,
+            verification_key: vk.key(),
+            circuit_id: vk.circuit_id,
+            version: vk.version,
+            revoked_slot: vk.revoked_slot,
+            timestamp: clock.unix_timestamp,
+        });
+
+        msg!(
+            "Verification key v{} revoked for circuit: {:?}",
+            vk.version,
+            vk.circuit_id
+        );
+        Ok(())
+    }
+
+    /// Close a revoked verification key and its prepared copy, refunding rent
+    /// SECURITY: Only pool authority can close VKs, and only VK_CLOSE_DELAY_SLOTS
+    /// after revocation
+    pub fn close_vk(ctx: Context<CloseVk>) -> Result<()> {
+        admin::require_pool_admin(
+            &ctx.accounts.pool,
+            &ctx.accounts.authority,
+            ctx.remaining_accounts,
+        )?;
+
+        let clock = Clock::get()?;
+        let vk = &ctx.accounts.verification_key;
+        require!(
+            vk.is_revoked(),
+            errors::VerifierError::VerificationKeyNotRevoked
+        );
+        require!(
+            clock.slot >= vk.revoked_slot.saturating_add(VK_CLOSE_DELAY_SLOTS),
+            errors::VerifierError::VkCloseDelayNotElapsed
+        );
+
+        emit!(VkClosedEvent {
+            pool: vk.pool,
+            verification_key: vk.key(),
+            circuit_id: vk.circuit_id,
+            version: vk.version,
+            rent_recipient: ctx.accounts.rent_recipient.key(),
+            timestamp: clock.unix_timestamp,
+        });
+
+        msg!(
+            "Verification key v{} closed for circuit: {:?}",
+            vk.version,
+            vk.circuit_id
+        );
+        Ok(())
+    }
+}
+
+/// Verify `proof` against `vk_account`, measuring the compute units used
+fn verify_with_result(
+    vk_account: &VerificationKey,
+    proof: &Groth16Proof,
+    public_inputs: &[[u8; 32]],
+) -> Result<VerificationResult> {
+    let start_units = sol_remaining_compute_units();
+
+    // Convert account data to Groth16VerifyingKey structure
+    let vk = Groth16VerifyingKey {
+        nr_public_inputs: public_inputs.len() as u32,
+        alpha_g1: vk_account.alpha_g1,
+        beta_g2: vk_account.beta_g2,
+        gamma_g2: vk_account.gamma_g2,
+        delta_g2: vk_account.delta_g2,
+        ic: vk_account.ic.clone(),
+    };
+
+    // Verify proof using groth16-solana library
+    let result = groth16::verify_proof(&vk, proof, public_inputs)?;
+
+    require!(result, errors::VerifierError::InvalidProof);
+
+    Ok(VerificationResult::new(
+        vk_account.circuit_id,
+        public_inputs,
+        start_units,
+    ))
 }
 
 #[derive(Accounts)]
 pub struct VerifyProof<'info> {
     /// Verification key account
+    #[account(
+        constraint = !verification_key.is_revoked() @ errors::VerifierError::VerificationKeyRevoked
+    )]
     pub verification_key: Account<'info, VerificationKey>,
 }
 
 #[derive(Accounts)]
-#[instruction(circuit_id: [u8; 32])]
+pub struct VerifyPrepared<'info> {
+    /// Cached verify-ready verification key
+    #[account(
+        constraint = prepared_vk.load()?.revoked == 0 @ errors::VerifierError::VerificationKeyRevoked
+    )]
+    pub prepared_vk: AccountLoader<'info, PreparedVerificationKey>,
+}
+
+#[derive(Accounts)]
+#[instruction(buffer_id: u64)]
+pub struct InitInputBuffer<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + PublicInputBuffer::INIT_SPACE,
+        seeds = [INPUT_BUFFER_SEED, authority.key().as_ref(), &buffer_id.to_le_bytes()],
+        bump
+    )]
+    pub input_buffer: Account<'info, PublicInputBuffer>,
+
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WriteInputBuffer<'info> {
+    #[account(mut, has_one = authority @ errors::VerifierError::Unauthorized)]
+    pub input_buffer: Account<'info, PublicInputBuffer>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct VerifyBuffered<'info> {
+    /// Cached verify-ready verification key
+    #[account(
+        constraint = prepared_vk.load()?.revoked == 0 @ errors::VerifierError::VerificationKeyRevoked
+    )]
+    pub prepared_vk: AccountLoader<'info, PreparedVerificationKey>,
+
+    /// Public inputs to verify the proof against
+    pub input_buffer: Account<'info, PublicInputBuffer>,
+}
+
+#[derive(Accounts)]
+pub struct CloseInputBuffer<'info> {
+    #[account(
+        mut,
+        has_one = authority @ errors::VerifierError::Unauthorized,
+        close = authority
+    )]
+    pub input_buffer: Account<'info, PublicInputBuffer>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct PrepareVk<'info> {
+    pub verification_key: Account<'info, VerificationKey>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = PreparedVerificationKey::SIZE,
+        seeds = [PREPARED_VK_SEED, verification_key.key().as_ref()],
+        bump
+    )]
+    pub prepared_vk: AccountLoader<'info, PreparedVerificationKey>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitCircuitRegistry<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + CircuitRegistry::INIT_SPACE,
+        seeds = [CIRCUIT_REGISTRY_SEED, pool.key().as_ref()],
+        bump
+    )]
+    pub circuit_registry: Account<'info, CircuitRegistry>,
+
+    /// CHECK: Shielded-pool PoolState (owner and discriminator checked by
+    /// admin::pool_authority)
+    pub pool: AccountInfo<'info>,
+
+    /// Pool admin: a signer, or the pool's AdminMultisig with co-signers as
+    /// remaining accounts
+    /// CHECK: Validated by admin::require_pool_admin
+    pub authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterCircuit<'info> {
+    #[account(
+        mut,
+        seeds = [CIRCUIT_REGISTRY_SEED, pool.key().as_ref()],
+        bump = circuit_registry.bump
+    )]
+    pub circuit_registry: Account<'info, CircuitRegistry>,
+
+    /// CHECK: Shielded-pool PoolState (owner and discriminator checked by
+    /// admin::pool_authority)
+    pub pool: AccountInfo<'info>,
+
+    /// Pool admin: a signer, or the pool's AdminMultisig with co-signers as
+    /// remaining accounts
+    /// CHECK: Validated by admin::require_pool_admin
+    pub authority: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(circuit_id: [u8; 32], version: u32, vk_data: VerificationKeyData)]
 pub struct StoreVk<'info> {
     #[account(
         init,
-        payer = authority,
-        space = VerificationKey::size(16), // Default 16 IC points
-        seeds = [b"vk", pool.key().as_ref(), &circuit_id],
+        payer = payer,
+        space = VerificationKey::size(vk_data.ic.len()),
+        seeds = [b"vk", pool.key().as_ref(), &circuit_id, &version.to_le_bytes()],
         bump
     )]
     pub verification_key: Account<'info, VerificationKey>,
 
-    /// CHECK: Pool account (validated manually in handler)
+    /// Verify-ready cache of the key
+    #[account(
+        init,
+        payer = payer,
+        space = PreparedVerificationKey::SIZE,
+        seeds = [PREPARED_VK_SEED, verification_key.key().as_ref()],
+        bump
+    )]
+    pub prepared_vk: AccountLoader<'info, PreparedVerificationKey>,
+
+    /// Circuit registry of the pool
+    #[account(
+        seeds = [CIRCUIT_REGISTRY_SEED, pool.key().as_ref()],
+        bump = circuit_registry.bump
+    )]
+    pub circuit_registry: Account<'info, CircuitRegistry>,
+
+    /// CHECK: Shielded-pool PoolState (owner and discriminator checked by
+    /// admin::pool_authority)
     pub pool: AccountInfo<'info>,
 
+    /// Pool admin: a signer, or the pool's AdminMultisig with co-signers as
+    /// remaining accounts
+    /// CHECK: Validated by admin::require_pool_admin
+    pub authority: UncheckedAccount<'info>,
+
     #[account(mut)]
-    pub authority: Signer<'info>,
+    pub payer: Signer<'info>,
 
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
+#[instruction(vk_data: VerificationKeyData)]
 pub struct UpdateVk<'info> {
-    /// Existing verification key to update
+    /// Existing verification key to update (resized to the new IC count)
+    #[account(
+        mut,
+        seeds = [
+            b"vk",
+            pool.key().as_ref(),
+            &verification_key.circuit_id,
+            &verification_key.version.to_le_bytes()
+        ],
+        bump = verification_key.bump,
+        constraint = !verification_key.is_revoked() @ errors::VerifierError::VerificationKeyRevoked,
+        realloc = VerificationKey::size(vk_data.ic.len()),
+        realloc::payer = payer,
+        realloc::zero = false
+    )]
+    pub verification_key: Account<'info, VerificationKey>,
+
+    /// Verify-ready cache of the key, rebuilt after the change
     #[account(
         mut,
-        seeds = [b"vk", pool.key().as_ref(), &verification_key.circuit_id],
-        bump = verification_key.bump
+        seeds = [PREPARED_VK_SEED, verification_key.key().as_ref()],
+        bump = prepared_vk.load()?.bump
+    )]
+    pub prepared_vk: AccountLoader<'info, PreparedVerificationKey>,
+
+    /// Circuit registry of the pool
+    #[account(
+        seeds = [CIRCUIT_REGISTRY_SEED, pool.key().as_ref()],
+        bump = circuit_registry.bump
+    )]
+    pub circuit_registry: Account<'info, CircuitRegistry>,
+
+    /// CHECK: Shielded-pool PoolState (owner and discriminator checked by
+    /// admin::pool_authority)
+    pub pool: AccountInfo<'info>,
+
+    /// Pool admin: a signer, or the pool's AdminMultisig with co-signers as
+    /// remaining accounts
+    /// CHECK: Validated by admin::require_pool_admin
+    pub authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(ic: Vec<[u8; 64]>)]
+pub struct AppendVkIc<'info> {
+    /// Verification key to extend
+    #[account(
+        mut,
+        seeds = [
+            b"vk",
+            pool.key().as_ref(),
+            &verification_key.circuit_id,
+            &verification_key.version.to_le_bytes()
+        ],
+        bump = verification_key.bump,
+        constraint = !verification_key.is_revoked() @ errors::VerifierError::VerificationKeyRevoked,
+        realloc = VerificationKey::size(verification_key.ic.len() + ic.len()),
+        realloc::payer = payer,
+        realloc::zero = false
     )]
     pub verification_key: Account<'info, VerificationKey>,
 
-    /// CHECK: Pool account (validated manually in handler)
+    /// Verify-ready cache of the key, rebuilt after the change
+    #[account(
+        mut,
+        seeds = [PREPARED_VK_SEED, verification_key.key().as_ref()],
+        bump = prepared_vk.load()?.bump
+    )]
+    pub prepared_vk: AccountLoader<'info, PreparedVerificationKey>,
+
+    /// Circuit registry of the pool
+    #[account(
+        seeds = [CIRCUIT_REGISTRY_SEED, pool.key().as_ref()],
+        bump = circuit_registry.bump
+    )]
+    pub circuit_registry: Account<'info, CircuitRegistry>,
+
+    /// CHECK: Shielded-pool PoolState (owner and discriminator checked by
+    /// admin::pool_authority)
     pub pool: AccountInfo<'info>,
 
-    pub authority: Signer<'info>,
+    /// Pool admin: a signer, or the pool's AdminMultisig with co-signers as
+    /// remaining accounts
+    /// CHECK: Validated by admin::require_pool_admin
+    pub authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeVk<'info> {
+    /// Verification key to revoke
+    #[account(
+        mut,
+        seeds = [
+            b"vk",
+            pool.key().as_ref(),
+            &verification_key.circuit_id,
+            &verification_key.version.to_le_bytes()
+        ],
+        bump = verification_key.bump,
+        constraint = !verification_key.is_revoked() @ errors::VerifierError::VerificationKeyRevoked
+    )]
+    pub verification_key: Account<'info, VerificationKey>,
+
+    /// Verify-ready cache of the key, revoked with it
+    #[account(
+        mut,
+        seeds = [PREPARED_VK_SEED, verification_key.key().as_ref()],
+        bump = prepared_vk.load()?.bump
+    )]
+    pub prepared_vk: AccountLoader<'info, PreparedVerificationKey>,
+
+    /// CHECK: Shielded-pool PoolState (owner and discriminator checked by
+    /// admin::pool_authority)
+    pub pool: AccountInfo<'info>,
+
+    /// Pool admin: a signer, or the pool's AdminMultisig with co-signers as
+    /// remaining accounts
+    /// CHECK: Validated by admin::require_pool_admin
+    pub authority: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseVk<'info> {
+    /// Revoked verification key to close
+    #[account(
+        mut,
+        seeds = [
+            b"vk",
+            pool.key().as_ref(),
+            &verification_key.circuit_id,
+            &verification_key.version.to_le_bytes()
+        ],
+        bump = verification_key.bump,
+        close = rent_recipient
+    )]
+    pub verification_key: Account<'info, VerificationKey>,
+
+    /// Verify-ready cache of the key, closed with it
+    #[account(
+        mut,
+        seeds = [PREPARED_VK_SEED, verification_key.key().as_ref()],
+        bump = prepared_vk.load()?.bump,
+        close = rent_recipient
+    )]
+    pub prepared_vk: AccountLoader<'info, PreparedVerificationKey>,
+
+    /// CHECK: Shielded-pool PoolState (owner and discriminator checked by
+    /// admin::pool_authority)
+    pub pool: AccountInfo<'info>,
+
+    /// Pool admin: a signer, or the pool's AdminMultisig with co-signers as
+    /// remaining accounts
+    /// CHECK: Validated by admin::require_pool_admin
+    pub authority: UncheckedAccount<'info>,
+
+    /// Receives the rent of both closed accounts
+    #[account(mut)]
+    pub rent_recipient: SystemAccount<'info>,
 }
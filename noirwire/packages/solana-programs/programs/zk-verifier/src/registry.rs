@@ -0,0 +1,231 @@
+//! # Circuit Registry
+//!
+//! Each pool keeps a registry of the circuits it accepts keys for. An entry
+//! records the circuit's name, version and (optionally) a commitment to its
+//! verification key, and the circuit ID is derived from them:
+//! ```text
+//! circuit_id = keccak256("noirwire.{name}.{version}" || vk_commitment)
+//! ```
+//! `store_vk` only accepts registered circuit IDs, and a VK-bound entry only
+//! accepts the key matching its commitment. The shielded pool resolves which
+//! circuit IDs are deposit, withdraw, ... circuits by name from the registry,
+//! so new circuits can be added without a program upgrade.
+
+use crate::errors::VerifierError;
+use crate::state::VerificationKey;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak;
+
+/// Seed for a pool's CircuitRegistry PDA: [CIRCUIT_REGISTRY_SEED, pool]
+pub const CIRCUIT_REGISTRY_SEED: &[u8] = b"circuit_registry";
+
+/// Maximum number of circuits in one registry
+pub const MAX_REGISTERED_CIRCUITS: usize = 16;
+
+/// Maximum length of a circuit name (e.g. "batch_settlement")
+pub const MAX_CIRCUIT_NAME_LEN: usize = 32;
+
+/// Maximum length of a circuit version (e.g. "v2")
+pub const MAX_CIRCUIT_VERSION_LEN: usize = 16;
+
+/// Compute circuit ID from circuit name, version, and VK commitment
+///
+/// # Arguments
+/// * `circuit_name` - Name of the circuit (e.g., "deposit", "withdraw")
+/// * `version` - Circuit version string (e.g., "v2")
+/// * `vk_commitment` - Optional commitment to the verification key
+///   (see `VerificationKey::commitment`)
+///
+/// # Returns
+/// A 32-byte circuit identifier
+pub fn compute_circuit_id(
+    circuit_name: &str,
+    version: &str,
+    vk_commitment: Option<&[u8; 32]>,
+) -> [u8; 32] {
+    let base_string = format!("noirwire.{}.{}", circuit_name, version);
+
+    if let Some(vk) = vk_commitment {
+        // Include VK commitment for full circuit binding
+        let mut data = base_string.into_bytes();
+        data.extend_from_slice(vk);
+        keccak::hash(&data).to_bytes()
+    } else {
+        // Base circuit ID without VK binding
+        keccak::hash(base_string.as_bytes()).to_bytes()
+    }
+}
+
+/// One registered circuit
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq, InitSpace)]
+pub struct RegisteredCircuit {
+    /// Circuit name (e.g. "deposit")
+    #[max_len(MAX_CIRCUIT_NAME_LEN)]
+    pub name: String,
+
+    /// Circuit version (e.g. "v2")
+    #[max_len(MAX_CIRCUIT_VERSION_LEN)]
+    pub version: String,
+
+    /// Commitment the circuit's key must match (None = not bound to a key)
+    pub vk_commitment: Option<[u8; 32]>,
+
+    /// compute_circuit_id(name, version, vk_commitment)
+    pub circuit_id: [u8; 32],
+}
+
+/// Circuits a pool accepts verification keys for
+#[account]
+#[derive(InitSpace)]
+pub struct CircuitRegistry {
+    /// Pool this registry belongs to
+    pub pool: Pubkey,
+
+    #[max_len(MAX_REGISTERED_CIRCUITS)]
+    pub circuits: Vec<RegisteredCircuit>,
+
+    /// Bump seed
+    pub bump: u8,
+}
+
+impl CircuitRegistry {
+    /// Registered circuit with this ID, if any
+    pub fn get(&self, circuit_id: &[u8; 32]) -> Option<&RegisteredCircuit> {
+        self.circuits
+            .iter()
+            .find(|circuit| circuit.circuit_id == *circuit_id)
+    }
+
+    /// Whether `circuit_id` is a registered circuit named `name`
+    pub fn is_registered_as(&self, circuit_id: &[u8; 32], name: &str) -> bool {
+        self.get(circuit_id)
+            .is_some_and(|circuit| circuit.name == name)
+    }
+
+    /// Register a circuit and return its ID
+    pub fn register(
+        &mut self,
+        name: String,
+        version: String,
+        vk_commitment: Option<[u8; 32]>,
+    ) -> Result<[u8; 32]> {
+        require!(
+            !name.is_empty()
+                && name.len() <= MAX_CIRCUIT_NAME_LEN
+                && !version.is_empty()
+                && version.len() <= MAX_CIRCUIT_VERSION_LEN,
+            VerifierError::InvalidCircuitName
+        );
+
+        let circuit_id = compute_circuit_id(&name, &version, vk_commitment.as_ref());
+        require!(
+            self.get(&circuit_id).is_none(),
+            VerifierError::CircuitAlreadyRegistered
+        );
+        require!(
+            self.circuits.len() < MAX_REGISTERED_CIRCUITS,
+            VerifierError::CircuitRegistryFull
+        );
+
+        self.circuits.push(RegisteredCircuit {
+            name,
+            version,
+            vk_commitment,
+            circuit_id,
+        });
+        Ok(circuit_id)
+    }
+
+    /// Check that `vk` may be stored for its circuit: the circuit must be
+    /// registered, and a VK-bound circuit only accepts its committed key
+    pub fn require_registered_key(&self, vk: &VerificationKey) -> Result<()> {
+        let circuit = self
+            .get(&vk.circuit_id)
+            .ok_or(VerifierError::UnregisteredCircuit)?;
+        if let Some(commitment) = circuit.vk_commitment {
+            require!(
+                vk.commitment() == commitment,
+                VerifierError::VkCommitmentMismatch
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registry() -> CircuitRegistry {
+        CircuitRegistry {
+            pool: Pubkey::new_unique(),
+            circuits: Vec::new(),
+            bump: 255,
+        }
+    }
+
+    fn key(circuit_id: [u8; 32], seed: u8) -> VerificationKey {
+        VerificationKey {
+            pool: Pubkey::default(),
+            circuit_id,
+            version: 1,
+            alpha_g1: [seed; 64],
+            beta_g2: [seed; 128],
+            gamma_g2: [seed; 128],
+            delta_g2: [seed; 128],
+            ic_length: 2,
+            ic: vec![[seed; 64]; 2],
+            revoked_slot: 0,
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn test_register_circuit() {
+        let mut registry = registry();
+
+        // Unbound IDs match the constants clients already use
+        let deposit = registry
+            .register("deposit".into(), "v2".into(), None)
+            .unwrap();
+        assert_eq!(deposit, keccak::hash(b"noirwire.deposit.v2").to_bytes());
+        assert!(registry.is_registered_as(&deposit, "deposit"));
+        assert!(!registry.is_registered_as(&deposit, "withdraw"));
+        assert!(!registry.is_registered_as(&[9u8; 32], "deposit"));
+
+        assert!(registry
+            .register("deposit".into(), "v2".into(), None)
+            .is_err());
+        assert!(registry.register("".into(), "v2".into(), None).is_err());
+        assert!(registry
+            .register("x".repeat(MAX_CIRCUIT_NAME_LEN + 1), "v2".into(), None)
+            .is_err());
+
+        for i in 1..MAX_REGISTERED_CIRCUITS {
+            registry
+                .register(format!("circuit_{}", i), "v1".into(), None)
+                .unwrap();
+        }
+        assert!(registry
+            .register("batch_8".into(), "v1".into(), None)
+            .is_err());
+    }
+
+    #[test]
+    fn test_vk_bound_circuit_accepts_only_committed_key() {
+        let mut registry = registry();
+        let unbound = registry
+            .register("deposit".into(), "v2".into(), None)
+            .unwrap();
+        assert!(registry.require_registered_key(&key(unbound, 1)).is_ok());
+        assert!(registry.require_registered_key(&key([9u8; 32], 1)).is_err());
+
+        let commitment = key([0u8; 32], 1).commitment();
+        let bound = registry
+            .register("batch_8".into(), "v1".into(), Some(commitment))
+            .unwrap();
+        assert_ne!(bound, compute_circuit_id("batch_8", "v1", None));
+        assert!(registry.require_registered_key(&key(bound, 1)).is_ok());
+        assert!(registry.require_registered_key(&key(bound, 2)).is_err());
+    }
+}
@@ -1,4 +1,16 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::compute_units::sol_remaining_compute_units;
+use anchor_lang::solana_program::keccak;
+
+use crate::errors::VerifierError;
+use crate::groth16::MAX_PUBLIC_INPUTS;
+
+/// Maximum IC points in a verification key (one per public input plus IC[0])
+pub const MAX_IC_POINTS: usize = MAX_PUBLIC_INPUTS + 1;
+
+/// Delay between revoking a verification key and closing it (~1 day)
+/// Leaves the revoked key on-chain long enough for indexers and auditors
+pub const VK_CLOSE_DELAY_SLOTS: u64 = 216_000;
 
 #[account]
 pub struct VerificationKey {
@@ -8,6 +20,9 @@ pub struct VerificationKey {
     /// Circuit identifier (e.g., "transfer", "batch_64")
     pub circuit_id: [u8; 32],
 
+    /// Key version within the circuit (part of the PDA seeds)
+    pub version: u32,
+
     /// Alpha G1 point (64 bytes - compressed)
     pub alpha_g1: [u8; 64],
 
@@ -25,6 +40,9 @@ pub struct VerificationKey {
     pub ic_length: u8,
     pub ic: Vec<[u8; 64]>,
 
+    /// Slot the key was revoked at (0 = active)
+    pub revoked_slot: u64,
+
     /// Bump seed
     pub bump: u8,
 }
@@ -34,14 +52,35 @@ impl VerificationKey {
         8 +         // discriminator
         32 +        // pool
         32 +        // circuit_id
+        4 +         // version
         64 +        // alpha_g1
         128 +       // beta_g2
         128 +       // gamma_g2
         128 +       // delta_g2
         1 +         // ic_length
         4 + (ic_count * 64) +  // ic vector
+        8 +         // revoked_slot
         1 // bump
     }
+
+    pub fn is_revoked(&self) -> bool {
+        self.revoked_slot != 0
+    }
+
+    /// keccak256 of the key's curve points (alpha, beta, gamma, delta, IC)
+    ///
+    /// Unlike the hash a pool stages, this leaves out the pool, circuit ID and
+    /// version, so it can be committed to before the circuit ID exists.
+    pub fn commitment(&self) -> [u8; 32] {
+        let mut points: Vec<&[u8]> = vec![
+            &self.alpha_g1,
+            &self.beta_g2,
+            &self.gamma_g2,
+            &self.delta_g2,
+        ];
+        points.extend(self.ic.iter().map(|point| point.as_slice()));
+        keccak::hashv(&points).to_bytes()
+    }
 }
 
 /// Verification key data for initialization
@@ -53,3 +92,156 @@ pub struct VerificationKeyData {
     pub delta_g2: [u8; 128],
     pub ic: Vec<[u8; 64]>,
 }
+
+impl VerificationKeyData {
+    /// Reject IC vectors that are empty or exceed the verifier's input limit
+    pub fn validate_ic_count(ic_count: usize) -> Result<()> {
+        require!(
+            (1..=MAX_IC_POINTS).contains(&ic_count),
+            VerifierError::InvalidVerificationKey
+        );
+        Ok(())
+    }
+}
+
+/// Outcome of a successful `verify`, set as the instruction's return data
+///
+/// CPI callers read it with `Return::get()` to learn which circuit verified
+/// and over which inputs, without re-deriving either.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct VerificationResult {
+    /// Circuit of the verification key used
+    pub circuit_id: [u8; 32],
+
+    /// keccak256 of the public inputs, concatenated in order
+    pub public_inputs_hash: [u8; 32],
+
+    /// Compute units the verification consumed (0 off-chain)
+    pub compute_units: u64,
+}
+
+impl VerificationResult {
+    /// Result for `circuit_id` over `public_inputs`, given the remaining compute
+    /// units read before verification started
+    pub fn new(circuit_id: [u8; 32], public_inputs: &[[u8; 32]], start_units: u64) -> Self {
+        VerificationResult {
+            circuit_id,
+            public_inputs_hash: Self::hash_public_inputs(public_inputs),
+            compute_units: start_units.saturating_sub(sol_remaining_compute_units()),
+        }
+    }
+
+    pub fn hash_public_inputs(public_inputs: &[[u8; 32]]) -> [u8; 32] {
+        let inputs: Vec<&[u8]> = public_inputs.iter().map(|input| input.as_slice()).collect();
+        keccak::hashv(&inputs).to_bytes()
+    }
+}
+
+/// Outcome of a successful `verify_batch`, set as the instruction's return data
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct BatchVerificationResult {
+    /// Circuit of the verification key used
+    pub circuit_id: [u8; 32],
+
+    /// keccak256 of each proof's public inputs, in proof order
+    pub public_inputs_hashes: Vec<[u8; 32]>,
+
+    /// Compute units the whole batch consumed (0 off-chain)
+    pub compute_units: u64,
+}
+
+/// Seed for the PreparedVerificationKey PDA: [PREPARED_VK_SEED, verification_key]
+pub const PREPARED_VK_SEED: &[u8] = b"prepared_vk";
+
+/// Verify-ready copy of a VerificationKey, kept in sync by store_vk,
+/// update_vk, append_vk_ic and revoke_vk
+///
+/// Zero-copy so `verify_prepared` reads the points in place instead of
+/// Borsh-deserializing the key and cloning its IC vector. IC points are stored
+/// already padded to the verifier's dispatch size (the zero entries are the
+/// point at infinity), so inputs above 16 need no IC copy either.
+///
+/// e(α, β) is not cached: the alt_bn128 pairing syscall only reports whether
+/// a product of pairings is one and cannot take a precomputed GT element.
+///
+/// No negated points are precomputed either. The only negated term in
+/// e(-A, B) · e(α, β) · e(vk_x, γ) · e(C, δ) = 1 is the proof's A, which the
+/// prover submits already negated; α, β, γ and δ enter the pairing as stored,
+/// so there is no per-verify negation on the key side to save.
+#[account(zero_copy)]
+pub struct PreparedVerificationKey {
+    /// VerificationKey this was prepared from
+    pub verification_key: Pubkey,
+
+    /// Circuit of the source key (reported in VerificationResult)
+    pub circuit_id: [u8; 32],
+
+    pub alpha_g1: [u8; 64],
+    pub beta_g2: [u8; 128],
+    pub gamma_g2: [u8; 128],
+    pub delta_g2: [u8; 128],
+
+    /// IC points, zero beyond `padded_inputs + 1`
+    pub ic: [[u8; 64]; MAX_IC_POINTS],
+
+    /// Public inputs expected by the circuit
+    pub nr_public_inputs: u32,
+
+    /// Input count the verifier dispatches on (`nr_public_inputs` rounded up)
+    pub padded_inputs: u32,
+
+    pub bump: u8,
+
+    /// Nonzero once the source key is revoked
+    pub revoked: u8,
+
+    pub _padding: [u8; 2],
+}
+
+impl PreparedVerificationKey {
+    pub const SIZE: usize = 8 + std::mem::size_of::<PreparedVerificationKey>();
+
+    /// Rebuild from the current contents of `vk`
+    pub fn prepare(&mut self, vk_key: Pubkey, vk: &VerificationKey) {
+        let nr_public_inputs = vk.ic.len().saturating_sub(1);
+
+        self.verification_key = vk_key;
+        self.circuit_id = vk.circuit_id;
+        self.alpha_g1 = vk.alpha_g1;
+        self.beta_g2 = vk.beta_g2;
+        self.gamma_g2 = vk.gamma_g2;
+        self.delta_g2 = vk.delta_g2;
+        // Element-wise to stay within the 4KB stack frame
+        for (i, point) in self.ic.iter_mut().enumerate() {
+            *point = vk.ic.get(i).copied().unwrap_or([0u8; 64]);
+        }
+        self.nr_public_inputs = nr_public_inputs as u32;
+        self.padded_inputs = crate::groth16::padded_input_count(nr_public_inputs) as u32;
+        self.revoked = vk.is_revoked() as u8;
+    }
+}
+
+/// Seed for PublicInputBuffer PDAs: [INPUT_BUFFER_SEED, authority, buffer_id (LE)]
+pub const INPUT_BUFFER_SEED: &[u8] = b"input_buffer";
+
+/// Public inputs staged over several transactions for `verify_buffered`
+///
+/// A verify transaction carries its inputs inline, and only
+/// MAX_DIRECT_PUBLIC_INPUTS fit next to the proof. Keys with more inputs (up
+/// to MAX_PUBLIC_INPUTS) are verified against a buffer filled in chunks by
+/// `write_input_buffer`.
+#[account]
+#[derive(InitSpace)]
+pub struct PublicInputBuffer {
+    /// Only signer allowed to write or close the buffer
+    pub authority: Pubkey,
+
+    /// Caller-chosen ID, so one authority can hold several buffers
+    pub buffer_id: u64,
+
+    /// Inputs written so far, in order
+    #[max_len(MAX_PUBLIC_INPUTS)]
+    pub inputs: Vec<[u8; 32]>,
+
+    pub bump: u8,
+}
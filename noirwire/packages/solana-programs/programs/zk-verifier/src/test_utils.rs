@@ -0,0 +1,82 @@
+//! Synthetic Groth16 vectors for unit tests
+
+use crate::groth16::{Groth16Proof, Groth16VerifyingKey};
+use ark_bn254::{Fq, Fr, G1Affine, G1Projective, G2Affine};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{BigInteger, PrimeField};
+
+fn fq_bytes(x: &Fq) -> [u8; 32] {
+    x.into_bigint().to_bytes_be().try_into().unwrap()
+}
+
+fn g1_bytes(p: &G1Affine) -> [u8; 64] {
+    [fq_bytes(&p.x), fq_bytes(&p.y)]
+        .concat()
+        .try_into()
+        .unwrap()
+}
+
+/// EIP-197 encoding: imaginary part first
+fn g2_bytes(p: &G2Affine) -> [u8; 128] {
+    [
+        fq_bytes(&p.x.c1),
+        fq_bytes(&p.x.c0),
+        fq_bytes(&p.y.c1),
+        fq_bytes(&p.y.c0),
+    ]
+    .concat()
+    .try_into()
+    .unwrap()
+}
+
+/// Degenerate VK whose G2 points are all the generator, plus a proof for it
+pub fn synthetic_proof(n: usize) -> (Groth16VerifyingKey, Groth16Proof, Vec<[u8; 32]>) {
+    synthetic_proof_seeded(n, 0)
+}
+
+/// Like `synthetic_proof`, with the proof and inputs varied by `seed` (the VK
+/// depends only on `n`)
+///
+/// The pairing product collapses to e(A + α + vk_x + C, G2), so
+/// C = -(A + α + vk_x) satisfies it (A is passed already negated).
+pub fn synthetic_proof_seeded(
+    n: usize,
+    seed: u64,
+) -> (Groth16VerifyingKey, Groth16Proof, Vec<[u8; 32]>) {
+    let g1 = G1Affine::generator();
+    let ic: Vec<G1Affine> = (0..=n)
+        .map(|i| (g1 * Fr::from(i as u64 + 2)).into_affine())
+        .collect();
+    let inputs: Vec<Fr> = (0..n)
+        .map(|i| Fr::from(1_000 * (seed + 1) + i as u64))
+        .collect();
+
+    let mut vk_x = G1Projective::from(ic[0]);
+    for (point, input) in ic[1..].iter().zip(inputs.iter()) {
+        vk_x += *point * input;
+    }
+    let a = g1 * Fr::from(7 + seed);
+    let alpha = g1 * Fr::from(11u64);
+    let c = -(a + alpha + vk_x);
+
+    let g2 = g2_bytes(&G2Affine::generator());
+    let vk = Groth16VerifyingKey {
+        nr_public_inputs: n as u32,
+        alpha_g1: g1_bytes(&alpha.into_affine()),
+        beta_g2: g2,
+        gamma_g2: g2,
+        delta_g2: g2,
+        ic: ic.iter().map(g1_bytes).collect(),
+    };
+    let proof = Groth16Proof {
+        a: g1_bytes(&a.into_affine()),
+        b: g2,
+        c: g1_bytes(&c.into_affine()),
+    };
+    let inputs = inputs
+        .iter()
+        .map(|x| x.into_bigint().to_bytes_be().try_into().unwrap())
+        .collect();
+
+    (vk, proof, inputs)
+}
@@ -0,0 +1,370 @@
+//! Compute-unit comparison of `verify` and `verify_prepared`, the results
+//! every verify instruction returns, and `verify_buffered` at MAX_PUBLIC_INPUTS
+//!
+//! Runs against the SBF build: `cargo test-sbf -p zk-verifier -- --nocapture`
+
+#![cfg(feature = "test-sbf")]
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::{hash, instruction::Instruction};
+use anchor_lang::system_program;
+use anchor_lang::{AnchorDeserialize, Discriminator, InstructionData, ToAccountMetas};
+use ark_bn254::{Fq, Fr, G1Affine, G1Projective, G2Affine};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{BigInteger, PrimeField};
+use solana_program_test::{BanksClient, ProgramTest};
+use solana_sdk::{
+    account::Account, compute_budget::ComputeBudgetInstruction, hash::Hash, signature::Keypair,
+    signer::Signer, transaction::Transaction,
+};
+use zk_verifier::admin::SHIELDED_POOL_PROGRAM_ID;
+use zk_verifier::groth16::{Groth16Proof, MAX_DIRECT_PUBLIC_INPUTS, MAX_PUBLIC_INPUTS};
+use zk_verifier::registry::{compute_circuit_id, CIRCUIT_REGISTRY_SEED};
+use zk_verifier::state::{
+    BatchVerificationResult, PreparedVerificationKey, VerificationKey, VerificationKeyData,
+    VerificationResult, INPUT_BUFFER_SEED, PREPARED_VK_SEED,
+};
+
+fn fq_bytes(x: &Fq) -> [u8; 32] {
+    x.into_bigint().to_bytes_be().try_into().unwrap()
+}
+
+fn g1_bytes(p: &G1Affine) -> [u8; 64] {
+    [fq_bytes(&p.x), fq_bytes(&p.y)]
+        .concat()
+        .try_into()
+        .unwrap()
+}
+
+fn g2_bytes(p: &G2Affine) -> [u8; 128] {
+    [
+        fq_bytes(&p.x.c1),
+        fq_bytes(&p.x.c0),
+        fq_bytes(&p.y.c1),
+        fq_bytes(&p.y.c0),
+    ]
+    .concat()
+    .try_into()
+    .unwrap()
+}
+
+/// Degenerate VK (all G2 points the generator) and a proof satisfying it
+/// (same construction as the groth16 unit tests)
+fn synthetic_proof(n: usize) -> (VerificationKeyData, Groth16Proof, Vec<[u8; 32]>) {
+    let g1 = G1Affine::generator();
+    let ic: Vec<G1Affine> = (0..=n)
+        .map(|i| (g1 * Fr::from(i as u64 + 2)).into_affine())
+        .collect();
+    let inputs: Vec<Fr> = (0..n).map(|i| Fr::from(1_000 + i as u64)).collect();
+
+    let mut vk_x = G1Projective::from(ic[0]);
+    for (point, input) in ic[1..].iter().zip(inputs.iter()) {
+        vk_x += *point * input;
+    }
+    let a = g1 * Fr::from(7u64);
+    let alpha = g1 * Fr::from(11u64);
+    let c = -(a + alpha + vk_x);
+
+    let g2 = g2_bytes(&G2Affine::generator());
+    let vk = VerificationKeyData {
+        alpha_g1: g1_bytes(&alpha.into_affine()),
+        beta_g2: g2,
+        gamma_g2: g2,
+        delta_g2: g2,
+        ic: ic.iter().map(g1_bytes).collect(),
+    };
+    let proof = Groth16Proof {
+        a: g1_bytes(&a.into_affine()),
+        b: g2,
+        c: g1_bytes(&c.into_affine()),
+    };
+    let inputs = inputs
+        .iter()
+        .map(|x| x.into_bigint().to_bytes_be().try_into().unwrap())
+        .collect();
+
+    (vk, proof, inputs)
+}
+
+/// Minimal PoolState: discriminator, version, authority (all the verifier reads)
+fn pool_account(authority: &Pubkey) -> Account {
+    let mut data = vec![0u8; 41];
+    data[..8].copy_from_slice(&hash::hash(b"account:PoolState").to_bytes()[..8]);
+    data[9..41].copy_from_slice(authority.as_ref());
+    Account {
+        lamports: 1_000_000_000,
+        data,
+        owner: SHIELDED_POOL_PROGRAM_ID,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// Simulate `ix`, returning the units consumed and its return data
+async fn simulate(
+    banks_client: &mut BanksClient,
+    payer: &Keypair,
+    blockhash: Hash,
+    ix: Instruction,
+) -> (u64, Vec<u8>) {
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            ComputeBudgetInstruction::set_compute_unit_limit(1_400_000),
+            ix,
+        ],
+        Some(&payer.pubkey()),
+        &[payer],
+        blockhash,
+    );
+    let simulation = banks_client.simulate_transaction(tx).await.unwrap();
+    assert!(
+        matches!(simulation.result, Some(Ok(()))),
+        "{:?}",
+        simulation.result
+    );
+    let details = simulation.simulation_details.unwrap();
+    let return_data = details.return_data.map(|r| r.data).unwrap_or_default();
+    (details.units_consumed, return_data)
+}
+
+#[tokio::test]
+async fn verify_prepared_uses_fewer_compute_units() {
+    let pool = Pubkey::new_unique();
+    let authority = Keypair::new();
+    let mut program_test = ProgramTest::new("zk_verifier", zk_verifier::ID, None);
+    program_test.add_account(pool, pool_account(&authority.pubkey()));
+    let (mut banks_client, payer, blockhash) = program_test.start().await;
+
+    let (circuit_registry, _) =
+        Pubkey::find_program_address(&[CIRCUIT_REGISTRY_SEED, pool.as_ref()], &zk_verifier::ID);
+    let mut setup = vec![Instruction {
+        program_id: zk_verifier::ID,
+        accounts: zk_verifier::accounts::InitCircuitRegistry {
+            circuit_registry,
+            pool,
+            authority: authority.pubkey(),
+            payer: payer.pubkey(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: zk_verifier::instruction::InitCircuitRegistry {}.data(),
+    }];
+    for name in ["bench_4", "bench_6"] {
+        setup.push(Instruction {
+            program_id: zk_verifier::ID,
+            accounts: zk_verifier::accounts::RegisterCircuit {
+                circuit_registry,
+                pool,
+                authority: authority.pubkey(),
+            }
+            .to_account_metas(None),
+            data: zk_verifier::instruction::RegisterCircuit {
+                name: name.to_string(),
+                version: "v1".to_string(),
+                vk_commitment: None,
+            }
+            .data(),
+        });
+    }
+    let tx = Transaction::new_signed_with_payer(
+        &setup,
+        Some(&payer.pubkey()),
+        &[&payer, &authority],
+        blockhash,
+    );
+    banks_client.process_transaction(tx).await.unwrap();
+
+    for (n, name) in [(4usize, "bench_4"), (6, "bench_6")] {
+        let circuit_id = compute_circuit_id(name, "v1", None);
+        let (vk_data, proof, inputs) = synthetic_proof(n);
+
+        let (verification_key, _) = Pubkey::find_program_address(
+            &[b"vk", pool.as_ref(), &circuit_id, &1u32.to_le_bytes()],
+            &zk_verifier::ID,
+        );
+        let (prepared_vk, _) = Pubkey::find_program_address(
+            &[PREPARED_VK_SEED, verification_key.as_ref()],
+            &zk_verifier::ID,
+        );
+
+        let store_vk = Instruction {
+            program_id: zk_verifier::ID,
+            accounts: zk_verifier::accounts::StoreVk {
+                verification_key,
+                prepared_vk,
+                circuit_registry,
+                pool,
+                authority: authority.pubkey(),
+                payer: payer.pubkey(),
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: zk_verifier::instruction::StoreVk {
+                circuit_id,
+                version: 1,
+                vk_data,
+            }
+            .data(),
+        };
+        let tx = Transaction::new_signed_with_payer(
+            &[store_vk],
+            Some(&payer.pubkey()),
+            &[&payer, &authority],
+            blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+
+        let inputs_for_hash = inputs.clone();
+        let verify = Instruction {
+            program_id: zk_verifier::ID,
+            accounts: zk_verifier::accounts::VerifyProof { verification_key }
+                .to_account_metas(None),
+            data: zk_verifier::instruction::Verify {
+                proof: proof.clone(),
+                public_inputs: inputs.clone(),
+            }
+            .data(),
+        };
+        let verify_prepared = Instruction {
+            program_id: zk_verifier::ID,
+            accounts: zk_verifier::accounts::VerifyPrepared { prepared_vk }.to_account_metas(None),
+            data: zk_verifier::instruction::VerifyPrepared {
+                proof: proof.clone(),
+                public_inputs: inputs.clone(),
+            }
+            .data(),
+        };
+        let verify_batch = Instruction {
+            program_id: zk_verifier::ID,
+            accounts: zk_verifier::accounts::VerifyProof { verification_key }
+                .to_account_metas(None),
+            data: zk_verifier::instruction::VerifyBatch {
+                proofs: vec![proof.clone(), proof],
+                public_inputs: vec![inputs.clone(), inputs],
+            }
+            .data(),
+        };
+
+        let (plain, return_data) = simulate(&mut banks_client, &payer, blockhash, verify).await;
+        let (prepared, prepared_return_data) =
+            simulate(&mut banks_client, &payer, blockhash, verify_prepared).await;
+        println!("{n} inputs: verify {plain} CU, verify_prepared {prepared} CU");
+        assert!(prepared < plain);
+
+        let inputs_hash = VerificationResult::hash_public_inputs(&inputs_for_hash);
+        for (units, data) in [(plain, return_data), (prepared, prepared_return_data)] {
+            let result = VerificationResult::try_from_slice(&data).unwrap();
+            assert_eq!(result.circuit_id, circuit_id);
+            assert_eq!(result.public_inputs_hash, inputs_hash);
+            assert!(result.compute_units > 0 && result.compute_units < units);
+        }
+
+        let (batch, batch_return_data) =
+            simulate(&mut banks_client, &payer, blockhash, verify_batch).await;
+        let result = BatchVerificationResult::try_from_slice(&batch_return_data).unwrap();
+        assert_eq!(result.circuit_id, circuit_id);
+        assert_eq!(result.public_inputs_hashes, vec![inputs_hash; 2]);
+        assert!(result.compute_units > 0 && result.compute_units < batch);
+    }
+}
+
+/// PreparedVerificationKey account for `vk_data`, added directly: a key with
+/// MAX_PUBLIC_INPUTS inputs takes several append_vk_ic transactions to store
+fn prepared_vk_account(circuit_id: [u8; 32], vk_data: VerificationKeyData) -> Account {
+    let vk = VerificationKey {
+        pool: Pubkey::new_unique(),
+        circuit_id,
+        version: 1,
+        alpha_g1: vk_data.alpha_g1,
+        beta_g2: vk_data.beta_g2,
+        gamma_g2: vk_data.gamma_g2,
+        delta_g2: vk_data.delta_g2,
+        ic_length: vk_data.ic.len() as u8,
+        ic: vk_data.ic,
+        revoked_slot: 0,
+        bump: 0,
+    };
+    let mut prepared: Box<PreparedVerificationKey> = Box::new(bytemuck::Zeroable::zeroed());
+    prepared.prepare(Pubkey::new_unique(), &vk);
+
+    let mut data = PreparedVerificationKey::DISCRIMINATOR.to_vec();
+    data.extend_from_slice(bytemuck::bytes_of(&*prepared));
+    Account {
+        lamports: 1_000_000_000,
+        data,
+        owner: zk_verifier::ID,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+#[tokio::test]
+async fn verify_buffered_at_max_public_inputs() {
+    let circuit_id = compute_circuit_id("bench_max", "v1", None);
+    let (vk_data, proof, inputs) = synthetic_proof(MAX_PUBLIC_INPUTS);
+    let prepared_vk = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new("zk_verifier", zk_verifier::ID, None);
+    program_test.add_account(prepared_vk, prepared_vk_account(circuit_id, vk_data));
+    let (mut banks_client, payer, blockhash) = program_test.start().await;
+
+    let buffer_id = 7u64;
+    let (input_buffer, _) = Pubkey::find_program_address(
+        &[
+            INPUT_BUFFER_SEED,
+            payer.pubkey().as_ref(),
+            &buffer_id.to_le_bytes(),
+        ],
+        &zk_verifier::ID,
+    );
+    let mut setup = vec![Instruction {
+        program_id: zk_verifier::ID,
+        accounts: zk_verifier::accounts::InitInputBuffer {
+            input_buffer,
+            authority: payer.pubkey(),
+            payer: payer.pubkey(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: zk_verifier::instruction::InitInputBuffer { buffer_id }.data(),
+    }];
+    // One chunk per transaction, the most a verify transaction could carry
+    for chunk in inputs.chunks(MAX_DIRECT_PUBLIC_INPUTS) {
+        setup.push(Instruction {
+            program_id: zk_verifier::ID,
+            accounts: zk_verifier::accounts::WriteInputBuffer {
+                input_buffer,
+                authority: payer.pubkey(),
+            }
+            .to_account_metas(None),
+            data: zk_verifier::instruction::WriteInputBuffer {
+                inputs: chunk.to_vec(),
+            }
+            .data(),
+        });
+    }
+    for ix in setup {
+        let tx =
+            Transaction::new_signed_with_payer(&[ix], Some(&payer.pubkey()), &[&payer], blockhash);
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+
+    let verify_buffered = Instruction {
+        program_id: zk_verifier::ID,
+        accounts: zk_verifier::accounts::VerifyBuffered {
+            prepared_vk,
+            input_buffer,
+        }
+        .to_account_metas(None),
+        data: zk_verifier::instruction::VerifyBuffered { proof }.data(),
+    };
+    let (units, return_data) =
+        simulate(&mut banks_client, &payer, blockhash, verify_buffered).await;
+    println!("{MAX_PUBLIC_INPUTS} inputs: verify_buffered {units} CU");
+
+    let result = VerificationResult::try_from_slice(&return_data).unwrap();
+    assert_eq!(result.circuit_id, circuit_id);
+    assert_eq!(
+        result.public_inputs_hash,
+        VerificationResult::hash_public_inputs(&inputs)
+    );
+}
@@ -38,6 +38,9 @@ const CIRCUIT_IDS = {
   ]),
 };
 
+// Version of the keys being deployed (part of the VK PDA seeds; bump on rotation)
+const VK_VERSION = 1;
+
 // Path to compiled circuit artifacts
 const CIRCUITS_PATH = path.join(__dirname, "../../noir-circuits/circuits/target");
 
@@ -150,8 +153,10 @@ async function findVkPDA(
   poolPubkey: PublicKey,
   circuitId: Buffer,
 ): Promise<[PublicKey, number]> {
+  const versionSeed = Buffer.alloc(4);
+  versionSeed.writeUInt32LE(VK_VERSION);
   return PublicKey.findProgramAddressSync(
-    [Buffer.from("verification_key"), poolPubkey.toBuffer(), circuitId],
+    [Buffer.from("vk"), poolPubkey.toBuffer(), circuitId, versionSeed],
     zkVerifierProgram.programId,
   );
 }
@@ -176,7 +181,7 @@ async function storeVk(
   const ic = vkData.ic.map((point) => Array.from(point));
 
   const tx = await zkVerifierProgram.methods
-    .storeVk(Array.from(circuitId), {
+    .storeVk(Array.from(circuitId), VK_VERSION, {
       alpha_g1: Array.from(alpha_g1),
       beta_g2: Array.from(beta_g2),
       gamma_g2: Array.from(gamma_g2),
@@ -185,6 +190,7 @@ async function storeVk(
     })
     .accounts({
       authority,
+      payer: authority,
       pool: poolPubkey,
       verificationKey: vkPDA,
       systemProgram: SystemProgram.programId,
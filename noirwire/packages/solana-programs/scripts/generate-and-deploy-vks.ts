@@ -23,27 +23,29 @@ import { BarretenbergBackend } from "@noir-lang/backend_barretenberg";
 import * as fs from "fs";
 import * as path from "path";
 import { fileURLToPath } from "url";
+import { keccak_256 } from "js-sha3";
 
 // ES module compatibility
 const __filename = fileURLToPath(import.meta.url);
 const __dirname = path.dirname(__filename);
 
-// Circuit IDs from proof.rs (keccak256 of "noirwire.{circuit}.v2")
+// Circuit version registered in the pool's CircuitRegistry
+const CIRCUIT_VERSION = "v2";
+
+// Unbound circuit IDs, as derived by the registry: keccak256("noirwire.{circuit}.v2")
+function circuitId(name: string): number[] {
+  return Array.from(Buffer.from(keccak_256(`noirwire.${name}.${CIRCUIT_VERSION}`), "hex"));
+}
+
 const CIRCUIT_IDS = {
-  DEPOSIT: [
-    0x04, 0x8b, 0xf3, 0x55, 0x3a, 0xfe, 0x34, 0x84, 0x85, 0x27, 0x8e, 0x5e, 0x56, 0x78, 0x39, 0x12,
-    0x4a, 0x80, 0xf6, 0xa5, 0x71, 0xd2, 0xb7, 0xa6, 0x44, 0xd3, 0x23, 0xbf, 0x97, 0xb4, 0x76, 0x5a,
-  ],
-  WITHDRAW: [
-    0x07, 0x49, 0xf5, 0x64, 0xac, 0x73, 0x88, 0x92, 0x4c, 0x2c, 0xd3, 0x94, 0xd2, 0x08, 0x15, 0xe2,
-    0x0e, 0xab, 0xa7, 0xf3, 0x5a, 0xf3, 0x31, 0xe3, 0x11, 0x07, 0x8d, 0x81, 0xbb, 0x78, 0xbc, 0x4e,
-  ],
-  TRANSFER: [
-    0xbc, 0x88, 0x93, 0xac, 0x50, 0xd8, 0x33, 0x91, 0x3c, 0x65, 0x5c, 0xaf, 0x91, 0xb7, 0x74, 0xfc,
-    0x99, 0x5f, 0x70, 0x42, 0x45, 0x62, 0x2e, 0xc6, 0x3d, 0x9d, 0x04, 0x27, 0x55, 0x86, 0x3f, 0x7d,
-  ],
+  DEPOSIT: circuitId("deposit"),
+  WITHDRAW: circuitId("withdraw"),
+  TRANSFER: circuitId("transfer"),
 };
 
+// Version of the keys being deployed (part of the VK PDA seeds; bump on rotation)
+const VK_VERSION = 1;
+
 // Paths
 const CIRCUITS_PATH = path.join(__dirname, "../../noir-circuits/target");
 const VK_OUTPUT_DIR = path.join(__dirname, "../vks");
@@ -221,9 +223,12 @@ async function findVkPDA(
   zkVerifierProgram: Program,
   poolPubkey: PublicKey,
   circuitId: number[],
+  version: number = VK_VERSION,
 ): Promise<[PublicKey, number]> {
+  const versionSeed = Buffer.alloc(4);
+  versionSeed.writeUInt32LE(version);
   return PublicKey.findProgramAddressSync(
-    [Buffer.from("vk"), poolPubkey.toBuffer(), Buffer.from(circuitId)],
+    [Buffer.from("vk"), poolPubkey.toBuffer(), Buffer.from(circuitId), versionSeed],
     zkVerifierProgram.programId,
   );
 }
@@ -247,7 +252,7 @@ async function storeVk(
 
   // Convert to format expected by Anchor
   const tx = await zkVerifierProgram.methods
-    .storeVk(circuitId, {
+    .storeVk(circuitId, VK_VERSION, {
       alphaG1: vkData.alpha_g1,
       betaG2: vkData.beta_g2,
       gammaG2: vkData.gamma_g2,
@@ -256,6 +261,7 @@ async function storeVk(
     })
     .accounts({
       authority,
+      payer: authority,
       pool: poolPubkey,
       verificationKey: vkPDA,
       systemProgram: SystemProgram.programId,
@@ -341,6 +347,36 @@ async function deployVKs(vks?: Map<string, ParsedVK>): Promise<void> {
     { name: "transfer", id: CIRCUIT_IDS.TRANSFER },
   ];
 
+  // Keys can only be stored for circuits in the pool's registry
+  const [circuitRegistry] = PublicKey.findProgramAddressSync(
+    [Buffer.from("circuit_registry"), poolPDA.toBuffer()],
+    zkVerifierProgram.programId,
+  );
+  if (!(await provider.connection.getAccountInfo(circuitRegistry))) {
+    await zkVerifierProgram.methods
+      .initCircuitRegistry()
+      .accounts({
+        pool: poolPDA,
+        authority: provider.wallet.publicKey,
+        payer: provider.wallet.publicKey,
+      })
+      .rpc();
+    console.log(`📒 Circuit registry created: ${circuitRegistry.toBase58()}`);
+  }
+  const registry = await zkVerifierProgram.account.circuitRegistry.fetch(circuitRegistry);
+  for (const circuit of circuits) {
+    const registered = registry.circuits.some((entry: any) =>
+      Buffer.from(entry.circuitId).equals(Buffer.from(circuit.id)),
+    );
+    if (!registered) {
+      await zkVerifierProgram.methods
+        .registerCircuit(circuit.name, CIRCUIT_VERSION, null)
+        .accounts({ pool: poolPDA, authority: provider.wallet.publicKey })
+        .rpc();
+      console.log(`📒 Registered circuit: ${circuit.name}.${CIRCUIT_VERSION}`);
+    }
+  }
+
   for (const circuit of circuits) {
     console.log(`\n📤 Deploying ${circuit.name.toUpperCase()} VK`);
     console.log("─".repeat(70));
@@ -384,6 +420,23 @@ async function deployVKs(vks?: Map<string, ParsedVK>): Promise<void> {
       const [vkPDA] = await findVkPDA(zkVerifierProgram, poolPDA, Array.from(circuit.id));
       const vkAccount = await zkVerifierProgram.account.verificationKey.fetch(vkPDA);
       console.log(`  ✓ Verified on-chain: ${vkAccount.icLength} IC points`);
+
+      // Stage the key's hash on the pool (live immediately for a circuit's
+      // first key, after the activation delay for a rotation)
+      const vkInfo = await provider.connection.getAccountInfo(vkPDA);
+      const vkHash = Array.from(Buffer.from(keccak_256(vkInfo!.data.subarray(8)), "hex"));
+      const stageTx = await shieldedPoolProgram.methods
+        .updatePoolConfig({
+          stageVk: { circuitId: Array.from(circuit.id), vkHash, version: VK_VERSION },
+        })
+        .accounts({
+          pool: poolPDA,
+          poolConfig: null,
+          circuitRegistry,
+          authority: provider.wallet.publicKey,
+        })
+        .rpc();
+      console.log(`  🔑 VK hash staged: ${stageTx}`);
     } catch (error: any) {
       if (error.message?.includes("already in use")) {
         console.warn(`  ⚠️  VK already deployed (account exists)`);
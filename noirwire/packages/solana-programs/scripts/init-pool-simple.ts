@@ -10,8 +10,6 @@
 import * as anchor from "@coral-xyz/anchor";
 import { PublicKey, SystemProgram, LAMPORTS_PER_SOL } from "@solana/web3.js";
 import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
-import { createHash } from "crypto";
-import * as fs from "fs";
 import * as path from "path";
 import { fileURLToPath } from "url";
 
@@ -50,19 +48,8 @@ console.log(`💰 Token Mint: ${TOKEN_MINT.toBase58()}`);
 const PER_AUTHORITY = wallet.publicKey; // Using wallet as authority for now
 console.log(`🔐 PER Authority: ${PER_AUTHORITY.toBase58()}\n`);
 
-// Load verification key hash
-const depositVkPath = path.join(__dirname, "../../noir-circuits/deposit_vk.json");
-let vkHash: number[];
-
-if (fs.existsSync(depositVkPath)) {
-  const vkData = JSON.parse(fs.readFileSync(depositVkPath, "utf-8"));
-  vkHash = Array.from(createHash("sha256").update(JSON.stringify(vkData)).digest());
-  console.log(`🔑 VK Hash (from deposit_vk.json): ${Buffer.from(vkHash).toString("hex").slice(0, 16)}...`);
-} else {
-  // Use placeholder hash
-  vkHash = Array(32).fill(0);
-  console.log(`⚠️  Using placeholder VK hash (deposit_vk.json not found)`);
-}
+// Verification key hashes are staged per circuit once the VKs are stored
+// (see generate-and-deploy-vks.ts)
 
 // Derive PDAs
 const [poolPda, poolBump] = PublicKey.findProgramAddressSync(
@@ -100,7 +87,13 @@ if (poolAccount) {
 
   try {
     const initTx = await shieldedPoolProgram.methods
-      .initialize(TOKEN_MINT, vkHash, PER_AUTHORITY)
+      .initialize(
+        TOKEN_MINT,
+        PER_AUTHORITY,
+        false,
+        new anchor.BN(1_000),
+        new anchor.BN("18446744073709551615"),
+      )
       .accounts({
         pool: poolPda,
         tokenMint: TOKEN_MINT,
@@ -133,6 +126,7 @@ if (historicalRootsAccount) {
         pool: poolPda,
         historicalRoots: historicalRootsPda,
         authority: wallet.publicKey,
+        payer: wallet.publicKey,
         systemProgram: SystemProgram.programId,
       })
       .rpc();
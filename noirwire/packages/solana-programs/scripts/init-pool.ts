@@ -20,7 +20,6 @@ import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
 import * as fs from "fs";
 import * as path from "path";
 import { fileURLToPath } from "url";
-import { createHash } from "crypto";
 
 const __filename = fileURLToPath(import.meta.url);
 const __dirname = path.dirname(__filename);
@@ -87,12 +86,9 @@ async function main() {
       process.exit(1);
     }
 
-    const vkData = JSON.parse(fs.readFileSync(depositVkPath, "utf-8"));
-    console.log("✅ Verification key loaded");
-
-    // Calculate VK hash (using simplified approach for now)
-    const vkHash = Array.from(createHash("sha256").update(JSON.stringify(vkData)).digest());
-    console.log(`🔑 VK Hash: ${Buffer.from(vkHash).toString("hex").slice(0, 16)}...\n`);
+    console.log("✅ Verification key found");
+    // Its hash is staged per circuit once the VK is stored
+    // (see generate-and-deploy-vks.ts)
 
     // Step 2: Derive pool PDA
     console.log("📋 Step 2: Deriving pool PDA...");
@@ -121,7 +117,13 @@ async function main() {
       );
 
       const initTx = await program.methods
-        .initialize(TOKEN_MINT, vkHash, PER_AUTHORITY)
+        .initialize(
+          TOKEN_MINT,
+          PER_AUTHORITY,
+          false,
+          new anchor.BN(1_000),
+          new anchor.BN("18446744073709551615"),
+        )
         .accounts({
           pool: poolPda,
           tokenMint: TOKEN_MINT,
@@ -160,6 +162,7 @@ async function main() {
           pool: poolPda,
           historicalRoots: historicalRootsPda,
           authority: wallet.publicKey,
+          payer: wallet.publicKey,
           systemProgram: SystemProgram.programId,
         })
         .rpc();
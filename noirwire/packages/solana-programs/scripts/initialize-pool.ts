@@ -14,7 +14,6 @@ import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { PublicKey, Keypair, SystemProgram } from "@solana/web3.js";
 import { TOKEN_PROGRAM_ID, NATIVE_MINT } from "@solana/spl-token";
-import { keccak_256 } from "js-sha3";
 import * as fs from "fs";
 import * as path from "path";
 
@@ -33,21 +32,8 @@ async function main() {
   const tokenMint = NATIVE_MINT;
   console.log(`Token Mint: ${tokenMint.toBase58()} (Native SOL)`);
 
-  // Load verification key and compute hash
-  const vkPath = path.join(__dirname, "../../noir-circuits/deposit_vk.json");
-
-  let vkHash: number[];
-  if (fs.existsSync(vkPath)) {
-    const vkData = JSON.parse(fs.readFileSync(vkPath, "utf-8"));
-    const vkString = JSON.stringify(vkData);
-    const hashBytes = keccak_256(vkString);
-    vkHash = Array.from(Buffer.from(hashBytes, "hex"));
-    console.log(`VK Hash: 0x${hashBytes.substring(0, 16)}...`);
-  } else {
-    // Use placeholder for testing
-    vkHash = Array.from(Buffer.alloc(32, 42));
-    console.log("⚠️  Using placeholder VK hash (VK file not found)");
-  }
+  // Verification key hashes are staged per circuit once the VKs are stored
+  // (see generate-and-deploy-vks.ts)
 
   // PER authority - for now use a test keypair (replace with actual PER authority)
   const perAuthority = Keypair.generate().publicKey;
@@ -89,7 +75,13 @@ async function main() {
   console.log("⏳ Sending initialization transaction...");
   try {
     const tx = await program.methods
-      .initialize(tokenMint, vkHash, perAuthority)
+      .initialize(
+        tokenMint,
+        perAuthority,
+        false,
+        new anchor.BN(1_000),
+        new anchor.BN("18446744073709551615"),
+      )
       .accounts({
         pool: poolState,
         tokenMint: tokenMint,
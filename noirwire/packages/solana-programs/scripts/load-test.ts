@@ -359,9 +359,14 @@ async function main(): Promise<void> {
   );
 
   // Initialize pool
-  const VK_HASH = Array.from(Buffer.alloc(32, "vk_hash_test"));
   await program.methods
-    .initialize(tokenMint, VK_HASH, Keypair.generate().publicKey)
+    .initialize(
+      tokenMint,
+      Keypair.generate().publicKey,
+      false,
+      new anchor.BN(1_000),
+      new anchor.BN("18446744073709551615"),
+    )
     .accounts({
       pool: poolState,
       tokenMint: tokenMint,
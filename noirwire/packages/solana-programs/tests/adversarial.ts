@@ -11,6 +11,7 @@ import {
 } from "@solana/spl-token";
 import { Keypair, PublicKey, SystemProgram, LAMPORTS_PER_SOL } from "@solana/web3.js";
 import { expect } from "chai";
+import { keccak_256 } from "js-sha3";
 
 /**
  * ADVERSARIAL SECURITY TESTS
@@ -42,18 +43,9 @@ describe("Adversarial Security Tests", function () {
   let poolAuthority: PublicKey;
   let attackerTokenAccount: PublicKey;
 
-  const VK_HASH = Array.from(Buffer.alloc(32, "vk_hash_test"));
-
-  // Circuit IDs
-  const DEPOSIT_CIRCUIT_ID = Buffer.from([
-    0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-  ]);
-
-  const WITHDRAW_CIRCUIT_ID = Buffer.from([
-    0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-  ]);
+  // Unbound v2 circuit IDs: keccak256("noirwire.{name}.v2")
+  const DEPOSIT_CIRCUIT_ID = Buffer.from(keccak_256("noirwire.deposit.v2"), "hex");
+  const WITHDRAW_CIRCUIT_ID = Buffer.from(keccak_256("noirwire.withdraw.v2"), "hex");
 
   // Helper functions
   async function findPoolPDA(mint: PublicKey): Promise<PublicKey> {
@@ -88,9 +80,21 @@ describe("Adversarial Security Tests", function () {
     return pda;
   }
 
+  // Verification keys are versioned; these tests only deploy version 1
+  const VK_VERSION = 1;
+  const VK_VERSION_SEED = Buffer.from([1, 0, 0, 0]);
+
+  function findCircuitRegistryPDA(pool: PublicKey): PublicKey {
+    const [pda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("circuit_registry"), pool.toBuffer()],
+      verifierProgram.programId,
+    );
+    return pda;
+  }
+
   async function findVkPDA(pool: PublicKey, circuitId: Buffer): Promise<PublicKey> {
     const [pda] = PublicKey.findProgramAddressSync(
-      [Buffer.from("vk"), pool.toBuffer(), circuitId],
+      [Buffer.from("vk"), pool.toBuffer(), circuitId, VK_VERSION_SEED],
       verifierProgram.programId,
     );
     return pda;
@@ -191,7 +195,13 @@ describe("Adversarial Security Tests", function () {
 
     // Initialize pool
     await poolProgram.methods
-      .initialize(tokenMint, VK_HASH, Keypair.generate().publicKey)
+      .initialize(
+        tokenMint,
+        Keypair.generate().publicKey,
+        false,
+        new anchor.BN(1_000),
+        new anchor.BN("18446744073709551615"),
+      )
       .accounts({
         pool: poolState,
         tokenMint: tokenMint,
@@ -225,12 +235,32 @@ describe("Adversarial Security Tests", function () {
       ic: [Array.from(Buffer.alloc(64, 5)), Array.from(Buffer.alloc(64, 6))],
     };
 
+    // Register the circuits so their keys can be stored and staged
+    await verifierProgram.methods
+      .initCircuitRegistry()
+      .accountsPartial({
+        pool: poolState,
+        authority: admin.publicKey,
+        payer: admin.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([admin])
+      .rpc();
+    for (const name of ["deposit", "withdraw"]) {
+      await verifierProgram.methods
+        .registerCircuit(name, "v2", null)
+        .accountsPartial({ pool: poolState, authority: admin.publicKey })
+        .signers([admin])
+        .rpc();
+    }
+
     const depositVk = await findVkPDA(poolState, DEPOSIT_CIRCUIT_ID);
     await verifierProgram.methods
-      .storeVk(Array.from(DEPOSIT_CIRCUIT_ID), mockVkData)
+      .storeVk(Array.from(DEPOSIT_CIRCUIT_ID), VK_VERSION, mockVkData)
       .accountsPartial({
         pool: poolState,
         authority: admin.publicKey,
+        payer: admin.publicKey,
         systemProgram: SystemProgram.programId,
       })
       .signers([admin])
@@ -238,14 +268,38 @@ describe("Adversarial Security Tests", function () {
 
     const withdrawVk = await findVkPDA(poolState, WITHDRAW_CIRCUIT_ID);
     await verifierProgram.methods
-      .storeVk(Array.from(WITHDRAW_CIRCUIT_ID), mockVkData)
+      .storeVk(Array.from(WITHDRAW_CIRCUIT_ID), VK_VERSION, mockVkData)
       .accountsPartial({
         pool: poolState,
         authority: admin.publicKey,
+        payer: admin.publicKey,
         systemProgram: SystemProgram.programId,
       })
       .signers([admin])
       .rpc();
+
+    // Accept both keys on the pool (a circuit's first key is live immediately)
+    for (const [circuitId, vk] of [
+      [DEPOSIT_CIRCUIT_ID, depositVk],
+      [WITHDRAW_CIRCUIT_ID, withdrawVk],
+    ] as const) {
+      const vkAccount = await provider.connection.getAccountInfo(vk);
+      await poolProgram.methods
+        .updatePoolConfig({
+          stageVk: {
+            circuitId: Array.from(circuitId),
+            vkHash: Array.from(Buffer.from(keccak_256(vkAccount!.data.subarray(8)), "hex")),
+            version: VK_VERSION,
+          },
+        })
+        .accountsPartial({
+          pool: poolState,
+          circuitRegistry: findCircuitRegistryPDA(poolState),
+          authority: admin.publicKey,
+        })
+        .signers([admin])
+        .rpc();
+    }
   }
 
   // ===== DOUBLE SPEND PREVENTION =====
@@ -295,7 +349,7 @@ describe("Adversarial Security Tests", function () {
     it("ATTACK: should reject deposit below minimum (spam prevention)", async function () {
       this.timeout(30000);
 
-      // MIN_DEPOSIT_SPL_UNITS = 1000 (defined in pool_state.rs)
+      // min_deposit = 1000 (set at initialize, 0.001 tokens at 6 decimals)
       const belowMinimum = 999;
       const proofData = createMockDepositProof(belowMinimum);
       const depositVk = await findVkPDA(poolState, DEPOSIT_CIRCUIT_ID);
@@ -596,14 +650,16 @@ describe("Adversarial Security Tests", function () {
       console.log("✓ INVARIANT: Pool starts unpaused and not in emergency mode");
     });
 
-    it("INVARIANT: VK hash is correctly stored", async function () {
+    it("INVARIANT: No verification key is accepted before one is staged", async function () {
       this.timeout(30000);
 
       const pool = await poolProgram.account.poolState.fetch(poolState);
 
-      expect(Array.from(pool.vkHash)).to.deep.equal(VK_HASH);
+      for (const entry of pool.circuitVks) {
+        expect(Array.from(entry.vkHash)).to.deep.equal(Array(32).fill(0));
+      }
 
-      console.log("✓ INVARIANT: VK hash matches initialization value");
+      console.log("✓ INVARIANT: Pool starts with no accepted verification keys");
     });
   });
 });
@@ -103,6 +103,7 @@ describe("Batch Settlement Security", function () {
       newRoot: Buffer.from(newRoot),
       nullifiersRoot,
       nullifierCount,
+      withdrawalsHash: Buffer.alloc(32), // No public withdrawals in this batch
     };
   }
 
@@ -168,9 +169,14 @@ describe("Batch Settlement Security", function () {
     console.log("Pool State:", poolState.toBase58());
 
     // Initialize pool
-    const vkHash = Buffer.alloc(32, 0xaa); // Mock VK hash
     await shieldedPool.methods
-      .initialize(tokenMint, Array.from(vkHash), perAuthority.publicKey)
+      .initialize(
+        tokenMint,
+        perAuthority.publicKey,
+        false,
+        new anchor.BN(1_000),
+        new anchor.BN("18446744073709551615"),
+      )
       .accounts({
         pool: poolState,
         tokenMint: tokenMint,
@@ -209,7 +215,7 @@ describe("Batch Settlement Security", function () {
         // Note: This test will work once ZK verification is integrated
         // Currently may pass because verification is not fully enforced
         await shieldedPool.methods
-          .settleBatch(invalidProof)
+          .settleBatch(invalidProof, [])
           .accounts({
             pool: poolState,
             perAuthority: perAuthority.publicKey,
@@ -240,7 +246,7 @@ describe("Batch Settlement Security", function () {
 
       try {
         await shieldedPool.methods
-          .settleBatch(proof)
+          .settleBatch(proof, [])
           .accounts({
             pool: poolState,
             perAuthority: perAuthority.publicKey,
@@ -265,7 +271,7 @@ describe("Batch Settlement Security", function () {
       // This may succeed with mock proof if ZK verification is not enforced
       try {
         await shieldedPool.methods
-          .settleBatch(validProof)
+          .settleBatch(validProof, [])
           .accounts({
             pool: poolState,
             perAuthority: perAuthority.publicKey,
@@ -298,7 +304,7 @@ describe("Batch Settlement Security", function () {
 
       try {
         await shieldedPool.methods
-          .settleBatch(proof)
+          .settleBatch(proof, [])
           .accounts({
             pool: poolState,
             perAuthority: attacker.publicKey, // Wrong authority!
@@ -322,7 +328,7 @@ describe("Batch Settlement Security", function () {
 
       try {
         await shieldedPool.methods
-          .settleBatch(proof)
+          .settleBatch(proof, [])
           .accounts({
             pool: poolState,
             perAuthority: poolAuthority.publicKey, // Pool authority, not PER!
@@ -346,7 +352,7 @@ describe("Batch Settlement Security", function () {
 
       try {
         await shieldedPool.methods
-          .settleBatch(proof)
+          .settleBatch(proof, [])
           .accounts({
             pool: poolState,
             perAuthority: perAuthority.publicKey, // Correct PER authority
@@ -385,7 +391,7 @@ describe("Batch Settlement Security", function () {
 
       try {
         await shieldedPool.methods
-          .settleBatch(proof)
+          .settleBatch(proof, [])
           .accounts({
             pool: poolState,
             perAuthority: perAuthority.publicKey,
@@ -409,7 +415,7 @@ describe("Batch Settlement Security", function () {
 
       try {
         await shieldedPool.methods
-          .settleBatch(proof)
+          .settleBatch(proof, [])
           .accounts({
             pool: poolState,
             perAuthority: perAuthority.publicKey,
@@ -433,7 +439,7 @@ describe("Batch Settlement Security", function () {
 
       try {
         await shieldedPool.methods
-          .settleBatch(proof)
+          .settleBatch(proof, [])
           .accounts({
             pool: poolState,
             perAuthority: perAuthority.publicKey,
@@ -457,7 +463,7 @@ describe("Batch Settlement Security", function () {
 
       try {
         await shieldedPool.methods
-          .settleBatch(proof)
+          .settleBatch(proof, [])
           .accounts({
             pool: poolState,
             perAuthority: perAuthority.publicKey,
@@ -496,7 +502,7 @@ describe("Batch Settlement Security", function () {
 
       try {
         await shieldedPool.methods
-          .settleBatch(proof)
+          .settleBatch(proof, [])
           .accounts({
             pool: poolState,
             perAuthority: perAuthority.publicKey,
@@ -533,7 +539,7 @@ describe("Batch Settlement Security", function () {
 
         try {
           await shieldedPool.methods
-            .settleBatch(proof)
+            .settleBatch(proof, [])
             .accounts({
               pool: poolState,
               perAuthority: perAuthority.publicKey,
@@ -199,11 +199,16 @@ describe("E2E with Real ZK Proofs", function () {
     poolVault = findVaultPDA(poolState);
 
     // Initialize pool
-    const VK_HASH = Array.from(Buffer.alloc(32, "test_vk"));
     const PER_AUTHORITY = Keypair.generate().publicKey;
 
     await shieldedPool.methods
-      .initialize(tokenMint, VK_HASH, PER_AUTHORITY)
+      .initialize(
+        tokenMint,
+        PER_AUTHORITY,
+        false,
+        new anchor.BN(1_000),
+        new anchor.BN("18446744073709551615"),
+      )
       .accounts({
         pool: poolState,
         tokenMint: tokenMint,
@@ -443,6 +448,8 @@ describe("E2E with Real ZK Proofs", function () {
         nullifier,
         oldRoot,
         newRoot: oldRoot, // Simplified - in production would compute actual new root
+        relayer: 0n,
+        fee: 0n,
         owner: userOwner,
         balance: deposit.amount,
         vaultId: deposit.vaultId,
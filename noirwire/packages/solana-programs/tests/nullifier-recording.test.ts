@@ -200,9 +200,14 @@ describe("Nullifier Recording (Double-Spend Prevention)", function () {
     console.log("Pool State:", poolState.toBase58());
 
     // Initialize pool
-    const vkHash = Buffer.alloc(32, 0xaa);
     await shieldedPool.methods
-      .initialize(tokenMint, Array.from(vkHash), perAuthority.publicKey)
+      .initialize(
+        tokenMint,
+        perAuthority.publicKey,
+        false,
+        new anchor.BN(1_000),
+        new anchor.BN("18446744073709551615"),
+      )
       .accounts({
         pool: poolState,
         tokenMint: tokenMint,
@@ -251,11 +256,12 @@ describe("Nullifier Recording (Double-Spend Prevention)", function () {
       newRoot,
       nullifiersRoot,
       nullifierCount: batchNullifiers.length,
+      withdrawalsHash: Buffer.alloc(32),
     };
 
     try {
       await shieldedPool.methods
-        .settleBatch(batchProof)
+        .settleBatch(batchProof, [])
         .accounts({
           pool: poolState,
           perAuthority: perAuthority.publicKey,
@@ -12,6 +12,7 @@ import {
 } from "@solana/spl-token";
 import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
 import { expect } from "chai";
+import { keccak_256 } from "js-sha3";
 
 /**
  * COMPREHENSIVE SHIELDED POOL TESTS - Security Critical Tests
@@ -35,18 +36,9 @@ describe("Shielded Pool - Comprehensive Security Tests", function () {
   let poolAuthority: PublicKey;
   let userTokenAccount: PublicKey;
 
-  const VK_HASH = Array.from(Buffer.alloc(32, "vk_hash_test"));
-
-  // Circuit IDs (from state/proof.rs)
-  const DEPOSIT_CIRCUIT_ID = Buffer.from([
-    0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-  ]);
-
-  const WITHDRAW_CIRCUIT_ID = Buffer.from([
-    0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-  ]);
+  // Unbound v2 circuit IDs: keccak256("noirwire.{name}.v2")
+  const DEPOSIT_CIRCUIT_ID = Buffer.from(keccak_256("noirwire.deposit.v2"), "hex");
+  const WITHDRAW_CIRCUIT_ID = Buffer.from(keccak_256("noirwire.withdraw.v2"), "hex");
 
   // Helper functions
   function generateKeypair(): Keypair {
@@ -85,9 +77,21 @@ describe("Shielded Pool - Comprehensive Security Tests", function () {
     return pda;
   }
 
+  // Verification keys are versioned; these tests only deploy version 1
+  const VK_VERSION = 1;
+  const VK_VERSION_SEED = Buffer.from([1, 0, 0, 0]);
+
+  function findCircuitRegistryPDA(pool: PublicKey): PublicKey {
+    const [pda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("circuit_registry"), pool.toBuffer()],
+      verifierProgram.programId,
+    );
+    return pda;
+  }
+
   async function findVkPDA(pool: PublicKey, circuitId: Buffer): Promise<PublicKey> {
     const [pda] = PublicKey.findProgramAddressSync(
-      [Buffer.from("vk"), pool.toBuffer(), circuitId],
+      [Buffer.from("vk"), pool.toBuffer(), circuitId, VK_VERSION_SEED],
       verifierProgram.programId,
     );
     return pda;
@@ -157,7 +161,13 @@ describe("Shielded Pool - Comprehensive Security Tests", function () {
 
     // Initialize pool
     await poolProgram.methods
-      .initialize(tokenMint, VK_HASH, Keypair.generate().publicKey)
+      .initialize(
+        tokenMint,
+        Keypair.generate().publicKey,
+        false,
+        new anchor.BN(1_000),
+        new anchor.BN("18446744073709551615"),
+      )
       .accounts({
         pool: poolState,
         tokenMint: tokenMint,
@@ -192,12 +202,32 @@ describe("Shielded Pool - Comprehensive Security Tests", function () {
       ic: [Array.from(Buffer.alloc(64, 5)), Array.from(Buffer.alloc(64, 6))],
     };
 
+    // Register the circuits so their keys can be stored and staged
+    await verifierProgram.methods
+      .initCircuitRegistry()
+      .accountsPartial({
+        pool: poolState,
+        authority: admin.publicKey,
+        payer: admin.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([admin])
+      .rpc();
+    for (const name of ["deposit", "withdraw"]) {
+      await verifierProgram.methods
+        .registerCircuit(name, "v2", null)
+        .accountsPartial({ pool: poolState, authority: admin.publicKey })
+        .signers([admin])
+        .rpc();
+    }
+
     const depositVk = await findVkPDA(poolState, DEPOSIT_CIRCUIT_ID);
     await verifierProgram.methods
-      .storeVk(Array.from(DEPOSIT_CIRCUIT_ID), mockVkData)
+      .storeVk(Array.from(DEPOSIT_CIRCUIT_ID), VK_VERSION, mockVkData)
       .accountsPartial({
         pool: poolState,
         authority: admin.publicKey,
+        payer: admin.publicKey,
         systemProgram: SystemProgram.programId,
       })
       .signers([admin])
@@ -205,14 +235,38 @@ describe("Shielded Pool - Comprehensive Security Tests", function () {
 
     const withdrawVk = await findVkPDA(poolState, WITHDRAW_CIRCUIT_ID);
     await verifierProgram.methods
-      .storeVk(Array.from(WITHDRAW_CIRCUIT_ID), mockVkData)
+      .storeVk(Array.from(WITHDRAW_CIRCUIT_ID), VK_VERSION, mockVkData)
       .accountsPartial({
         pool: poolState,
         authority: admin.publicKey,
+        payer: admin.publicKey,
         systemProgram: SystemProgram.programId,
       })
       .signers([admin])
       .rpc();
+
+    // Accept both keys on the pool (a circuit's first key is live immediately)
+    for (const [circuitId, vk] of [
+      [DEPOSIT_CIRCUIT_ID, depositVk],
+      [WITHDRAW_CIRCUIT_ID, withdrawVk],
+    ] as const) {
+      const vkAccount = await provider.connection.getAccountInfo(vk);
+      await poolProgram.methods
+        .updatePoolConfig({
+          stageVk: {
+            circuitId: Array.from(circuitId),
+            vkHash: Array.from(Buffer.from(keccak_256(vkAccount!.data.subarray(8)), "hex")),
+            version: VK_VERSION,
+          },
+        })
+        .accountsPartial({
+          pool: poolState,
+          circuitRegistry: findCircuitRegistryPDA(poolState),
+          authority: admin.publicKey,
+        })
+        .signers([admin])
+        .rpc();
+    }
   }
 
   // ===== WEEK 1, DAY 3: DEPOSIT FLOW TESTS =====
@@ -338,6 +392,48 @@ describe("Shielded Pool - Comprehensive Security Tests", function () {
       }
     });
 
+    it("SECURITY: should reject recipient token account not owned by the recipient", async function () {
+      this.timeout(30000);
+
+      // Token account of the right mint, but owned by someone other than the
+      // proof-bound recipient
+      const otherATA = await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        admin, // payer
+        tokenMint,
+        admin.publicKey, // owner
+      );
+
+      const amount = 50_000_000;
+      const proofData = createMockWithdrawProof(amount, user.publicKey);
+      const withdrawVk = await findVkPDA(poolState, WITHDRAW_CIRCUIT_ID);
+      const nullifierPda = await findNullifierPDA(poolState, Buffer.from(proofData.nullifier));
+
+      try {
+        await poolProgram.methods
+          .withdraw(proofData, user.publicKey)
+          .accounts({
+            pool: poolState,
+            poolVault: poolVault,
+            recipientTokenAccount: otherATA.address,
+            nullifierEntry: nullifierPda,
+            verificationKey: withdrawVk,
+            verifierProgram: verifierProgram.programId,
+            payer: user.publicKey,
+            poolAuthority: poolAuthority,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([user])
+          .rpc();
+
+        throw new Error("SECURITY FAILURE: Withdraw paid a token account the recipient does not own");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidRecipient");
+        console.log("✓ SECURITY: Mismatched recipient token account owner rejected");
+      }
+    });
+
     it("EDGE CASE: should handle unique nullifiers", async function () {
       this.timeout(30000);
 
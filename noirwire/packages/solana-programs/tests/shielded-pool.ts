@@ -13,7 +13,6 @@ describe("Shielded Pool Program", function () {
   const program = anchor.workspace.ShieldedPool as Program<ShieldedPool>;
   const provider = anchor.getProvider();
 
-  const VK_HASH = Array.from(Buffer.alloc(32, "vk_hash_test"));
 
   // PER (Private Ephemeral Rollup) authority for batch settlements
   // In tests, we use a deterministic keypair for simplicity
@@ -87,7 +86,13 @@ describe("Shielded Pool Program", function () {
       const poolVault = findVaultPDA(poolState);
 
       await program.methods
-        .initialize(tokenMint, VK_HASH, PER_AUTHORITY)
+        .initialize(
+          tokenMint,
+          PER_AUTHORITY,
+          false,
+          new anchor.BN(1_000),
+          new anchor.BN("18446744073709551615"),
+        )
         .accounts({
           pool: poolState,
           tokenMint: tokenMint,
@@ -136,7 +141,13 @@ describe("Shielded Pool Program", function () {
 
       // First initialization
       await program.methods
-        .initialize(tokenMint, VK_HASH, PER_AUTHORITY)
+        .initialize(
+          tokenMint,
+          PER_AUTHORITY,
+          false,
+          new anchor.BN(1_000),
+          new anchor.BN("18446744073709551615"),
+        )
         .accounts({
           pool: poolState,
           tokenMint: tokenMint,
@@ -153,7 +164,13 @@ describe("Shielded Pool Program", function () {
       // Attempt second initialization with same pool
       try {
         await program.methods
-          .initialize(tokenMint, Array.from(Buffer.alloc(32, 1)), PER_AUTHORITY)
+          .initialize(
+            tokenMint,
+            PER_AUTHORITY,
+            false,
+            new anchor.BN(1_000),
+            new anchor.BN("18446744073709551615"),
+          )
           .accounts({
             pool: poolState,
             tokenMint: tokenMint,
@@ -199,7 +216,13 @@ describe("Shielded Pool Program", function () {
       const vault = findVaultPDA(pool);
 
       await program.methods
-        .initialize(tokenMint, VK_HASH, PER_AUTHORITY)
+        .initialize(
+          tokenMint,
+          PER_AUTHORITY,
+          false,
+          new anchor.BN(1_000),
+          new anchor.BN("18446744073709551615"),
+        )
         .accounts({
           pool: pool,
           tokenMint: tokenMint,
@@ -250,7 +273,13 @@ describe("Shielded Pool Program", function () {
       const vault = findVaultPDA(pool);
 
       await program.methods
-        .initialize(tokenMint, VK_HASH, PER_AUTHORITY)
+        .initialize(
+          tokenMint,
+          PER_AUTHORITY,
+          false,
+          new anchor.BN(1_000),
+          new anchor.BN("18446744073709551615"),
+        )
         .accounts({
           pool: pool,
           tokenMint: tokenMint,
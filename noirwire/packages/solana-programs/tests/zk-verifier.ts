@@ -5,6 +5,7 @@ import { ShieldedPool } from "../target/types/shielded_pool";
 import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
 import { TOKEN_PROGRAM_ID, createMint } from "@solana/spl-token";
 import { expect } from "chai";
+import { keccak_256 } from "js-sha3";
 
 describe("ZK Verifier Program", function () {
   this.timeout(120000); // 2 minute timeout
@@ -47,15 +48,37 @@ describe("ZK Verifier Program", function () {
     c: Array.from(Buffer.alloc(64, 3)),
   };
 
+  // Verification keys are versioned; these tests only deploy version 1
+  const VK_VERSION = 1;
+  const VK_VERSION_SEED = Buffer.from([1, 0, 0, 0]);
+
   // Helper function to find verification key PDA
   function findVkPDA(poolKey: PublicKey, circuitId: Buffer): PublicKey {
     const [pda] = PublicKey.findProgramAddressSync(
-      [Buffer.from("vk"), poolKey.toBuffer(), circuitId],
+      [Buffer.from("vk"), poolKey.toBuffer(), circuitId, VK_VERSION_SEED],
       program.programId,
     );
     return pda;
   }
 
+  // Helper to register a uniquely named circuit in the pool's registry
+  // Returns its circuit ID: keccak256("noirwire.{name}.v1")
+  async function registerCircuit(poolKey: PublicKey, authority: Keypair): Promise<Buffer> {
+    testCounter++;
+    const name = `circuit_${testCounter}_${Math.random().toString(36).slice(2, 8)}`;
+
+    await program.methods
+      .registerCircuit(name, "v1", null)
+      .accountsPartial({
+        pool: poolKey,
+        authority: authority.publicKey,
+      })
+      .signers([authority])
+      .rpc();
+
+    return Buffer.from(keccak_256(`noirwire.${name}.v1`), "hex");
+  }
+
   // Helper to generate keypair
   function generateKeypair(): Keypair {
     return Keypair.generate();
@@ -102,11 +125,16 @@ describe("ZK Verifier Program", function () {
     );
 
     // Initialize pool
-    const vkHash = Array.from(Buffer.alloc(32, "test_vk"));
     const perAuthority = Keypair.generate().publicKey;
 
     await shieldedPoolProgram.methods
-      .initialize(tokenMint, vkHash, perAuthority)
+      .initialize(
+        tokenMint,
+        perAuthority,
+        false,
+        new anchor.BN(1_000),
+        new anchor.BN("18446744073709551615"),
+      )
       .accountsPartial({
         tokenMint: tokenMint,
         poolVault: poolVault,
@@ -119,6 +147,18 @@ describe("ZK Verifier Program", function () {
       .signers([authority])
       .rpc();
 
+    // Verification keys can only be stored for registered circuits
+    await program.methods
+      .initCircuitRegistry()
+      .accountsPartial({
+        pool: poolState,
+        authority: authority.publicKey,
+        payer: authority.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([authority])
+      .rpc();
+
     return poolState;
   }
 
@@ -132,15 +172,16 @@ describe("ZK Verifier Program", function () {
       await airdropSol(poolAuthority.publicKey);
       const poolAccount = await createPoolForTesting(poolAuthority);
 
-      const circuitId = generateUniqueCircuitId();
+      const circuitId = await registerCircuit(poolAccount, poolAuthority);
       const verificationKey = findVkPDA(poolAccount, circuitId);
       const mockVkData = createMockVkData();
 
       await program.methods
-        .storeVk(Array.from(circuitId), mockVkData)
+        .storeVk(Array.from(circuitId), VK_VERSION, mockVkData)
         .accountsPartial({
           pool: poolAccount,
           authority: poolAuthority.publicKey,
+          payer: poolAuthority.publicKey,
           systemProgram: SystemProgram.programId,
         })
         .signers([poolAuthority])
@@ -154,22 +195,51 @@ describe("ZK Verifier Program", function () {
       console.log("✓ Verification key stored successfully");
     });
 
-    it("should reject double storage of same circuit key", async function () {
+    it("should reject verification keys for unregistered circuits", async function () {
       this.timeout(30000);
 
       const poolAuthority = generateKeypair();
       await airdropSol(poolAuthority.publicKey);
       const poolAccount = await createPoolForTesting(poolAuthority);
       const circuitId = generateUniqueCircuitId();
+
+      try {
+        await program.methods
+          .storeVk(Array.from(circuitId), VK_VERSION, createMockVkData())
+          .accountsPartial({
+            pool: poolAccount,
+            authority: poolAuthority.publicKey,
+            payer: poolAuthority.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([poolAuthority])
+          .rpc();
+
+        expect.fail("Should have rejected unregistered circuit");
+      } catch (err: any) {
+        const errMsg = err.message || err.toString();
+        expect(errMsg).to.include("UnregisteredCircuit");
+        console.log("✓ Rejected verification key for unregistered circuit");
+      }
+    });
+
+    it("should reject double storage of same circuit key", async function () {
+      this.timeout(30000);
+
+      const poolAuthority = generateKeypair();
+      await airdropSol(poolAuthority.publicKey);
+      const poolAccount = await createPoolForTesting(poolAuthority);
+      const circuitId = await registerCircuit(poolAccount, poolAuthority);
       const verificationKey = findVkPDA(poolAccount, circuitId);
       const mockVkData = createMockVkData();
 
       // First storage
       await program.methods
-        .storeVk(Array.from(circuitId), mockVkData)
+        .storeVk(Array.from(circuitId), VK_VERSION, mockVkData)
         .accountsPartial({
           pool: poolAccount,
           authority: poolAuthority.publicKey,
+          payer: poolAuthority.publicKey,
           systemProgram: SystemProgram.programId,
         })
         .signers([poolAuthority])
@@ -178,10 +248,11 @@ describe("ZK Verifier Program", function () {
       // Attempt second storage to same PDA
       try {
         await program.methods
-          .storeVk(Array.from(circuitId), mockVkData)
+          .storeVk(Array.from(circuitId), VK_VERSION, mockVkData)
           .accountsPartial({
             pool: poolAccount,
             authority: poolAuthority.publicKey,
+            payer: poolAuthority.publicKey,
             systemProgram: SystemProgram.programId,
           })
           .signers([poolAuthority])
@@ -205,16 +276,17 @@ describe("ZK Verifier Program", function () {
       const poolAuthority = generateKeypair();
       await airdropSol(poolAuthority.publicKey);
       const poolAccount = await createPoolForTesting(poolAuthority);
-      const circuitId = generateUniqueCircuitId();
+      const circuitId = await registerCircuit(poolAccount, poolAuthority);
       const verificationKey = findVkPDA(poolAccount, circuitId);
       const mockVkData = createMockVkData();
 
       // Store a verification key first
       await program.methods
-        .storeVk(Array.from(circuitId), mockVkData)
+        .storeVk(Array.from(circuitId), VK_VERSION, mockVkData)
         .accountsPartial({
           pool: poolAccount,
           authority: poolAuthority.publicKey,
+          payer: poolAuthority.publicKey,
           systemProgram: SystemProgram.programId,
         })
         .signers([poolAuthority])
@@ -269,6 +341,74 @@ describe("ZK Verifier Program", function () {
     });
   });
 
+  // ===== REVOCATION TESTS =====
+
+  describe("Verification Key Revocation", () => {
+    it("should reject verification with a revoked key and delay closing it", async function () {
+      this.timeout(30000);
+
+      const poolAuthority = generateKeypair();
+      await airdropSol(poolAuthority.publicKey);
+      const poolAccount = await createPoolForTesting(poolAuthority);
+      const circuitId = await registerCircuit(poolAccount, poolAuthority);
+      const verificationKey = findVkPDA(poolAccount, circuitId);
+
+      await program.methods
+        .storeVk(Array.from(circuitId), VK_VERSION, createMockVkData())
+        .accountsPartial({
+          pool: poolAccount,
+          authority: poolAuthority.publicKey,
+          payer: poolAuthority.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([poolAuthority])
+        .rpc();
+
+      await program.methods
+        .revokeVk()
+        .accountsPartial({
+          verificationKey,
+          pool: poolAccount,
+          authority: poolAuthority.publicKey,
+        })
+        .signers([poolAuthority])
+        .rpc();
+
+      const vk = await program.account.verificationKey.fetch(verificationKey);
+      expect(vk.revokedSlot.toNumber()).to.be.greaterThan(0);
+
+      try {
+        await program.methods
+          .verify(mockProof, [Array.from(Buffer.alloc(32, 10))])
+          .accountsPartial({ verificationKey })
+          .rpc();
+        expect.fail("Should have rejected revoked key");
+      } catch (err: any) {
+        const errMsg = err.message || err.toString();
+        expect(errMsg).to.include("VerificationKeyRevoked");
+      }
+
+      try {
+        await program.methods
+          .closeVk()
+          .accountsPartial({
+            verificationKey,
+            pool: poolAccount,
+            authority: poolAuthority.publicKey,
+            rentRecipient: poolAuthority.publicKey,
+          })
+          .signers([poolAuthority])
+          .rpc();
+        expect.fail("Should have rejected close before the delay");
+      } catch (err: any) {
+        const errMsg = err.message || err.toString();
+        expect(errMsg).to.include("VkCloseDelayNotElapsed");
+      }
+
+      console.log("✓ Revoked key rejected by verify; close waits for the delay");
+    });
+  });
+
   // ===== PDA VERIFICATION TESTS =====
 
   describe("PDA Derivation and Validation", () => {